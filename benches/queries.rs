@@ -0,0 +1,73 @@
+//! Timings of the query hot paths on a large seeded repository.
+//!
+//! Run with `cargo bench --bench queries [-- <name filter>]`. Each case runs
+//! for about two seconds after a warm-up, so the suite takes well under a
+//! minute; the printed figure is the mean time per call.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ski::test_support::{seed_large_repo, temp_repo};
+use ski::IssueFilter;
+
+/// How long each case is repeated for
+const CASE_TIME: Duration = Duration::from_secs(2);
+
+fn bench<T>(filter: Option<&str>, name: &str, mut run: impl FnMut() -> T) {
+    if filter.is_some_and(|f| !name.contains(f)) {
+        return;
+    }
+    black_box(run());
+    let start = Instant::now();
+    let mut runs = 0;
+    while start.elapsed() < CASE_TIME {
+        black_box(run());
+        runs += 1;
+    }
+    println!(
+        "{:<28} {:>12.3?} per call ({} runs)",
+        name,
+        start.elapsed() / runs,
+        runs
+    );
+}
+
+fn main() -> ski::Result<()> {
+    // `cargo bench` passes `--bench`; anything else narrows the cases run
+    let filter = std::env::args().skip(1).find(|a| !a.starts_with("--"));
+    let filter = filter.as_deref();
+
+    let (db, _dir) = temp_repo()?;
+    let start = Instant::now();
+    seed_large_repo(&db)?;
+    println!("seeded in {:.2?}", start.elapsed());
+
+    let conn = db.conn();
+    let one_label = IssueFilter {
+        labels: vec!["label-03".to_string()],
+        ..Default::default()
+    };
+    // Issue n carries labels n % 30 and 7n % 30, so these two share issues
+    let two_labels = IssueFilter {
+        labels: vec!["label-03".to_string(), "label-21".to_string()],
+        ..Default::default()
+    };
+
+    bench(filter, "list_issues default", || {
+        ski::db::list_issues(conn, &IssueFilter::default())
+    });
+    bench(filter, "list_issues label", || {
+        ski::db::list_issues(conn, &one_label)
+    });
+    bench(filter, "list_issues two labels", || {
+        ski::db::list_issues(conn, &two_labels)
+    });
+    bench(filter, "list_issues_with_labels", || {
+        ski::db::list_issues_with_labels(conn, &IssueFilter::default())
+    });
+    bench(filter, "search_issues", || {
+        ski::db::search_issues(conn, "crash", &IssueFilter::default())
+    });
+    bench(filter, "get_comments", || ski::db::get_comments(conn, 42));
+    Ok(())
+}
@@ -1,12 +1,21 @@
 use serde::{Deserialize, Serialize};
 use ski::{
-    Comment, Issue, IssueCreate, IssueFilter, IssueState, IssueType, IssueUpdate, Label,
-    LinkedIssueRef, SkisDb, SortField, SortOrder, StateReason,
+    Comment, CreateOpts, Cursor, Issue, IssueActivity, IssueCreate, IssueFilter, IssueType,
+    IssueUpdate, Label, LinkType, LinkedIssueRef, SearchScope, SkisDb, SortField, SortOrder,
+    StateFilter, StateReason,
 };
-use std::path::PathBuf;
+use ski::config::ListConfig;
+use ski::export::{export_all, ExportData};
+use ski::models::{adjust_for_theme, validate_color, Theme};
+use ski::output::{format_issue_reference, ReferenceFormat};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder};
-use tauri::webview::WebviewWindowBuilder;
+use tauri::webview::{WebviewWindow, WebviewWindowBuilder};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tracing::{debug, error, info, warn};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
@@ -77,6 +86,7 @@ pub struct AppState {
     db: Mutex<Option<SkisDb>>,
     current_dir: Mutex<Option<PathBuf>>,
     recent_paths: Mutex<Vec<String>>,
+    launch_request: Mutex<Option<LaunchRequest>>,
 }
 
 impl Default for AppState {
@@ -85,6 +95,36 @@ impl Default for AppState {
             db: Mutex::new(None),
             current_dir: Mutex::new(None),
             recent_paths: Mutex::new(Vec::new()),
+            launch_request: Mutex::new(LaunchRequest::from_args(std::env::args().skip(1))),
+        }
+    }
+}
+
+/// Repository and issue requested on the command line (`--repo <path> --issue <n>`),
+/// as passed by `skis issue browse`
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchRequest {
+    pub repo: Option<String>,
+    pub issue: Option<i64>,
+}
+
+impl LaunchRequest {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Option<Self> {
+        let mut request = LaunchRequest {
+            repo: None,
+            issue: None,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--repo" => request.repo = args.next(),
+                "--issue" => request.issue = args.next().and_then(|n| n.parse().ok()),
+                _ => {}
+            }
+        }
+        if request.repo.is_none() && request.issue.is_none() {
+            None
+        } else {
+            Some(request)
         }
     }
 }
@@ -97,14 +137,24 @@ pub struct Response<T: Serialize> {
     pub data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Machine-readable error code so the frontend can point at the bad field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
 }
 
+/// Error code for a label color that fails `validate_color`
+const INVALID_COLOR: &str = "invalid_color";
+
+/// Error code for a repository written by a newer SKIS
+const SCHEMA_TOO_NEW: &str = "schema_too_new";
+
 impl<T: Serialize> Response<T> {
     pub fn ok(data: T) -> Self {
         Self {
             ok: true,
             data: Some(data),
             error: None,
+            code: None,
         }
     }
 
@@ -113,6 +163,14 @@ impl<T: Serialize> Response<T> {
             ok: false,
             data: None,
             error: Some(msg.into()),
+            code: None,
+        }
+    }
+
+    pub fn err_code(code: &'static str, msg: impl Into<String>) -> Response<T> {
+        Response {
+            code: Some(code),
+            ..Response::err(msg)
         }
     }
 }
@@ -128,13 +186,89 @@ macro_rules! with_db {
     }};
 }
 
-// Extended issue view with labels and links
+// ============ Command Timing ============
+
+/// Commands slower than this are logged as warnings
+const SLOW_COMMAND_MS: u64 = 250;
+
+/// Number of command timings kept for `get_recent_command_stats`
+const COMMAND_STATS_CAPACITY: usize = 100;
+
+/// Timings of the most recent commands, oldest first
+static COMMAND_STATS: Mutex<VecDeque<CommandStat>> = Mutex::new(VecDeque::new());
+
+/// How long one command took, for the diagnostics panel
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandStat {
+    pub command: &'static str,
+    pub elapsed_ms: u64,
+    pub ok: bool,
+    /// Unix time in milliseconds when the command finished
+    pub finished_at: u64,
+}
+
+/// Run a command body inside a tracing span, logging its duration and outcome
+/// (never its arguments or result) and recording it in `COMMAND_STATS`
+fn timed<T: Serialize>(command: &'static str, body: impl FnOnce() -> Response<T>) -> Response<T> {
+    let span = tracing::info_span!("command", name = command);
+    let _enter = span.enter();
+
+    let start = Instant::now();
+    let response = body();
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    if elapsed_ms > SLOW_COMMAND_MS {
+        warn!(command, elapsed_ms, ok = response.ok, "Slow command");
+    } else {
+        debug!(command, elapsed_ms, ok = response.ok, "Command finished");
+    }
+    record_command_stat(CommandStat {
+        command,
+        elapsed_ms,
+        ok: response.ok,
+        finished_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    });
+    response
+}
+
+fn record_command_stat(stat: CommandStat) {
+    let mut stats = COMMAND_STATS.lock().unwrap_or_else(|e| e.into_inner());
+    if stats.len() == COMMAND_STATS_CAPACITY {
+        stats.pop_front();
+    }
+    stats.push_back(stat);
+}
+
+// Extended issue view with labels (carried on `issue`) and links
 #[derive(Debug, Serialize)]
 pub struct IssueView {
     #[serde(flatten)]
     pub issue: Issue,
-    pub labels: Vec<Label>,
     pub linked_issues: Vec<LinkedIssueRef>,
+    /// Comment count and last activity, populated by list_issues
+    #[serde(flatten)]
+    pub activity: Option<IssueActivity>,
+}
+
+impl IssueView {
+    /// Enrich an issue with its labels (unless already loaded) and links
+    fn load(db: &SkisDb, mut issue: Issue, activity: Option<IssueActivity>) -> Self {
+        if issue.labels.is_none() {
+            let _ = ski::db::attach_labels(db.conn(), std::slice::from_mut(&mut issue));
+        }
+        // The frontend always expects a labels array
+        issue.labels.get_or_insert_with(Vec::new);
+        let linked_issues =
+            ski::db::get_linked_issues_with_titles(db.conn(), issue.id).unwrap_or_default();
+        Self {
+            issue,
+            linked_issues,
+            activity,
+        }
+    }
 }
 
 // Filter parameters from frontend
@@ -143,25 +277,33 @@ pub struct FilterParams {
     pub state: Option<String>,
     pub issue_type: Option<String>,
     pub labels: Option<Vec<String>>,
+    /// Only issues with none of these labels
+    pub exclude_labels: Option<Vec<String>>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub include_deleted: Option<bool>,
+    pub include_archived: Option<bool>,
+    /// Only issues with (`true`) or without (`false`) comments
+    pub has_comments: Option<bool>,
     pub search: Option<String>,
+    pub search_in: Option<String>,
+    /// Opaque cursor from a previous page's `next_cursor`
+    pub cursor: Option<String>,
 }
 
 impl FilterParams {
-    fn to_filter(&self) -> IssueFilter {
-        let mut filter = IssueFilter::default();
-
-        if let Some(state) = &self.state {
-            filter.state = match state.to_lowercase().as_str() {
-                "open" => Some(IssueState::Open),
-                "closed" => Some(IssueState::Closed),
-                _ => None,
-            };
-        }
+    /// Without a `state`, the states listed come from `list`, as in the CLI
+    fn to_filter(&self, list: &ListConfig) -> IssueFilter {
+        // An unknown state lists everything rather than failing the request
+        let mut filter = IssueFilter {
+            state: list
+                .resolve_state(self.state.as_deref())
+                .unwrap_or(StateFilter::All)
+                .state(),
+            ..Default::default()
+        };
 
         if let Some(issue_type) = &self.issue_type {
             filter.issue_type = issue_type.parse().ok();
@@ -171,15 +313,31 @@ impl FilterParams {
             filter.labels = labels.clone();
         }
 
+        if let Some(exclude_labels) = &self.exclude_labels {
+            filter.exclude_labels = exclude_labels.clone();
+        }
+
         if let Some(sort_by) = &self.sort_by {
             filter.sort_by = match sort_by.to_lowercase().as_str() {
                 "created" => SortField::Created,
                 "updated" => SortField::Updated,
+                "closed" => SortField::Closed,
+                "activity" => SortField::Activity,
+                "comments" => SortField::Comments,
                 "id" => SortField::Id,
+                "title" => SortField::Title,
                 _ => SortField::Updated,
             };
         }
 
+        if let Some(search_in) = &self.search_in {
+            filter.search_scope = match search_in.to_lowercase().as_str() {
+                "title" => SearchScope::Title,
+                "body" => SearchScope::Body,
+                _ => SearchScope::All,
+            };
+        }
+
         if let Some(sort_order) = &self.sort_order {
             filter.sort_order = match sort_order.to_lowercase().as_str() {
                 "asc" => SortOrder::Asc,
@@ -200,6 +358,12 @@ impl FilterParams {
             filter.include_deleted = include_deleted;
         }
 
+        if let Some(include_archived) = self.include_archived {
+            filter.include_archived = include_archived;
+        }
+
+        filter.has_comments = self.has_comments;
+
         filter
     }
 }
@@ -211,6 +375,7 @@ pub struct CreateIssueParams {
     pub body: Option<String>,
     pub issue_type: Option<String>,
     pub labels: Option<Vec<String>>,
+    pub points: Option<u32>,
 }
 
 // Issue update parameters from frontend
@@ -219,6 +384,12 @@ pub struct UpdateIssueParams {
     pub title: Option<String>,
     pub body: Option<String>,
     pub issue_type: Option<String>,
+    pub points: Option<u32>,
+    /// Remove the estimate (takes precedence over `points`)
+    pub clear_points: Option<bool>,
+    /// The issue's `version` when the frontend loaded it; the save fails
+    /// with a conflict if someone else has changed the issue since
+    pub version: Option<i64>,
 }
 
 // Directory state response
@@ -226,103 +397,217 @@ pub struct UpdateIssueParams {
 pub struct DirectoryState {
     pub path: Option<String>,
     pub initialized: bool,
+    /// Schema version of the open database, for the info panel
+    pub schema_version: Option<i32>,
 }
 
 // ============ Directory Commands ============
 
 #[tauri::command]
 fn get_current_dir(state: State<AppState>) -> Response<DirectoryState> {
-    let dir_guard = state.current_dir.lock().unwrap();
-    let db_guard = state.db.lock().unwrap();
+    timed("get_current_dir", || {
+        let dir_guard = state.current_dir.lock().unwrap();
+        let db_guard = state.db.lock().unwrap();
+
+        Response::ok(DirectoryState {
+            path: dir_guard.as_ref().map(|p| p.display().to_string()),
+            initialized: db_guard.is_some(),
+            schema_version: db_guard.as_ref().and_then(|db| db.schema_version().ok()),
+        })
+    })
+}
 
-    Response::ok(DirectoryState {
-        path: dir_guard.as_ref().map(|p| p.display().to_string()),
-        initialized: db_guard.is_some(),
+#[tauri::command]
+fn select_directory(
+    window: WebviewWindow,
+    state: State<AppState>,
+    path: String,
+) -> Response<DirectoryState> {
+    timed("select_directory", || {
+        info!(path = %path, "Selecting directory");
+        let dir_path = ski::util::expand_path(&path);
+        let skis_dir = dir_path.join(".skis");
+
+        debug!(skis_dir = %skis_dir.display(), "Looking for .skis directory");
+
+        // Try to open existing SKIS repository
+        match SkisDb::open_at(&skis_dir) {
+            Ok(db) => {
+                info!(path = %path, "Opened existing SKIS repository");
+                let schema_version = db.schema_version().ok();
+                {
+                    let mut db_guard = state.db.lock().unwrap();
+                    let mut dir_guard = state.current_dir.lock().unwrap();
+                    *db_guard = Some(db);
+                    *dir_guard = Some(dir_path.clone());
+                }
+                set_window_repo(window.app_handle(), window.label(), Some(&dir_path));
+                Response::ok(DirectoryState {
+                    path: Some(path),
+                    initialized: true,
+                    schema_version,
+                })
+            }
+            Err(ski::Error::SchemaTooNew(found, supported)) => {
+                warn!(path = %path, found, supported, "Repository schema is newer than supported");
+                Response::err_code(
+                    SCHEMA_TOO_NEW,
+                    format!(
+                        "This repository uses database schema version {}, but this app only supports up to version {}. Update the SKIS app to open it.",
+                        found, supported
+                    ),
+                )
+            }
+            Err(e) => {
+                debug!(path = %path, error = %e, "Directory not initialized");
+                // Not initialized - store directory but no db
+                {
+                    let mut dir_guard = state.current_dir.lock().unwrap();
+                    let mut db_guard = state.db.lock().unwrap();
+                    *dir_guard = Some(dir_path);
+                    *db_guard = None;
+                }
+                set_window_repo(window.app_handle(), window.label(), None);
+                Response::ok(DirectoryState {
+                    path: Some(path),
+                    initialized: false,
+                    schema_version: None,
+                })
+            }
+        }
     })
 }
 
+/// Initialize a SKIS repository. With an explicit `path` the directory is created if
+/// needed and becomes the current directory together with the new database; without
+/// one the previously selected directory is used.
 #[tauri::command]
-fn select_directory(state: State<AppState>, path: String) -> Response<DirectoryState> {
-    info!(path = %path, "Selecting directory");
-    let dir_path = PathBuf::from(&path);
-    let skis_dir = dir_path.join(".skis");
+fn init_repository(
+    window: WebviewWindow,
+    state: State<AppState>,
+    path: Option<String>,
+) -> Response<DirectoryState> {
+    timed("init_repository", || {
+        let dir_path = match path {
+            Some(p) => {
+                let dir_path = ski::util::expand_path(&p);
+                if let Err(e) = std::fs::create_dir_all(&dir_path) {
+                    error!(path = %dir_path.display(), error = %e, "Failed to create directory");
+                    return Response::err(e.to_string());
+                }
+                dir_path
+            }
+            None => {
+                let dir_guard = state.current_dir.lock().unwrap();
+                match dir_guard.as_ref() {
+                    Some(p) => p.clone(),
+                    None => {
+                        warn!("init_repository called with no directory selected");
+                        return Response::err("No directory selected");
+                    }
+                }
+            }
+        };
 
-    debug!(skis_dir = %skis_dir.display(), "Looking for .skis directory");
+        info!(path = %dir_path.display(), "Initializing new SKIS repository");
 
-    // Try to open existing SKIS repository
-    match SkisDb::open_at(&skis_dir) {
-        Ok(db) => {
-            info!(path = %path, "Opened existing SKIS repository");
-            let mut db_guard = state.db.lock().unwrap();
-            let mut dir_guard = state.current_dir.lock().unwrap();
-            *db_guard = Some(db);
-            *dir_guard = Some(dir_path);
-            Response::ok(DirectoryState {
-                path: Some(path),
-                initialized: true,
-            })
+        match SkisDb::init(&dir_path) {
+            Ok(db) => {
+                info!(path = %dir_path.display(), "Successfully initialized SKIS repository");
+                let schema_version = db.schema_version().ok();
+                {
+                    let mut db_guard = state.db.lock().unwrap();
+                    let mut dir_guard = state.current_dir.lock().unwrap();
+                    *db_guard = Some(db);
+                    *dir_guard = Some(dir_path.clone());
+                }
+                set_window_repo(window.app_handle(), window.label(), Some(&dir_path));
+                Response::ok(DirectoryState {
+                    path: Some(dir_path.display().to_string()),
+                    initialized: true,
+                    schema_version,
+                })
+            }
+            Err(e) => {
+                error!(path = %dir_path.display(), error = %e, "Failed to initialize repository");
+                Response::err(e.to_string())
+            }
         }
-        Err(e) => {
-            debug!(path = %path, error = %e, "Directory not initialized");
-            // Not initialized - store directory but no db
-            let mut dir_guard = state.current_dir.lock().unwrap();
+    })
+}
+
+/// Close the open repository: drop the database handle, forget the directory and
+/// emit `repository-closed` so every window can reset its view
+#[tauri::command]
+fn close_repository(window: WebviewWindow, state: State<AppState>) -> Response<DirectoryState> {
+    timed("close_repository", || {
+        {
             let mut db_guard = state.db.lock().unwrap();
-            *dir_guard = Some(dir_path);
+            let mut dir_guard = state.current_dir.lock().unwrap();
+            if let Some(dir) = dir_guard.as_ref() {
+                info!(path = %dir.display(), "Closing SKIS repository");
+            }
             *db_guard = None;
-            Response::ok(DirectoryState {
-                path: Some(path),
-                initialized: false,
-            })
+            *dir_guard = None;
         }
-    }
+        set_window_repo(window.app_handle(), window.label(), None);
+        let _ = window.app_handle().emit("repository-closed", ());
+        Response::ok(DirectoryState {
+            path: None,
+            initialized: false,
+            schema_version: None,
+        })
+    })
 }
 
-#[tauri::command]
-fn init_repository(state: State<AppState>) -> Response<DirectoryState> {
-    let dir_guard = state.current_dir.lock().unwrap();
-    let dir_path = match dir_guard.as_ref() {
-        Some(p) => p.clone(),
-        None => {
-            warn!("init_repository called with no directory selected");
-            return Response::err("No directory selected");
-        }
+/// Title a window after its open repository ("SKIS — name"), or plain "SKIS" when
+/// none is open, and refresh the Window menu to match
+fn set_window_repo(app: &AppHandle, window_label: &str, path: Option<&Path>) {
+    let Some(window) = app.get_webview_window(window_label) else {
+        return;
     };
-    drop(dir_guard);
 
-    info!(path = %dir_path.display(), "Initializing new SKIS repository");
-
-    match SkisDb::init(&dir_path) {
-        Ok(db) => {
-            info!(path = %dir_path.display(), "Successfully initialized SKIS repository");
-            let mut db_guard = state.db.lock().unwrap();
-            *db_guard = Some(db);
-            Response::ok(DirectoryState {
-                path: Some(dir_path.display().to_string()),
-                initialized: true,
-            })
-        }
-        Err(e) => {
-            error!(path = %dir_path.display(), error = %e, "Failed to initialize repository");
-            Response::err(e.to_string())
-        }
+    let title = match path.and_then(|p| p.file_name()) {
+        Some(name) => format!("SKIS — {}", name.to_string_lossy()),
+        None => "SKIS".to_string(),
+    };
+    if let Err(e) = window.set_title(&title) {
+        warn!(window = %window_label, error = %e, "Failed to set window title");
+    }
+    if let Err(e) = rebuild_menu_from_state(app) {
+        warn!(error = %e, "Failed to rebuild menu");
     }
 }
 
 #[tauri::command]
 fn get_home_dir() -> Response<String> {
-    match dirs::home_dir() {
+    timed("get_home_dir", || match dirs::home_dir() {
         Some(p) => Response::ok(p.display().to_string()),
         None => Response::err("Could not determine home directory"),
-    }
+    })
 }
 
 #[tauri::command]
 fn get_log_path() -> Response<String> {
-    let log_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("skis")
-        .join("logs");
-    Response::ok(log_dir.display().to_string())
+    timed("get_log_path", || {
+        let log_dir = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("skis")
+            .join("logs");
+        Response::ok(log_dir.display().to_string())
+    })
+}
+
+/// Take the command-line launch request, if any (only the first caller receives it)
+#[tauri::command]
+fn take_launch_request(state: State<AppState>) -> Response<Option<LaunchRequest>> {
+    timed("take_launch_request", || {
+        let request = state.launch_request.lock().unwrap().take();
+        if let Some(ref r) = request {
+            info!(repo = ?r.repo, issue = ?r.issue, "Handing launch request to frontend");
+        }
+        Response::ok(request)
+    })
 }
 
 /// Log a message from the frontend
@@ -337,119 +622,222 @@ fn log_frontend(level: String, message: String, context: Option<String>) {
     }
 }
 
+/// Timings of the last 100 commands, oldest first, for the diagnostics panel
+#[tauri::command]
+fn get_recent_command_stats() -> Response<Vec<CommandStat>> {
+    let stats = COMMAND_STATS.lock().unwrap_or_else(|e| e.into_inner());
+    Response::ok(stats.iter().cloned().collect())
+}
+
 // ============ Issue Commands ============
 
+// A page of issues for infinite scroll
+#[derive(Debug, Serialize)]
+pub struct IssueList {
+    pub issues: Vec<IssueView>,
+    /// Number of issues matching the filter across all pages
+    pub total: i64,
+    /// Issues already loaded before this page, as sent in `FilterParams.offset`
+    pub offset: usize,
+    pub limit: usize,
+    pub has_more: bool,
+    /// Pass back as `FilterParams.cursor` for the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
 #[tauri::command]
-fn list_issues(state: State<AppState>, filter: FilterParams) -> Response<Vec<IssueView>> {
-    with_db!(state, |db: &SkisDb| {
-        let issue_filter = filter.to_filter();
+fn list_issues(state: State<AppState>, filter: FilterParams) -> Response<IssueList> {
+    timed("list_issues", || {
+        with_db!(state, |db: &SkisDb| {
+            let config = match db.config() {
+                Ok(config) => config,
+                Err(e) => return Response::err(e.to_string()),
+            };
+            let issue_filter = filter.to_filter(&config.list);
+            let cursor = match filter.cursor.as_deref().map(Cursor::decode).transpose() {
+                Ok(c) => c,
+                Err(e) => return Response::err(e.to_string()),
+            };
 
-        let issues = if let Some(search) = &filter.search {
-            match ski::db::search_issues(db.conn(), search, &issue_filter) {
-                Ok(i) => i,
+            // Comment count and last activity come back from the listing query itself
+            let page = if let Some(search) = &filter.search {
+                ski::db::search_issues_page(db.conn(), search, &issue_filter, cursor.as_ref())
+            } else {
+                ski::db::list_issues_page(db.conn(), &issue_filter, cursor.as_ref())
+            };
+            let page = match page {
+                Ok(p) => p,
                 Err(e) => return Response::err(e.to_string()),
+            };
+
+            let total = if let Some(search) = &filter.search {
+                ski::db::count_search_results(db.conn(), search, &issue_filter)
+            } else {
+                ski::db::count_issues(db.conn(), &issue_filter)
+            };
+            let total = match total {
+                Ok(t) => t,
+                Err(e) => return Response::err(e.to_string()),
+            };
+
+            // Labels come back with the page; add links to each issue
+            let views: Vec<IssueView> = page
+                .issues
+                .into_iter()
+                .map(|(issue, activity)| IssueView::load(db, issue, Some(activity)))
+                .collect();
+
+            Response::ok(IssueList {
+                issues: views,
+                total,
+                offset: issue_filter.offset,
+                limit: issue_filter.limit,
+                has_more: page.next_cursor.is_some(),
+                next_cursor: page.next_cursor.map(|c| c.encode()),
+            })
+        })
+    })
+}
+
+#[tauri::command]
+fn get_issue(state: State<AppState>, id: i64) -> Response<IssueView> {
+    timed("get_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            match ski::db::get_issue(db.conn(), id) {
+                Ok(Some(issue)) => Response::ok(IssueView::load(db, issue, None)),
+                Ok(None) => Response::err(format!("Issue #{} not found", id)),
+                Err(e) => Response::err(e.to_string()),
             }
-        } else {
-            match ski::db::list_issues(db.conn(), &issue_filter) {
-                Ok(i) => i,
+        })
+    })
+}
+
+/// Format a one-line issue reference ("plain" or "markdown") for the clipboard
+#[tauri::command]
+fn copy_issue_reference(
+    state: State<AppState>,
+    id: i64,
+    format: Option<String>,
+) -> Response<String> {
+    timed("copy_issue_reference", || {
+        with_db!(state, |db: &SkisDb| {
+            let format: ReferenceFormat = match format.as_deref().map(str::parse).transpose() {
+                Ok(f) => f.unwrap_or_default(),
                 Err(e) => return Response::err(e.to_string()),
+            };
+            match ski::db::get_issue(db.conn(), id) {
+                Ok(Some(issue)) => Response::ok(format_issue_reference(&issue, format)),
+                Ok(None) => Response::err(format!("Issue #{} not found", id)),
+                Err(e) => Response::err(e.to_string()),
             }
-        };
+        })
+    })
+}
 
-        // Enrich each issue with labels and links
-        let mut views = Vec::with_capacity(issues.len());
-        for issue in issues {
-            let labels = ski::db::get_issue_labels(db.conn(), issue.id).unwrap_or_default();
-            let linked_issues =
-                ski::db::get_linked_issues_with_titles(db.conn(), issue.id).unwrap_or_default();
-            views.push(IssueView {
-                issue,
-                labels,
-                linked_issues,
-            });
-        }
+/// Repository settings the frontend applies itself
+#[derive(Debug, Serialize)]
+pub struct RepoConfig {
+    /// Holds the state filter to start from, so the GUI lists what `skis issue list` does
+    pub list: ListConfig,
+}
 
-        Response::ok(views)
+/// The open repository's `config.toml` settings the frontend needs
+#[tauri::command]
+fn get_repo_config(state: State<AppState>) -> Response<RepoConfig> {
+    timed("get_repo_config", || {
+        with_db!(state, |db: &SkisDb| {
+            match db.config() {
+                Ok(config) => Response::ok(RepoConfig { list: config.list }),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
+/// Starting body for a new issue of `issue_type`, from `.skis/templates/`
+/// or the built-in skeleton
 #[tauri::command]
-fn get_issue(state: State<AppState>, id: i64) -> Response<IssueView> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::get_issue(db.conn(), id) {
-            Ok(Some(issue)) => {
-                let labels = ski::db::get_issue_labels(db.conn(), id).unwrap_or_default();
-                let linked_issues =
-                    ski::db::get_linked_issues_with_titles(db.conn(), id).unwrap_or_default();
-                Response::ok(IssueView {
-                    issue,
-                    labels,
-                    linked_issues,
-                })
+fn get_body_template(state: State<AppState>, issue_type: String) -> Response<String> {
+    timed("get_body_template", || {
+        let issue_type: IssueType = match issue_type.parse() {
+            Ok(issue_type) => issue_type,
+            Err(e) => return Response::err(e.to_string()),
+        };
+        with_db!(state, |db: &SkisDb| {
+            match db.body_template(issue_type) {
+                Ok(template) => Response::ok(template),
+                Err(e) => Response::err(e.to_string()),
             }
-            Ok(None) => Response::err(format!("Issue #{} not found", id)),
-            Err(e) => Response::err(e.to_string()),
-        }
+        })
     })
 }
 
 #[tauri::command]
 fn create_issue(state: State<AppState>, params: CreateIssueParams) -> Response<IssueView> {
-    debug!(title = %params.title, "Creating new issue");
-    with_db!(state, |db: &SkisDb| {
-        let issue_type = params
-            .issue_type
-            .as_ref()
-            .and_then(|t| t.parse().ok())
-            .unwrap_or(IssueType::Task);
-
-        let create = IssueCreate {
-            title: params.title,
-            body: params.body,
-            issue_type,
-            labels: params.labels.unwrap_or_default(),
-        };
+    timed("create_issue", || {
+        debug!(title = %params.title, "Creating new issue");
+        with_db!(state, |db: &SkisDb| {
+            let issue_type = params
+                .issue_type
+                .as_ref()
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(IssueType::Task);
+
+            let create = IssueCreate {
+                title: params.title,
+                body: params.body,
+                issue_type,
+                labels: params.labels.unwrap_or_default(),
+                points: params.points,
+            };
+            let opts = match db.config() {
+                Ok(config) => CreateOpts {
+                    title_prefixes: config.title_prefix,
+                    body_format: config.body,
+                },
+                Err(e) => return Response::err(e.to_string()),
+            };
 
-        match ski::db::create_issue(db.conn(), &create) {
-            Ok(issue) => {
-                info!(id = issue.id, title = %issue.title, "Created issue");
-                let labels = ski::db::get_issue_labels(db.conn(), issue.id).unwrap_or_default();
-                Response::ok(IssueView {
-                    issue,
-                    labels,
-                    linked_issues: vec![],
-                })
-            }
-            Err(e) => {
-                error!(error = %e, "Failed to create issue");
-                Response::err(e.to_string())
+            match db.with_retry(|conn| ski::db::create_issue_with(conn, &create, &opts)) {
+                Ok(issue) => {
+                    info!(id = issue.id, title = %issue.title, "Created issue");
+                    Response::ok(IssueView::load(db, issue, None))
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to create issue");
+                    Response::err(e.to_string())
+                }
             }
-        }
+        })
     })
 }
 
 #[tauri::command]
 fn update_issue(state: State<AppState>, id: i64, params: UpdateIssueParams) -> Response<IssueView> {
-    with_db!(state, |db: &SkisDb| {
-        let update = IssueUpdate {
-            title: params.title,
-            body: params.body,
-            issue_type: params.issue_type.as_ref().and_then(|t| t.parse().ok()),
-        };
+    timed("update_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            let update = IssueUpdate {
+                title: params.title,
+                body: params.body,
+                issue_type: params.issue_type.as_ref().and_then(|t| t.parse().ok()),
+                points: if params.clear_points.unwrap_or(false) {
+                    Some(None)
+                } else {
+                    params.points.map(Some)
+                },
+                expected_version: params.version,
+            };
+            let body_format = match db.config() {
+                Ok(config) => config.body,
+                Err(e) => return Response::err(e.to_string()),
+            };
 
-        match ski::db::update_issue(db.conn(), id, &update) {
-            Ok(issue) => {
-                let labels = ski::db::get_issue_labels(db.conn(), id).unwrap_or_default();
-                let linked_issues =
-                    ski::db::get_linked_issues_with_titles(db.conn(), id).unwrap_or_default();
-                Response::ok(IssueView {
-                    issue,
-                    labels,
-                    linked_issues,
-                })
+            match db.with_retry(|conn| ski::db::update_issue_with(conn, id, &update, &body_format))
+            {
+                Ok(issue) => Response::ok(IssueView::load(db, issue, None)),
+                Err(e) => Response::err(e.to_string()),
             }
-            Err(e) => Response::err(e.to_string()),
-        }
+        })
     })
 }
 
@@ -460,82 +848,73 @@ fn close_issue(
     reason: Option<String>,
     comment: Option<String>,
 ) -> Response<IssueView> {
-    with_db!(state, |db: &SkisDb| {
-        let state_reason = reason
-            .as_ref()
-            .and_then(|r| match r.to_lowercase().as_str() {
-                "completed" => Some(StateReason::Completed),
-                "not_planned" => Some(StateReason::NotPlanned),
-                _ => None,
-            })
-            .unwrap_or(StateReason::Completed);
+    timed("close_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            let state_reason = reason
+                .as_ref()
+                .and_then(|r| match r.to_lowercase().as_str() {
+                    "completed" => Some(StateReason::Completed),
+                    "not_planned" => Some(StateReason::NotPlanned),
+                    _ => None,
+                })
+                .unwrap_or(StateReason::Completed);
 
-        let result = match comment {
-            Some(c) => ski::db::close_issue_with_comment(db.conn(), id, state_reason, Some(&c)),
-            None => ski::db::close_issue(db.conn(), id, state_reason),
-        };
+            let body_format = match db.config() {
+                Ok(config) => config.body,
+                Err(e) => return Response::err(e.to_string()),
+            };
 
-        match result {
-            Ok(issue) => {
-                let labels = ski::db::get_issue_labels(db.conn(), id).unwrap_or_default();
-                let linked_issues =
-                    ski::db::get_linked_issues_with_titles(db.conn(), id).unwrap_or_default();
-                Response::ok(IssueView {
-                    issue,
-                    labels,
-                    linked_issues,
-                })
+            let result = db.with_retry(|conn| {
+                ski::db::close_issue_with_comment(
+                    conn,
+                    id,
+                    state_reason,
+                    comment.as_deref(),
+                    &body_format,
+                )
+            });
+
+            match result {
+                Ok(issue) => Response::ok(IssueView::load(db, issue, None)),
+                Err(e) => Response::err(e.to_string()),
             }
-            Err(e) => Response::err(e.to_string()),
-        }
+        })
     })
 }
 
 #[tauri::command]
 fn reopen_issue(state: State<AppState>, id: i64) -> Response<IssueView> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::reopen_issue(db.conn(), id) {
-            Ok(issue) => {
-                let labels = ski::db::get_issue_labels(db.conn(), id).unwrap_or_default();
-                let linked_issues =
-                    ski::db::get_linked_issues_with_titles(db.conn(), id).unwrap_or_default();
-                Response::ok(IssueView {
-                    issue,
-                    labels,
-                    linked_issues,
-                })
+    timed("reopen_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            match db.with_retry(|conn| ski::db::reopen_issue(conn, id)) {
+                Ok(issue) => Response::ok(IssueView::load(db, issue, None)),
+                Err(e) => Response::err(e.to_string()),
             }
-            Err(e) => Response::err(e.to_string()),
-        }
+        })
     })
 }
 
 #[tauri::command]
 fn delete_issue(state: State<AppState>, id: i64) -> Response<()> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::delete_issue(db.conn(), id) {
-            Ok(()) => Response::ok(()),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("delete_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            match db.with_retry(|conn| ski::db::delete_issue(conn, id)) {
+                Ok(()) => Response::ok(()),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 #[tauri::command]
 fn restore_issue(state: State<AppState>, id: i64) -> Response<IssueView> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::restore_issue(db.conn(), id) {
-            Ok(issue) => {
-                let labels = ski::db::get_issue_labels(db.conn(), id).unwrap_or_default();
-                let linked_issues =
-                    ski::db::get_linked_issues_with_titles(db.conn(), id).unwrap_or_default();
-                Response::ok(IssueView {
-                    issue,
-                    labels,
-                    linked_issues,
-                })
+    timed("restore_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            match db.with_retry(|conn| ski::db::restore_issue(conn, id)) {
+                Ok(issue) => Response::ok(IssueView::load(db, issue, None)),
+                Err(e) => Response::err(e.to_string()),
             }
-            Err(e) => Response::err(e.to_string()),
-        }
+        })
     })
 }
 
@@ -543,41 +922,77 @@ fn restore_issue(state: State<AppState>, id: i64) -> Response<IssueView> {
 
 #[tauri::command]
 fn get_comments(state: State<AppState>, issue_id: i64) -> Response<Vec<Comment>> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::get_comments(db.conn(), issue_id) {
-            Ok(comments) => Response::ok(comments),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("get_comments", || {
+        with_db!(state, |db: &SkisDb| {
+            match ski::db::get_comments(db.conn(), issue_id) {
+                Ok(comments) => Response::ok(comments),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 #[tauri::command]
-fn add_comment(state: State<AppState>, issue_id: i64, body: String) -> Response<Comment> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::add_comment(db.conn(), issue_id, &body) {
-            Ok(comment) => Response::ok(comment),
-            Err(e) => Response::err(e.to_string()),
-        }
+fn add_comment(
+    state: State<AppState>,
+    issue_id: i64,
+    body: String,
+    allow_deleted: Option<bool>,
+) -> Response<Comment> {
+    timed("add_comment", || {
+        with_db!(state, |db: &SkisDb| {
+            let body_format = match db.config() {
+                Ok(config) => config.body,
+                Err(e) => return Response::err(e.to_string()),
+            };
+
+            let allow_deleted = allow_deleted.unwrap_or(false);
+            match db.with_retry(|conn| {
+                ski::db::add_comment_with(conn, issue_id, &body, &body_format, allow_deleted)
+            }) {
+                Ok(comment) => Response::ok(comment),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 #[tauri::command]
 fn update_comment(state: State<AppState>, comment_id: i64, body: String) -> Response<Comment> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::update_comment(db.conn(), comment_id, &body) {
-            Ok(comment) => Response::ok(comment),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("update_comment", || {
+        with_db!(state, |db: &SkisDb| {
+            let body_format = match db.config() {
+                Ok(config) => config.body,
+                Err(e) => return Response::err(e.to_string()),
+            };
+
+            match db.with_retry(|conn| {
+                ski::db::update_comment_with(conn, comment_id, &body, &body_format)
+            }) {
+                Ok(comment) => Response::ok(comment),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
+/// Delete a comment and return the issue's remaining comments
 #[tauri::command]
-fn delete_comment(state: State<AppState>, comment_id: i64) -> Response<()> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::delete_comment(db.conn(), comment_id) {
-            Ok(()) => Response::ok(()),
-            Err(e) => Response::err(e.to_string()),
-        }
+fn delete_comment(
+    state: State<AppState>,
+    issue_id: i64,
+    comment_id: i64,
+) -> Response<Vec<Comment>> {
+    timed("delete_comment", || {
+        with_db!(state, |db: &SkisDb| {
+            if let Err(e) = db.with_retry(|conn| ski::db::delete_comment(conn, comment_id)) {
+                return Response::err(e.to_string());
+            }
+            match ski::db::get_comments(db.conn(), issue_id) {
+                Ok(comments) => Response::ok(comments),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
@@ -585,11 +1000,63 @@ fn delete_comment(state: State<AppState>, comment_id: i64) -> Response<()> {
 
 #[tauri::command]
 fn list_labels(state: State<AppState>) -> Response<Vec<Label>> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::list_labels(db.conn()) {
-            Ok(labels) => Response::ok(labels),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("list_labels", || {
+        with_db!(state, |db: &SkisDb| {
+            match ski::db::list_labels(db.conn()) {
+                Ok(labels) => Response::ok(labels),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
+    })
+}
+
+// A label with its color adjusted for the current theme
+#[derive(Debug, Serialize)]
+pub struct PaletteLabel {
+    #[serde(flatten)]
+    pub label: Label,
+    /// Color to draw the chip with; `color` stays as stored
+    pub display_color: Option<String>,
+}
+
+/// All labels with colors adjusted for a "light" or "dark" theme
+#[tauri::command]
+fn get_label_palette(state: State<AppState>, theme: String) -> Response<Vec<PaletteLabel>> {
+    timed("get_label_palette", || {
+        let theme: Theme = match theme.parse() {
+            Ok(theme) => theme,
+            Err(e) => return Response::err(e.to_string()),
+        };
+        with_db!(state, |db: &SkisDb| {
+            match ski::db::list_labels(db.conn()) {
+                Ok(labels) => Response::ok(
+                    labels
+                        .into_iter()
+                        .map(|label| PaletteLabel {
+                            display_color: label
+                                .color
+                                .as_deref()
+                                .map(|c| adjust_for_theme(c, theme)),
+                            label,
+                        })
+                        .collect(),
+                ),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
+    })
+}
+
+/// Closest existing label name to `name`, for "did you mean" hints in tag input
+#[tauri::command]
+fn suggest_label(state: State<AppState>, name: String) -> Response<Option<String>> {
+    timed("suggest_label", || {
+        with_db!(state, |db: &SkisDb| {
+            match ski::db::suggest_label(db.conn(), &name) {
+                Ok(suggestion) => Response::ok(suggestion),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
@@ -600,36 +1067,44 @@ fn create_label(
     description: Option<String>,
     color: Option<String>,
 ) -> Response<Label> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::create_label(
-            db.conn(),
-            &name,
-            description.as_deref(),
-            color.as_deref(),
-        ) {
-            Ok(label) => Response::ok(label),
-            Err(e) => Response::err(e.to_string()),
+    timed("create_label", || {
+        if let Some(Err(e)) = color.as_deref().map(validate_color) {
+            return Response::err_code(INVALID_COLOR, e.to_string());
         }
+        with_db!(state, |db: &SkisDb| {
+            match db.with_retry(|conn| {
+                ski::db::create_label(conn, &name, description.as_deref(), color.as_deref())
+            }) {
+                Ok(label) => Response::ok(label),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 #[tauri::command]
 fn delete_label(state: State<AppState>, name: String) -> Response<()> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::delete_label(db.conn(), &name) {
-            Ok(()) => Response::ok(()),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("delete_label", || {
+        with_db!(state, |db: &SkisDb| {
+            match db.with_retry(|conn| ski::db::delete_label(conn, &name)) {
+                Ok(()) => Response::ok(()),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 #[tauri::command]
 fn add_label_to_issue(state: State<AppState>, issue_id: i64, label_name: String) -> Response<()> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::add_label_to_issue(db.conn(), issue_id, &label_name) {
-            Ok(()) => Response::ok(()),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("add_label_to_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            match db
+                .with_retry(|conn| ski::db::add_label_to_issue(conn, issue_id, &label_name, false))
+            {
+                Ok(()) => Response::ok(()),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
@@ -639,125 +1114,143 @@ fn remove_label_from_issue(
     issue_id: i64,
     label_name: String,
 ) -> Response<()> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::remove_label_from_issue(db.conn(), issue_id, &label_name) {
-            Ok(()) => Response::ok(()),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("remove_label_from_issue", || {
+        with_db!(state, |db: &SkisDb| {
+            match db.with_retry(|conn| {
+                ski::db::remove_label_from_issue(conn, issue_id, &label_name, false)
+            }) {
+                Ok(()) => Response::ok(()),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 // ============ Link Commands ============
 
 #[tauri::command]
-fn link_issues(state: State<AppState>, issue_a: i64, issue_b: i64) -> Response<()> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::add_link(db.conn(), issue_a, issue_b) {
-            Ok(()) => Response::ok(()),
-            Err(e) => Response::err(e.to_string()),
-        }
+fn link_issues(
+    state: State<AppState>,
+    issue_a: i64,
+    issue_b: i64,
+    link_type: Option<String>,
+    note: Option<String>,
+    allow_deleted: Option<bool>,
+) -> Response<()> {
+    timed("link_issues", || {
+        let link_type: LinkType = match link_type.as_deref().map(str::parse).transpose() {
+            Ok(t) => t.unwrap_or_default(),
+            Err(e) => return Response::err(e.to_string()),
+        };
+        with_db!(state, |db: &SkisDb| {
+            let allow_deleted = allow_deleted.unwrap_or(false);
+            match db.with_retry(|conn| {
+                ski::db::add_link_with_note(
+                    conn,
+                    issue_a,
+                    issue_b,
+                    link_type,
+                    note.as_deref(),
+                    allow_deleted,
+                )
+            }) {
+                Ok(()) => Response::ok(()),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 #[tauri::command]
 fn unlink_issues(state: State<AppState>, issue_a: i64, issue_b: i64) -> Response<()> {
-    with_db!(state, |db: &SkisDb| {
-        match ski::db::remove_link(db.conn(), issue_a, issue_b) {
-            Ok(()) => Response::ok(()),
-            Err(e) => Response::err(e.to_string()),
-        }
+    timed("unlink_issues", || {
+        with_db!(state, |db: &SkisDb| {
+            match db.with_retry(|conn| ski::db::remove_link(conn, issue_a, issue_b)) {
+                Ok(()) => Response::ok(()),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
     })
 }
 
 // ============ Export Commands ============
 
-#[derive(Debug, Serialize)]
-pub struct ExportData {
-    pub issues: Vec<IssueView>,
-    pub labels: Vec<Label>,
-    pub exported_at: String,
+#[tauri::command]
+fn export_json(state: State<AppState>, canonical: Option<bool>) -> Response<ExportData> {
+    timed("export_json", || {
+        with_db!(state, |db: &SkisDb| {
+            match export_all(db.conn(), canonical.unwrap_or(false)) {
+                Ok(data) => Response::ok(data),
+                Err(e) => Response::err(e.to_string()),
+            }
+        })
+    })
 }
 
+/// Write the export straight to `path`, returning the number of bytes written.
+/// Unlike `export_json`, the data never crosses the IPC boundary.
 #[tauri::command]
-fn export_json(state: State<AppState>) -> Response<ExportData> {
-    with_db!(state, |db: &SkisDb| {
-        // Get all issues (including closed, but not deleted)
-        let filter = IssueFilter {
-            state: None,
-            issue_type: None,
-            labels: vec![],
-            sort_by: SortField::Id,
-            sort_order: SortOrder::Asc,
-            limit: 100000,
-            offset: 0,
-            include_deleted: false,
-        };
-
-        let issues = match ski::db::list_issues(db.conn(), &filter) {
-            Ok(i) => i,
-            Err(e) => return Response::err(e.to_string()),
-        };
-
-        // Enrich each issue with labels, links, and comments
-        let mut views = Vec::with_capacity(issues.len());
-        for issue in issues {
-            let labels = ski::db::get_issue_labels(db.conn(), issue.id).unwrap_or_default();
-            let linked_issues =
-                ski::db::get_linked_issues_with_titles(db.conn(), issue.id).unwrap_or_default();
-            views.push(IssueView {
-                issue,
-                labels,
-                linked_issues,
-            });
-        }
-
-        // Get all labels
-        let labels = match ski::db::list_labels(db.conn()) {
-            Ok(l) => l,
-            Err(e) => return Response::err(e.to_string()),
-        };
-
-        let exported_at = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-
-        Response::ok(ExportData {
-            issues: views,
-            labels,
-            exported_at,
+fn export_json_to_file(
+    state: State<AppState>,
+    path: String,
+    canonical: Option<bool>,
+) -> Response<u64> {
+    timed("export_json_to_file", || {
+        let path = ski::util::expand_path(&path);
+        with_db!(state, |db: &SkisDb| {
+            let data = match export_all(db.conn(), canonical.unwrap_or(false)) {
+                Ok(data) => data,
+                Err(e) => return Response::err(e.to_string()),
+            };
+            match write_json(&path, &data) {
+                Ok(bytes) => Response::ok(bytes),
+                Err(e) => Response::err(format!("Failed to write {}: {}", path.display(), e)),
+            }
         })
     })
 }
 
+fn write_json(path: &Path, data: &ExportData) -> std::io::Result<u64> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, data)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(std::fs::metadata(path)?.len())
+}
+
 // ============ Window Commands ============
 
 #[tauri::command]
 fn open_edit_window(app: AppHandle, issue_id: Option<i64>) -> Response<()> {
-    let label = match issue_id {
-        Some(id) => format!("edit-{}", id),
-        None => "new".to_string(),
-    };
+    timed("open_edit_window", || {
+        let label = match issue_id {
+            Some(id) => format!("edit-{}", id),
+            None => "new".to_string(),
+        };
 
-    let title = match issue_id {
-        Some(id) => format!("Edit Issue #{}", id),
-        None => "New Issue".to_string(),
-    };
+        let title = match issue_id {
+            Some(id) => format!("Edit Issue #{}", id),
+            None => "New Issue".to_string(),
+        };
 
-    // Check if window already exists
-    if let Some(window) = app.get_webview_window(&label) {
-        let _ = window.set_focus();
-        return Response::ok(());
-    }
+        // Check if window already exists
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.set_focus();
+            return Response::ok(());
+        }
 
-    // Create new window
-    match WebviewWindowBuilder::new(&app, &label, tauri::WebviewUrl::App("edit.html".into()))
-        .title(&title)
-        .inner_size(600.0, 580.0)
-        .min_inner_size(500.0, 450.0)
-        .resizable(true)
-        .build()
-    {
-        Ok(_) => Response::ok(()),
-        Err(e) => Response::err(e.to_string()),
-    }
+        // Create new window
+        match WebviewWindowBuilder::new(&app, &label, tauri::WebviewUrl::App("edit.html".into()))
+            .title(&title)
+            .inner_size(600.0, 580.0)
+            .min_inner_size(500.0, 450.0)
+            .resizable(true)
+            .build()
+        {
+            Ok(_) => Response::ok(()),
+            Err(e) => Response::err(e.to_string()),
+        }
+    })
 }
 
 // ============ Menu Commands ============
@@ -766,32 +1259,36 @@ static WINDOW_COUNTER: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU
 
 #[tauri::command]
 fn open_new_window(app: AppHandle) -> Response<()> {
-    let counter = WINDOW_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-    let label = format!("main-{}", counter);
-
-    match WebviewWindowBuilder::new(&app, &label, tauri::WebviewUrl::App("index.html".into()))
-        .title("SKIS")
-        .inner_size(1200.0, 800.0)
-        .min_inner_size(800.0, 600.0)
-        .resizable(true)
-        .build()
-    {
-        Ok(_) => Response::ok(()),
-        Err(e) => Response::err(e.to_string()),
-    }
+    timed("open_new_window", || {
+        let counter = WINDOW_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let label = format!("main-{}", counter);
+
+        match WebviewWindowBuilder::new(&app, &label, tauri::WebviewUrl::App("index.html".into()))
+            .title("SKIS")
+            .inner_size(1200.0, 800.0)
+            .min_inner_size(800.0, 600.0)
+            .resizable(true)
+            .build()
+        {
+            Ok(_) => Response::ok(()),
+            Err(e) => Response::err(e.to_string()),
+        }
+    })
 }
 
 #[tauri::command]
 fn update_recent_menu(app: AppHandle, state: State<AppState>, paths: Vec<String>) -> Response<()> {
-    // Store paths in state for later rebuilds
-    {
-        let mut recent = state.recent_paths.lock().unwrap();
-        *recent = paths.clone();
-    }
-    if let Err(e) = rebuild_menu(&app, &paths) {
-        return Response::err(e.to_string());
-    }
-    Response::ok(())
+    timed("update_recent_menu", || {
+        // Store paths in state for later rebuilds
+        {
+            let mut recent = state.recent_paths.lock().unwrap();
+            *recent = paths.clone();
+        }
+        if let Err(e) = rebuild_menu(&app, &paths) {
+            return Response::err(e.to_string());
+        }
+        Response::ok(())
+    })
 }
 
 fn rebuild_menu_from_state(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
@@ -802,10 +1299,12 @@ fn rebuild_menu_from_state(app: &AppHandle) -> Result<(), Box<dyn std::error::Er
 
 #[tauri::command]
 fn refresh_window_menu(app: AppHandle) -> Response<()> {
-    if let Err(e) = rebuild_menu_from_state(&app) {
-        return Response::err(e.to_string());
-    }
-    Response::ok(())
+    timed("refresh_window_menu", || {
+        if let Err(e) = rebuild_menu_from_state(&app) {
+            return Response::err(e.to_string());
+        }
+        Response::ok(())
+    })
 }
 
 fn rebuild_menu(app: &AppHandle, recent_paths: &[String]) -> Result<(), Box<dyn std::error::Error>> {
@@ -854,6 +1353,11 @@ fn rebuild_menu(app: &AppHandle, recent_paths: &[String]) -> Result<(), Box<dyn
                 .build(app)?,
         )
         .item(&recent_submenu.build()?)
+        .item(
+            &MenuItemBuilder::new("Close Repository")
+                .id("close-repository")
+                .build(app)?,
+        )
         .separator()
         .item(
             &MenuItemBuilder::new("New Issue")
@@ -982,6 +1486,9 @@ pub fn run() {
             } else if id == "open" {
                 debug!("Open directory requested from menu");
                 let _ = app.emit("menu-open", ());
+            } else if id == "close-repository" {
+                info!("Close repository requested from menu");
+                let _ = app.emit("menu-close-repository", ());
             } else if id == "reload" {
                 debug!("Reload requested from menu");
                 let _ = app.emit("menu-reload", ());
@@ -1007,17 +1514,8 @@ pub fn run() {
                     .join("skis")
                     .join("logs");
                 // Open the log directory in the system file browser
-                #[cfg(target_os = "macos")]
-                {
-                    let _ = std::process::Command::new("open").arg(&log_dir).spawn();
-                }
-                #[cfg(target_os = "windows")]
-                {
-                    let _ = std::process::Command::new("explorer").arg(&log_dir).spawn();
-                }
-                #[cfg(target_os = "linux")]
-                {
-                    let _ = std::process::Command::new("xdg-open").arg(&log_dir).spawn();
+                if let Err(e) = ski::platform::open_path(&log_dir) {
+                    warn!(error = %e, "Failed to open log directory");
                 }
             }
         })
@@ -1035,12 +1533,18 @@ pub fn run() {
             get_current_dir,
             select_directory,
             init_repository,
+            close_repository,
             get_home_dir,
             get_log_path,
             log_frontend,
+            get_recent_command_stats,
+            take_launch_request,
+            get_repo_config,
             // Issues
             list_issues,
             get_issue,
+            copy_issue_reference,
+            get_body_template,
             create_issue,
             update_issue,
             close_issue,
@@ -1054,6 +1558,8 @@ pub fn run() {
             delete_comment,
             // Labels
             list_labels,
+            get_label_palette,
+            suggest_label,
             create_label,
             delete_label,
             add_label_to_issue,
@@ -1063,6 +1569,7 @@ pub fn run() {
             unlink_issues,
             // Export
             export_json,
+            export_json_to_file,
             // Windows
             open_edit_window,
             open_new_window,
@@ -9,6 +9,8 @@ let issueId = null;
 let labels = [];
 let selectedLabels = new Set();
 let originalLabels = new Set(); // Track original labels for diffing on save
+let issueVersion = null; // Version loaded, so saving over someone else's edit fails
+let bodyTemplate = ''; // Template last put in the body of a new issue
 
 const inputTitle = document.getElementById('input-title');
 const inputType = document.getElementById('input-type');
@@ -83,6 +85,7 @@ async function init() {
         inputTitle.value = issue.title;
         inputType.value = issue.type;
         inputBody.value = issue.body || '';
+        issueVersion = issue.version;
 
         // Select issue's labels
         issue.labels.forEach(l => {
@@ -93,6 +96,10 @@ async function init() {
     } catch (e) {
       console.error('Could not load issue:', e);
     }
+  } else {
+    await applyBodyTemplate();
+    // Follow the type while the body is still the untouched template
+    inputType.addEventListener('change', applyBodyTemplate);
   }
 
   renderLabelsDropdown();
@@ -101,6 +108,19 @@ async function init() {
   inputTitle.focus();
 }
 
+async function applyBodyTemplate() {
+  if (inputBody.value !== bodyTemplate) return;
+  try {
+    const result = await invoke('get_body_template', { issueType: inputType.value });
+    if (result.ok) {
+      bodyTemplate = result.data;
+      inputBody.value = bodyTemplate;
+    }
+  } catch (e) {
+    console.error('Could not load body template:', e);
+  }
+}
+
 function renderLabelsDropdown() {
   // Render menu items
   if (labels.length === 0) {
@@ -212,7 +232,8 @@ async function save() {
         params: {
           title: inputTitle.value,
           body: inputBody.value || null,
-          issue_type: inputType.value
+          issue_type: inputType.value,
+          version: issueVersion
         }
       });
 
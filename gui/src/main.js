@@ -22,7 +22,9 @@ const MAX_RECENT_DIRS = 10;
 const STORAGE_RECENT_DIRS = 'skis_recent_directories';
 const STORAGE_WINDOW_STATE = 'skis_main_window_state';
 const STORAGE_APP_STATE = 'skis_app_state';
-const PAGE_SIZE = 50;
+const STORAGE_PAGE_SIZE = 'skis_page_size';
+// Issues fetched per scroll step; override with localStorage.setItem('skis_page_size', n)
+const PAGE_SIZE = parseInt(localStorage.getItem(STORAGE_PAGE_SIZE), 10) || 50;
 
 // ============ State ============
 
@@ -35,6 +37,8 @@ let homeDir = '';
 let recentDirectories = [];
 let isLoadingMore = false;
 let hasMoreIssues = true;
+let nextCursor = null;
+let totalIssues = 0;
 let sidebarCollapsed = false;
 let sidebarWidth = 320;
 
@@ -47,6 +51,7 @@ const btnInit = document.getElementById('btn-init');
 
 // Filters
 const searchInput = document.getElementById('search-input');
+const searchIn = document.getElementById('search-in');
 const filterState = document.getElementById('filter-state');
 const filterType = document.getElementById('filter-type');
 const filterLabel = document.getElementById('filter-label');
@@ -94,6 +99,7 @@ const bodyEditTabPreview = document.getElementById('body-edit-tab-preview');
 const linkedIssues = document.getElementById('linked-issues');
 const linkForm = document.getElementById('link-form');
 const linkIssueId = document.getElementById('link-issue-id');
+const linkType = document.getElementById('link-type');
 const btnNewLink = document.getElementById('btn-new-link');
 const btnLink = document.getElementById('btn-link');
 const btnCancelLink = document.getElementById('btn-cancel-link');
@@ -192,6 +198,17 @@ async function init() {
     await browseDirectory();
   });
 
+  onMenuEvent('menu-close-repository', async () => {
+    log.info('Menu event: close-repository');
+    await closeRepository();
+  });
+
+  // The repository is shared by all windows, so every window resets when it closes
+  await listen('repository-closed', () => {
+    log.info('Repository closed event received');
+    resetRepositoryView();
+  });
+
   onMenuEvent('menu-reload', async () => {
     log.info('Menu event: reload');
     await reload();
@@ -223,12 +240,22 @@ async function init() {
     }
   });
 
-  // Only restore directory for main window - new windows start blank
+  // Only restore directory for main window - new windows start blank.
+  // A launch request from `skis issue browse` takes precedence over the saved directory.
   if (isMainWindow) {
-    const savedDir = localStorage.getItem('skis_directory');
-    if (savedDir) {
-      log.info(`Restoring saved directory`, savedDir);
-      await selectDirectory(savedDir, true);
+    const launch = await takeLaunchRequest();
+    if (launch?.repo) {
+      log.info(`Opening launch request`, `repo=${launch.repo}, issue=${launch.issue}`);
+      await selectDirectory(launch.repo);
+      if (launch.issue) {
+        await selectIssue(launch.issue);
+      }
+    } else {
+      const savedDir = localStorage.getItem('skis_directory');
+      if (savedDir) {
+        log.info(`Restoring saved directory`, savedDir);
+        await selectDirectory(savedDir, true);
+      }
     }
   }
 
@@ -236,6 +263,18 @@ async function init() {
   log.info('Frontend initialization complete');
 }
 
+async function takeLaunchRequest() {
+  try {
+    const result = await invoke('take_launch_request');
+    if (result.ok) {
+      return result.data;
+    }
+  } catch (e) {
+    log.error(`Could not get launch request: ${e}`);
+  }
+  return null;
+}
+
 function setupEventListeners() {
   // Directory
   btnBrowse.addEventListener('click', browseDirectory);
@@ -247,6 +286,7 @@ function setupEventListeners() {
     saveAppState();
   };
   searchInput.addEventListener('input', debounce(loadAndSave, 300));
+  searchIn.addEventListener('change', loadAndSave);
   filterState.addEventListener('change', loadAndSave);
   filterType.addEventListener('change', loadAndSave);
   filterLabel.addEventListener('change', loadAndSave);
@@ -287,6 +327,7 @@ function setupEventListeners() {
     if (swatch) {
       colorSwatches.querySelectorAll('.color-swatch').forEach(s => s.classList.remove('selected'));
       swatch.classList.add('selected');
+      colorSwatches.classList.remove('invalid');
     }
   });
 
@@ -407,6 +448,7 @@ async function selectDirectory(path, restoreState = false) {
     log.debug(`select_directory result`, `ok=${result.ok}, initialized=${result.data?.initialized}`);
     if (result.ok) {
       directoryPath.value = shortenPath(path);
+      directoryPath.title = directoryInfo(path, result.data.schema_version);
       localStorage.setItem('skis_directory', path);
 
       // Add to recent directories
@@ -418,6 +460,8 @@ async function selectDirectory(path, restoreState = false) {
 
         // Restore app state before loading (filters, sort) - only for main window on startup
         const savedState = restoreState ? restoreAppState() : null;
+        // The state filter always starts from the repository's list.default_state
+        await applyRepoConfig();
 
         await loadIssues();
         await loadLabels();
@@ -436,6 +480,12 @@ async function selectDirectory(path, restoreState = false) {
         btnInit.style.display = 'inline-block';
         showEmptyState('Directory not initialized. Click ⚡ to initialize SKIS.');
       }
+    } else if (result.code === 'schema_too_new') {
+      // Written by a newer SKIS: explain instead of half-loading the repository
+      log.warn(`Repository schema too new`, path);
+      btnInit.style.display = 'none';
+      showEmptyState(escapeHtml(result.error));
+      showError(result.error);
     } else {
       log.error(`selectDirectory error: ${result.error}`);
       showError(result.error);
@@ -446,6 +496,25 @@ async function selectDirectory(path, restoreState = false) {
   }
 }
 
+// Tooltip for the directory field: full path plus database schema version
+// Start the state filter where `skis issue list` does, from config.toml
+async function applyRepoConfig() {
+  try {
+    const result = await invoke('get_repo_config');
+    if (result.ok) {
+      filterState.value = result.data.list.default_state;
+    } else {
+      log.warn(`get_repo_config failed`, result.error);
+    }
+  } catch (err) {
+    log.error(`get_repo_config exception: ${err}`);
+  }
+}
+
+function directoryInfo(path, schemaVersion) {
+  return schemaVersion != null ? `${path}\nDatabase schema v${schemaVersion}` : path;
+}
+
 async function initRepository() {
   log.info('initRepository called');
   try {
@@ -454,6 +523,7 @@ async function initRepository() {
     if (result.ok) {
       log.info('Repository initialized successfully');
       btnInit.style.display = 'none';
+      await applyRepoConfig();
       await loadIssues();
       await loadLabels();
     } else {
@@ -466,6 +536,32 @@ async function initRepository() {
   }
 }
 
+async function closeRepository() {
+  log.info('closeRepository called');
+  try {
+    const result = await invoke('close_repository');
+    if (!result.ok) {
+      showError(result.error);
+    }
+  } catch (err) {
+    log.error(`closeRepository exception: ${err}`);
+    showError(err);
+  }
+}
+
+function resetRepositoryView() {
+  currentIssue = null;
+  issues = [];
+  labels = [];
+  renderIssueDetail();
+  updateLabelFilter();
+  directoryPath.value = '';
+  directoryPath.title = '';
+  localStorage.removeItem('skis_directory');
+  btnInit.style.display = 'none';
+  showEmptyState('No repository open. Choose a directory to get started.');
+}
+
 async function createNewDatabase() {
   log.info('createNewDatabase called');
   try {
@@ -494,14 +590,16 @@ async function createNewDatabase() {
         } else {
           // Initialize new database
           log.info(`Initializing new database`, selected);
-          const initResult = await invoke('init_repository');
+          const initResult = await invoke('init_repository', { path: selected });
           log.debug(`init_repository result`, `ok=${initResult.ok}`);
           if (initResult.ok) {
             log.info(`New database created successfully`, selected);
             directoryPath.value = shortenPath(selected);
+            directoryPath.title = directoryInfo(selected, initResult.data.schema_version);
             localStorage.setItem('skis_directory', selected);
             addRecentDirectory(selected);
             btnInit.style.display = 'none';
+            await applyRepoConfig();
             await loadIssues();
             await loadLabels();
           } else {
@@ -530,23 +628,18 @@ async function reload() {
 
 async function exportToJson() {
   try {
-    const result = await invoke('export_json');
-    if (result.ok) {
-      const json = JSON.stringify(result.data, null, 2);
-
-      // Use save dialog to get file path
-      const { save } = window.__TAURI__.dialog;
-      const filePath = await save({
-        defaultPath: 'skis-export.json',
-        filters: [{ name: 'JSON', extensions: ['json'] }]
-      });
+    // Use save dialog to get file path
+    const { save } = window.__TAURI__.dialog;
+    const filePath = await save({
+      defaultPath: 'skis-export.json',
+      filters: [{ name: 'JSON', extensions: ['json'] }]
+    });
+    if (!filePath) return;
 
-      if (filePath) {
-        // Write the file
-        const { writeTextFile } = window.__TAURI__.fs;
-        await writeTextFile(filePath, json);
-        alert(`Exported ${result.data.issues.length} issues to ${filePath}`);
-      }
+    // The backend writes the file itself, so large exports don't cross IPC
+    const result = await invoke('export_json_to_file', { path: filePath });
+    if (result.ok) {
+      alert(`Exported ${formatBytes(result.data)} to ${filePath}`);
     } else {
       showError(result.error);
     }
@@ -555,6 +648,12 @@ async function exportToJson() {
   }
 }
 
+function formatBytes(bytes) {
+  if (bytes < 1024) return `${bytes} bytes`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 // ============ Sidebar ============
 
 function toggleSidebar() {
@@ -632,6 +731,7 @@ function saveAppState() {
     sortBy: sortBy.value,
     sortOrder: sortOrder,
     search: searchInput.value,
+    searchIn: searchIn.value,
     selectedIssueId: currentIssue?.id || null
   };
   localStorage.setItem(STORAGE_APP_STATE, JSON.stringify(state));
@@ -654,6 +754,7 @@ function restoreAppState() {
       btnSortOrder.textContent = sortOrder === 'desc' ? '↓' : '↑';
     }
     if (state.search) searchInput.value = state.search;
+    if (state.searchIn) searchIn.value = state.searchIn;
 
     return state;
   } catch (e) {
@@ -670,11 +771,10 @@ async function loadIssues(append) {
 
   if (isLoadingMore) return;
 
-  const offset = append ? issues.length : 0;
-
   if (!append) {
     issues = [];
     hasMoreIssues = true;
+    nextCursor = null;
   }
 
   if (!hasMoreIssues) return;
@@ -688,14 +788,18 @@ async function loadIssues(append) {
     sort_by: sortBy.value,
     sort_order: sortOrder,
     search: searchInput.value || null,
+    search_in: searchIn.value,
     limit: PAGE_SIZE,
-    offset: offset
+    // The keyset cursor positions the page, so rows changing between pages aren't
+    // skipped or repeated; the offset is only echoed back for display
+    offset: append ? issues.length : 0,
+    cursor: append ? nextCursor : null
   };
 
   try {
     const result = await invoke('list_issues', { filter });
     if (result.ok) {
-      const newIssues = result.data;
+      const newIssues = result.data.issues;
 
       if (append) {
         issues = [...issues, ...newIssues];
@@ -703,7 +807,9 @@ async function loadIssues(append) {
         issues = newIssues;
       }
 
-      hasMoreIssues = newIssues.length === PAGE_SIZE;
+      nextCursor = result.data.next_cursor;
+      hasMoreIssues = result.data.has_more;
+      totalIssues = result.data.total;
       renderIssueList();
     } else {
       showError(result.error);
@@ -725,7 +831,7 @@ function renderIssueList() {
   }
 
   emptyState.style.display = 'none';
-  const countText = hasMoreIssues ? `${issues.length}+ issues` : `${issues.length} issue${issues.length !== 1 ? 's' : ''}`;
+  const countText = hasMoreIssues ? `${issues.length} of ${totalIssues} issues` : `${issues.length} issue${issues.length !== 1 ? 's' : ''}`;
   issueCount.textContent = countText;
 
   issueList.innerHTML = issues.map(issue => `
@@ -736,6 +842,10 @@ function renderIssueList() {
         <span class="label-pill type-${issue.type}">${issue.type}</span>
         ${issue.labels.map(l => renderLabelPill(l, true)).join('')}
       </div>
+      ${issue.last_activity_at ? `
+      <div class="issue-item-meta">
+        ${issue.comment_count > 0 ? `💬 ${issue.comment_count} · ` : ''}updated ${formatRelativeTime(issue.last_activity_at)}
+      </div>` : ''}
     </div>
   `).join('');
 
@@ -745,9 +855,29 @@ function renderIssueList() {
       const id = parseInt(el.dataset.id);
       selectIssue(id);
     });
+    // Right-click copies a reference (shift for the Markdown link form)
+    el.addEventListener('contextmenu', e => {
+      e.preventDefault();
+      copyIssueReference(parseInt(el.dataset.id), e.shiftKey ? 'markdown' : 'plain');
+    });
   });
 }
 
+async function copyIssueReference(id, format) {
+  try {
+    const result = await invoke('copy_issue_reference', { id, format });
+    if (result.ok) {
+      await navigator.clipboard.writeText(result.data);
+      log.info(`Copied issue reference`, result.data);
+    } else {
+      showError(result.error);
+    }
+  } catch (err) {
+    log.error(`copyIssueReference error: ${err}`);
+    showError(err);
+  }
+}
+
 function navigateIssueList(direction) {
   if (issues.length === 0) return;
 
@@ -875,8 +1005,8 @@ function renderIssueDetail() {
   // Linked issues
   if (currentIssue.linked_issues.length > 0) {
     linkedIssues.innerHTML = currentIssue.linked_issues.map(li => `
-      <span class="linked-issue" data-id="${li.id}">
-        #${li.id} ${escapeHtml(li.title.substring(0, 30))}${li.title.length > 30 ? '...' : ''}
+      <span class="linked-issue${li.deleted ? ' deleted' : ''}" data-id="${li.id}" title="${li.deleted ? 'Deleted issue' : ''}">
+        ${li.link_type && li.link_type !== 'relates' ? `<span class="link-type">${li.link_type.replace('_', ' ')}</span> ` : ''}#${li.id} ${escapeHtml(li.title.substring(0, 30))}${li.title.length > 30 ? '...' : ''}${li.deleted ? ' (deleted)' : ''}
         <button class="btn-icon unlink-btn" data-id="${li.id}" title="Unlink">×</button>
       </span>
     `).join('');
@@ -952,6 +1082,15 @@ async function loadComments(issueId) {
   }
 }
 
+// Keep the list row's comment count in step without reloading the list
+function updateCommentCount(issueId, count) {
+  const issue = issues.find(i => i.id === issueId);
+  if (issue && issue.comment_count !== undefined) {
+    issue.comment_count = count;
+    renderIssueList();
+  }
+}
+
 function renderComments(comments) {
   if (comments.length === 0) {
     commentsList.innerHTML = '<p style="color: var(--color-text-muted); font-size: 0.8rem;">No comments yet</p>';
@@ -963,7 +1102,7 @@ function renderComments(comments) {
       <div class="comment-header">
         <div class="comment-meta">
           ${formatDateTime(c.created_at)}
-          ${c.updated_at && c.updated_at !== c.created_at ? '<span class="comment-edited">(edited)</span>' : ''}
+          ${c.edited ? '<span class="comment-edited">(edited)</span>' : ''}
         </div>
         <div class="comment-actions">
           <button class="btn-icon btn-edit-comment" data-id="${c.id}" title="Edit">✎</button>
@@ -1109,10 +1248,12 @@ async function linkIssue() {
   try {
     const result = await invoke('link_issues', {
       issueA: currentIssue.id,
-      issueB: targetId
+      issueB: targetId,
+      linkType: linkType.value
     });
     if (result.ok) {
       linkIssueId.value = '';
+      linkType.value = 'relates';
       linkForm.style.display = 'none';
       btnNewLink.textContent = '+';
       btnNewLink.title = 'Link issue';
@@ -1263,6 +1404,7 @@ function showNewLabelForm() {
     btn.title = 'Cancel';
   }
   newLabelName.value = '';
+  colorSwatches.classList.remove('invalid');
   // Reset color selection to first (gray)
   colorSwatches.querySelectorAll('.color-swatch').forEach((swatch, i) => {
     swatch.classList.toggle('selected', i === 0);
@@ -1322,6 +1464,9 @@ async function saveNewLabel() {
       startEditLabels();
     } else {
       log.error(`Failed to create label: ${result.error}`);
+      if (result.code === 'invalid_color') {
+        colorSwatches.classList.add('invalid');
+      }
       showError(result.error);
     }
   } catch (err) {
@@ -1381,7 +1526,7 @@ async function saveEditTitle() {
   try {
     const result = await invoke('update_issue', {
       id: currentIssue.id,
-      params: { title }
+      params: { title, version: currentIssue.version }
     });
     if (result.ok) {
       currentIssue = result.data;
@@ -1465,7 +1610,8 @@ async function saveEditBody() {
     const result = await invoke('update_issue', {
       id: currentIssue.id,
       params: {
-        body: body
+        body: body,
+        version: currentIssue.version
       }
     });
     if (result.ok) {
@@ -1590,9 +1736,10 @@ async function deleteComment(commentId) {
   if (!confirm('Delete this comment?')) return;
 
   try {
-    const result = await invoke('delete_comment', { commentId });
+    const result = await invoke('delete_comment', { issueId: currentIssue.id, commentId });
     if (result.ok) {
-      await loadComments(currentIssue.id);
+      renderComments(result.data);
+      updateCommentCount(currentIssue.id, result.data.length);
     } else {
       showError(result.error);
     }
@@ -0,0 +1,97 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use ski::db::{self, SkisDb};
+use ski::error::{Error, Result};
+use ski::platform;
+
+use crate::commands::issue::print_issue_view;
+use crate::IssueBrowseArgs;
+
+/// Environment variable overriding GUI discovery (path to the app or binary)
+const GUI_ENV: &str = "SKIS_GUI";
+
+pub fn run(args: IssueBrowseArgs) -> Result<()> {
+    open_issue(args.number, false)
+}
+
+/// Open an issue in the SKIS GUI, falling back to the terminal view
+/// when the GUI is missing or fails to start
+pub fn open_issue(number: i64, show_comments: bool) -> Result<()> {
+    let db = SkisDb::open()?;
+    let issue = db::get_issue(db.conn(), number)?.ok_or(Error::IssueNotFound(number))?;
+
+    match launch_gui(db.discovered_root(), number) {
+        Ok(()) => {
+            println!("Opened issue #{} in the SKIS GUI", number);
+            Ok(())
+        }
+        Err(reason) => {
+            eprintln!("warning: {}; showing issue in the terminal", reason);
+            print_issue_view(db.conn(), &issue, show_comments)
+        }
+    }
+}
+
+/// Start the GUI focused on the given repository and issue
+fn launch_gui(root: &Path, number: i64) -> std::result::Result<(), String> {
+    let gui = find_gui().ok_or_else(|| "SKIS GUI not found".to_string())?;
+    let number = number.to_string();
+    let args: [&OsStr; 4] = [
+        OsStr::new("--repo"),
+        root.as_os_str(),
+        OsStr::new("--issue"),
+        OsStr::new(&number),
+    ];
+
+    platform::launch_app(&gui, &args)
+        .map(|_| ())
+        .map_err(|e| format!("failed to launch {}: {}", gui.display(), e))
+}
+
+/// Locate an installed GUI, honouring `SKIS_GUI` before the known install paths
+fn find_gui() -> Option<PathBuf> {
+    if let Some(value) = std::env::var_os(GUI_ENV) {
+        let path = PathBuf::from(value);
+        return path.exists().then_some(path);
+    }
+
+    known_install_paths().into_iter().find(|p| p.exists())
+}
+
+#[cfg(target_os = "macos")]
+fn known_install_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from("/Applications/SKIS.app")];
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join("Applications/SKIS.app"));
+    }
+    paths
+}
+
+#[cfg(target_os = "windows")]
+fn known_install_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(local) = dirs::data_local_dir() {
+        paths.push(local.join("SKIS").join("skis-gui.exe"));
+    }
+    if let Some(program_files) = std::env::var_os("ProgramFiles") {
+        paths.push(
+            PathBuf::from(program_files)
+                .join("SKIS")
+                .join("skis-gui.exe"),
+        );
+    }
+    paths
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn known_install_paths() -> Vec<PathBuf> {
+    let mut paths = vec![
+        PathBuf::from("/usr/bin/skis-gui"),
+        PathBuf::from("/usr/local/bin/skis-gui"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(".local/bin/skis-gui"));
+    }
+    paths
+}
@@ -0,0 +1,98 @@
+use std::path::Path;
+use std::process::Command;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use ski::db::{self, SkisDb};
+use ski::error::{Error, Result};
+use ski::models::{Issue, IssueFilter, IssueState, SortField, SortOrder, StateReason};
+use ski::output::{render_changelog, ChangelogGroup};
+
+use crate::ChangelogArgs;
+
+pub fn run(args: ChangelogArgs) -> Result<()> {
+    let group = match args.group_by.to_lowercase().as_str() {
+        "type" => ChangelogGroup::Type,
+        "label" => ChangelogGroup::Label,
+        _ => {
+            eprintln!(
+                "error: invalid group '{}', must be type or label",
+                args.group_by
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let db = SkisDb::open()?;
+    let since = resolve_since(&args.since, db.discovered_root())?;
+
+    let filter = IssueFilter {
+        state: Some(IssueState::Closed),
+        closed_after: Some(since),
+        sort_by: SortField::Id,
+        sort_order: SortOrder::Asc,
+        limit: i64::MAX as usize,
+        ..Default::default()
+    };
+
+    let entries = closed_entries(&db, &filter, args.include_wontfix)?;
+    let heading = format!("Changes since {}", args.since);
+    print!("{}", render_changelog(&heading, &entries, group));
+    Ok(())
+}
+
+/// Closed issues matching `filter`, each paired with its label names. Issues
+/// closed as not planned are left out unless `include_wontfix` is set.
+pub fn closed_entries(
+    db: &SkisDb,
+    filter: &IssueFilter,
+    include_wontfix: bool,
+) -> Result<Vec<(Issue, Vec<String>)>> {
+    let mut entries = Vec::new();
+    for mut issue in db::list_issues_with_labels(db.conn(), filter)? {
+        if !include_wontfix && issue.state_reason == Some(StateReason::NotPlanned) {
+            continue;
+        }
+        let labels = issue
+            .labels
+            .take()
+            .into_iter()
+            .flatten()
+            .map(|l| l.name)
+            .collect();
+        entries.push((issue, labels));
+    }
+    Ok(entries)
+}
+
+/// Resolve `--since` as a duration (`14d`), a date (`2024-01-31`), or a git ref (`v0.9`)
+pub fn resolve_since(since: &str, root: &Path) -> Result<DateTime<Utc>> {
+    if let Ok(duration) = ski::util::parse_duration(since) {
+        return Utc::now()
+            .checked_sub_signed(duration)
+            .ok_or_else(|| Error::InvalidSince(since.to_string()));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+
+    git_ref_time(since, root).ok_or_else(|| Error::InvalidSince(since.to_string()))
+}
+
+/// Commit time of a git tag or other ref, if `root` is a git checkout that has it
+fn git_ref_time(reference: &str, root: &Path) -> Option<DateTime<Utc>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "-1", "--format=%cI", reference, "--"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stamp = String::from_utf8(output.stdout).ok()?;
+    DateTime::parse_from_rfc3339(stamp.trim())
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
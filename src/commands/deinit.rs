@@ -0,0 +1,58 @@
+use ski::db::{self, SkisDb};
+use ski::error::{Error, Result};
+use ski::models::IssueFilter;
+
+use crate::DeinitArgs;
+
+pub fn run(args: DeinitArgs) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let skis_dir = db::find_skis_dir()?;
+
+    // Only remove the current directory's own repository, never one discovered in a parent
+    let root = skis_dir.parent().unwrap_or(&skis_dir);
+    if std::fs::canonicalize(root)? != std::fs::canonicalize(&cwd)? {
+        return Err(Error::NotRepositoryRoot(root.display().to_string()));
+    }
+
+    let issue_count = {
+        let db = SkisDb::open_at(&skis_dir)?;
+        let filter = IssueFilter {
+            include_deleted: true,
+            include_archived: true,
+            ..Default::default()
+        };
+        db::count_issues(db.conn(), &filter)?
+    };
+
+    if !args.yes {
+        eprint!(
+            "Remove {} and its {} issue(s)? [y/N] ",
+            skis_dir.display(),
+            issue_count
+        );
+        if !confirmed()? {
+            println!("Cancelled");
+            return Ok(());
+        }
+
+        eprint!("This cannot be undone. Really delete? [y/N] ");
+        if !confirmed()? {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    std::fs::remove_dir_all(&skis_dir)?;
+    println!(
+        "Removed SKIS repository from {} ({} issue(s) deleted)",
+        cwd.display(),
+        issue_count
+    );
+    Ok(())
+}
+
+fn confirmed() -> Result<bool> {
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
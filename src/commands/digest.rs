@@ -0,0 +1,24 @@
+use std::str::FromStr;
+
+use ski::db::{self, SkisDb};
+use ski::error::Result;
+use ski::output::{render_digest, DigestFormat};
+
+use crate::commands::changelog::resolve_since;
+use crate::DigestArgs;
+
+pub fn run(args: DigestArgs) -> Result<()> {
+    let format = DigestFormat::from_str(&args.format)?;
+    let db = SkisDb::open()?;
+    let since = resolve_since(&args.since, db.discovered_root())?;
+
+    let document = render_digest(&db::digest(db.conn(), since)?, format);
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, document)?;
+            println!("Wrote digest to {}", path);
+        }
+        None => print!("{}", document),
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use ski::db::{self, SkisDb};
+use ski::error::Result;
+
+use crate::DoctorArgs;
+
+pub fn run(args: DoctorArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+
+    let empty = db::find_empty_comments(db.conn())?;
+    if empty.is_empty() {
+        println!("No problems found");
+        return Ok(());
+    }
+
+    for comment in &empty {
+        println!(
+            "Comment #{} on issue #{} is empty",
+            comment.id, comment.issue_id
+        );
+    }
+
+    if args.fix {
+        let tx = db.conn().unchecked_transaction()?;
+        for comment in &empty {
+            db::delete_comment(&tx, comment.id)?;
+        }
+        tx.commit()?;
+        println!("Deleted {} empty comment(s)", empty.len());
+    } else {
+        println!(
+            "Found {} empty comment(s). Run 'skis doctor --fix' to delete them.",
+            empty.len()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use ski::db::SkisDb;
+use ski::error::Result;
+use ski::export::export_issues;
+use ski::models::StateFilter;
+
+use crate::ExportArgs;
+
+pub fn run(args: ExportArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let mut filter = args.filter.to_bulk_filter(StateFilter::All)?;
+    filter.include_archived = true;
+    let data = export_issues(
+        db.conn(),
+        args.filter.search.as_deref(),
+        &filter,
+        args.canonical,
+    )?;
+    println!("{}", serde_json::to_string_pretty(&data)?);
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, Utc};
+use ski::db::{self, SkisDb};
+use ski::error::{Error, Result};
+use ski::models::{IssueCreate, IssueFilter, IssueTimestamps, IssueType, LinkType, StateReason};
+
+use crate::FixturesGenerateArgs;
+
+/// Labels every fixture repository gets
+const LABELS: &[&str] = &[
+    "bug",
+    "ui",
+    "backend",
+    "docs",
+    "performance",
+    "security",
+    "good first issue",
+    "needs-triage",
+];
+
+const VERBS: &[&str] = &[
+    "Fix",
+    "Add",
+    "Improve",
+    "Investigate",
+    "Remove",
+    "Refactor",
+    "Support",
+    "Document",
+];
+
+const SUBJECTS: &[&str] = &[
+    "login form",
+    "search index",
+    "CSV export",
+    "settings page",
+    "sync service",
+    "dark theme",
+    "keyboard shortcuts",
+    "cache layer",
+    "upload dialog",
+    "API client",
+    "release notes",
+    "error messages",
+];
+
+const CONTEXTS: &[&str] = &[
+    "on startup",
+    "for large repositories",
+    "after upgrading",
+    "in offline mode",
+    "on Windows",
+    "when the session expires",
+    "with unicode titles",
+    "under heavy load",
+];
+
+const COMMENTS: &[&str] = &[
+    "I can reproduce this on the latest build.",
+    "Looks related to the change in the last release.",
+    "Working on a fix.",
+    "Could we add a test for this?",
+    "Not seeing this anymore after the update.",
+    "Here is a smaller example that shows it.",
+    "Bumping this, it keeps coming up.",
+];
+
+/// How far back the generated history reaches
+const HISTORY_DAYS: i64 = 365;
+
+pub fn generate(args: FixturesGenerateArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let conn = db.conn();
+    let has_issues = db::count_issues(conn, &IssueFilter::default())? > 0;
+    if !args.force && (has_issues || !db::list_labels(conn)?.is_empty()) {
+        return Err(Error::RepositoryNotEmpty);
+    }
+
+    let mut rng = fastrand::Rng::with_seed(args.seed);
+    let pick = |rng: &mut fastrand::Rng, words: &[&'static str]| words[rng.usize(..words.len())];
+
+    let existing: Vec<String> = db::list_labels(conn)?.into_iter().map(|l| l.name).collect();
+    for name in LABELS {
+        if !existing.iter().any(|e| e.eq_ignore_ascii_case(name)) {
+            db::create_label(conn, name, None, None)?;
+        }
+    }
+
+    // Spread creation times over the last year, oldest first so issue numbers
+    // follow creation order. Times count from midnight UTC, so a seed gives the
+    // same repository all day.
+    let end = Utc::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight")
+        .and_utc();
+    let span = HISTORY_DAYS * 24 * 60 * 60;
+    let mut created: Vec<i64> = (0..args.issues).map(|_| rng.i64(0..span)).collect();
+    created.sort_unstable();
+
+    let types = [
+        IssueType::Task,
+        IssueType::Task,
+        IssueType::Bug,
+        IssueType::Bug,
+        IssueType::Request,
+        IssueType::Epic,
+    ];
+    let mut ids = Vec::with_capacity(args.issues);
+    let mut times = Vec::with_capacity(args.issues);
+    let mut comments = 0;
+    for offset in created {
+        let issue_type = types[rng.usize(..types.len())];
+        let context = pick(&mut rng, CONTEXTS);
+        let create = IssueCreate {
+            title: format!(
+                "{} {} {}",
+                pick(&mut rng, VERBS),
+                pick(&mut rng, SUBJECTS),
+                context
+            ),
+            body: Some(format!(
+                "Noticed {}. See the steps in the comments.",
+                context
+            )),
+            issue_type,
+            labels: (0..rng.usize(0..=2))
+                .map(|_| pick(&mut rng, LABELS).to_string())
+                .collect(),
+            points: rng.bool().then(|| rng.u32(1..=8)),
+        };
+        let issue = db::create_issue(conn, &create)?;
+        ids.push(issue.id);
+
+        let created_at = end - Duration::seconds(span - offset);
+        let age = (end - created_at).num_seconds().max(1);
+        let updated_at = created_at + Duration::seconds(rng.i64(0..age.min(30 * 24 * 60 * 60)));
+
+        let mut comment_at = created_at;
+        for _ in 0..rng.usize(0..=3) {
+            comment_at += Duration::seconds(rng.i64(0..=(updated_at - comment_at).num_seconds()));
+            let comment = db::add_comment(conn, issue.id, pick(&mut rng, COMMENTS))?;
+            db::backdate_comment(conn, comment.id, comment_at)?;
+            comments += 1;
+        }
+
+        let closed_at = if rng.u8(..10) < 4 {
+            let reason = if rng.u8(..4) == 0 {
+                StateReason::NotPlanned
+            } else {
+                StateReason::Completed
+            };
+            db::close_issue(conn, issue.id, reason)?;
+            Some(updated_at)
+        } else {
+            None
+        };
+        times.push(IssueTimestamps {
+            id: issue.id,
+            created_at,
+            updated_at,
+            closed_at,
+        });
+    }
+
+    // About one link per ten issues, skipping self-links and repeats
+    let mut links = HashSet::new();
+    for _ in 0..ids.len() / 10 {
+        let (a, b) = (pick_id(&mut rng, &ids), pick_id(&mut rng, &ids));
+        if a == b || !links.insert((a.min(b), a.max(b))) {
+            continue;
+        }
+        let link_type = if rng.bool() {
+            LinkType::Relates
+        } else {
+            LinkType::Blocks
+        };
+        db::add_link(conn, a, b, link_type, false)?;
+    }
+
+    // Last, so closing and commenting don't leave today's date behind
+    db::backdate_issues(conn, &times)?;
+
+    println!(
+        "Generated {} issues, {} comments and {} links (seed {})",
+        ids.len(),
+        comments,
+        links.len(),
+        args.seed
+    );
+    Ok(())
+}
+
+fn pick_id(rng: &mut fastrand::Rng, ids: &[i64]) -> i64 {
+    ids[rng.usize(..ids.len())]
+}
@@ -1,9 +1,32 @@
-use ski::db::SkisDb;
+use ski::db::{self, SkisDb};
 use ski::error::Result;
 
-pub fn run() -> Result<()> {
+use crate::InitArgs;
+
+pub fn run(args: InitArgs) -> Result<()> {
+    if let Some(db_path) = db::db_path_from_env()? {
+        SkisDb::init_file(&db_path)?;
+        println!("Initialized empty SKIS database at {}", db_path.display());
+        return Ok(());
+    }
+
     let cwd = std::env::current_dir()?;
-    SkisDb::init(&cwd)?;
-    println!("Initialized empty SKIS repository in {}/.skis/", cwd.display());
+    let backup = if args.force {
+        SkisDb::init_force(&cwd)?.1
+    } else {
+        SkisDb::init(&cwd)?;
+        None
+    };
+
+    match backup {
+        Some(backup) => {
+            println!("Moved broken database to {}", backup.display());
+            println!("Reinitialized SKIS repository in {}/.skis/", cwd.display());
+        }
+        None => println!(
+            "Initialized empty SKIS repository in {}/.skis/",
+            cwd.display()
+        ),
+    }
     Ok(())
 }
@@ -1,18 +1,28 @@
-use std::io::Read;
+use std::collections::HashSet;
+use std::io::{IsTerminal, Read, Write};
 use std::str::FromStr;
 
+use chrono::Utc;
 use colored::Colorize;
+use serde::Serialize;
+use ski::config::TitlePrefixes;
 use ski::db::{self, SkisDb};
-use ski::error::Result;
+use ski::error::{Error, Result};
 use ski::models::{
-    Issue, IssueCreate, IssueFilter, IssueState, IssueType, IssueUpdate, IssueView, SortField,
-    SortOrder, StateReason,
+    with_time_format, CreateOpts, Issue, IssueCreate, IssueFilter, IssueState, IssueType,
+    IssueUpdate, IssueView, LabelView, LinkStatus, LinkType, StateFilter, StateReason, TimeFormat,
+};
+use ski::next::{rank_next, NextWeights};
+use ski::output::{
+    display_width, fit_to_width, format_issue_reference, format_relative_time, format_timestamp,
+    labels_that_fit, more_labels, project_fields, references_footer, render_issue, scan_references,
+    truncate_to_width, tsv_row, IssueField, OutputFormat, ReferenceFormat, ViewField,
 };
-use ski::output::format_timestamp;
 
 use crate::{
-    IssueCloseArgs, IssueCommentArgs, IssueCreateArgs, IssueDeleteArgs, IssueEditArgs,
-    IssueListArgs, IssueLinkArgs, IssueReopenArgs, IssueRestoreArgs, IssueUnlinkArgs,
+    IssueArchiveArgs, IssueBodyArgs, IssueCloseArgs, IssueCommentArgs, IssueCopyArgs,
+    IssueCreateArgs, IssueDeleteArgs, IssueEditArgs, IssueGetArgs, IssueLinkArgs, IssueListArgs,
+    IssueNextArgs, IssueReopenArgs, IssueRestoreArgs, IssueRetypeArgs, IssueUnlinkArgs,
     IssueViewArgs,
 };
 
@@ -52,24 +62,30 @@ fn format_label_colored(name: &str, color: Option<&str>) -> String {
     }
 }
 
+/// `--body` or `--body-file` value that reads the body from stdin
+const STDIN_BODY: &str = "-";
+
 /// Read body content from file or stdin (if path is "-")
 fn read_body_from_file(path: &str) -> Result<String> {
-    if path == "-" {
+    if path == STDIN_BODY {
         let mut content = String::new();
         std::io::stdin().read_to_string(&mut content)?;
         Ok(content)
     } else {
-        Ok(std::fs::read_to_string(path)?)
+        Ok(std::fs::read_to_string(ski::util::expand_path(path))?)
     }
 }
 
-/// Open $EDITOR to get content from user
-fn read_body_from_editor() -> Result<Option<String>> {
+/// Open $EDITOR to get content from user, starting from `initial`
+fn read_body_from_editor(initial: &str) -> Result<Option<String>> {
     let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
 
     // Create a temp file
     let temp_dir = std::env::temp_dir();
     let temp_path = temp_dir.join(format!("skis-{}.md", std::process::id()));
+    if !initial.is_empty() {
+        std::fs::write(&temp_path, initial)?;
+    }
 
     // Spawn editor
     let status = std::process::Command::new(&editor)
@@ -96,20 +112,35 @@ fn read_body_from_editor() -> Result<Option<String>> {
     Ok(None)
 }
 
-/// Resolve body from --body, --body-file, or --editor options
+/// Resolve body from --body, --body-file, or --editor options.
+/// `--body -` reads stdin, like `--body-file -`.
 fn resolve_body(
     body: Option<String>,
     body_file: Option<String>,
     editor: bool,
 ) -> Result<Option<String>> {
     match (body, body_file, editor) {
+        (Some(b), Some(f), _) if b == STDIN_BODY && f == STDIN_BODY => Err(Error::BodyStdinTwice),
+        (Some(b), _, _) if b == STDIN_BODY => Ok(Some(read_body_from_file(&b)?)),
         (Some(b), _, _) => Ok(Some(b)),
         (None, Some(path), _) => Ok(Some(read_body_from_file(&path)?)),
-        (None, None, true) => read_body_from_editor(),
+        (None, None, true) => read_body_from_editor(""),
         (None, None, false) => Ok(None),
     }
 }
 
+/// Label flag values with comma-separated names split apart, so
+/// `-l "bug, ui"` means the same as `-l bug -l ui`
+fn split_labels(labels: Vec<String>) -> Vec<String> {
+    labels
+        .iter()
+        .flat_map(|l| l.split(','))
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 pub fn create(args: IssueCreateArgs) -> Result<()> {
     let title = match args.title {
         Some(t) => t,
@@ -120,146 +151,445 @@ pub fn create(args: IssueCreateArgs) -> Result<()> {
     };
 
     let issue_type = IssueType::from_str(&args.issue_type)?;
-    let body = resolve_body(args.body, args.body_file, args.editor)?;
-
     let db = SkisDb::open()?;
+    // The editor starts from the type's template; --body and --body-file bypass it
+    let body = match (&args.body, &args.body_file, args.editor) {
+        (None, None, true) => read_body_from_editor(&db.body_template(issue_type)?)?,
+        _ => resolve_body(args.body, args.body_file, args.editor)?,
+    };
+
+    let labels = split_labels(args.labels);
+    for (input, stored) in db::label_case_mismatches(db.conn(), &labels)? {
+        eprintln!(
+            "warning: using existing label '{}' for input '{}'",
+            stored, input
+        );
+    }
+
     let create = IssueCreate {
         title,
         body,
         issue_type,
-        labels: args.labels,
+        labels,
+        points: args.points,
+    };
+
+    let config = db.config()?;
+    let opts = CreateOpts {
+        title_prefixes: if args.no_prefix {
+            TitlePrefixes::default()
+        } else {
+            config.title_prefix
+        },
+        body_format: config.body,
     };
 
-    let issue = db::create_issue(db.conn(), &create)?;
+    let issue = db.with_retry(|conn| db::create_issue_with(conn, &create, &opts))?;
+    if args.json {
+        return print_json(&issue_view(db.conn(), issue.id)?);
+    }
     println!("Created issue #{}", issue.id);
     Ok(())
 }
 
 pub fn list(args: IssueListArgs) -> Result<()> {
+    match args.color.to_lowercase().as_str() {
+        "auto" => {}
+        "always" => colored::control::set_override(true),
+        "never" => colored::control::set_override(false),
+        _ => return Err(Error::InvalidColorChoice(args.color)),
+    }
+    let format = crate::commands::output_format(args.format.as_deref(), args.json)?;
+    let fields = args
+        .fields
+        .as_deref()
+        .map(IssueField::parse_list)
+        .transpose()?;
+    let time_format = args
+        .time_format
+        .as_deref()
+        .map(TimeFormat::from_str)
+        .transpose()?
+        .unwrap_or_default();
     let db = SkisDb::open()?;
 
-    let state = match args.state.to_lowercase().as_str() {
-        "open" => Some(IssueState::Open),
-        "closed" => Some(IssueState::Closed),
-        "all" => None,
-        _ => {
-            eprintln!("error: invalid state '{}', must be open, closed, or all", args.state);
-            std::process::exit(1);
-        }
+    let config = db.config()?;
+    // `--ids` feeds `--stdin` pipelines, so it lists every match unless limited
+    let unlimited = args.ids && args.filter.limit.is_none();
+    let mut filter = if unlimited {
+        args.filter.to_bulk_filter(config.list.default_state)?
+    } else {
+        args.filter.to_filter(&config.list)?
     };
+    let max_limit = config.max_list_limit;
+    if filter.limit > max_limit && !args.count && !unlimited {
+        eprintln!(
+            "warning: --limit {} is above max_list_limit ({}); showing at most {}",
+            filter.limit, max_limit, max_limit
+        );
+        filter.limit = max_limit;
+    }
+    let limit = filter.limit;
+    if args.random {
+        // Shuffle every match, then keep --limit of them
+        filter.limit = i64::MAX as usize;
+    }
 
-    let issue_type = args
-        .issue_type
-        .map(|t| IssueType::from_str(&t))
-        .transpose()?;
-
-    let sort_by = match args.sort.to_lowercase().as_str() {
-        "updated" => SortField::Updated,
-        "created" => SortField::Created,
-        "id" => SortField::Id,
-        _ => {
-            eprintln!(
-                "error: invalid sort field '{}', must be updated, created, or id",
-                args.sort
-            );
-            std::process::exit(1);
-        }
-    };
+    if args.explain {
+        let (sql, params) = match &args.filter.search {
+            Some(query) => db::build_search_query(query, &filter),
+            None => db::build_list_query(&filter),
+        };
+        let params: Vec<_> = params.into_iter().map(sql_value_to_json).collect();
+        return print_json(&serde_json::json!({ "sql": sql, "params": params }));
+    }
 
-    let sort_order = match args.order.to_lowercase().as_str() {
-        "asc" => SortOrder::Asc,
-        "desc" => SortOrder::Desc,
-        _ => {
-            eprintln!(
-                "error: invalid sort order '{}', must be asc or desc",
-                args.order
-            );
-            std::process::exit(1);
-        }
-    };
+    if args.count {
+        let count = match &args.filter.search {
+            Some(query) => db::count_search_results(db.conn(), query, &filter)?,
+            None => db::count_issues(db.conn(), &filter)?,
+        };
+        println!("{}", count);
+        return Ok(());
+    }
 
-    let filter = IssueFilter {
-        state,
-        issue_type,
-        labels: args.labels,
-        include_deleted: args.deleted,
-        sort_by,
-        sort_order,
-        limit: args.limit,
-        offset: args.offset,
+    let mut issues = if let Some(query) = &args.filter.search {
+        db::search_issues_with_labels(db.conn(), query, &filter)?
+    } else {
+        db::list_issues_with_labels(db.conn(), &filter)?
     };
+    if args.random {
+        fastrand::shuffle(&mut issues);
+        issues.truncate(limit);
+    }
 
-    let issues = if let Some(query) = &args.search {
-        db::search_issues(db.conn(), query, &filter)?
+    // With no prefixes configured (or no --strip-prefixes) titles pass through
+    let prefixes = if args.strip_prefixes {
+        db.config()?.title_prefix
     } else {
-        db::list_issues(db.conn(), &filter)?
+        TitlePrefixes::default()
     };
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&issues)?);
+    if args.ids {
+        for issue in &issues {
+            println!("{}", issue.id);
+        }
+    } else if format == OutputFormat::Json {
+        let json = with_time_format(time_format, || {
+            if fields.is_none() && !args.with_relative {
+                return crate::commands::to_json(&issues, args.compact);
+            }
+            let rows = issues
+                .iter()
+                .map(|issue| {
+                    let mut row = serde_json::to_value(issue)?;
+                    if let Some(fields) = &fields {
+                        row = project_fields(&row, fields);
+                    }
+                    if args.with_relative {
+                        insert_relative_times(&mut row, issue);
+                    }
+                    Ok(row)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            crate::commands::to_json(&rows, args.compact)
+        })?;
+        println!("{}", json);
+    } else if format == OutputFormat::Tsv {
+        let fields = fields.as_deref().unwrap_or(IssueField::DEFAULT);
+        for issue in &issues {
+            let cells: Vec<String> = fields
+                .iter()
+                .map(|&field| tsv_cell(issue, field, &prefixes))
+                .collect();
+            let cells: Vec<&str> = cells.iter().map(String::as_str).collect();
+            println!("{}", tsv_row(&cells));
+        }
     } else if issues.is_empty() {
         println!("No issues found");
     } else {
         // Simple table output with colors
-        println!(
-            "{:<6} {:<8} {:<8} {:<20} {}",
-            "ID".bold(),
-            "TYPE".bold(),
-            "STATE".bold(),
-            "LABELS".bold(),
-            "TITLE".bold()
-        );
+        let default_fields = if args.filter.deleted || args.filter.only_deleted {
+            IssueField::DEFAULT_WITH_DELETED
+        } else {
+            IssueField::DEFAULT
+        };
+        let fields = fields.as_deref().unwrap_or(default_fields);
+        let columns = terminal_columns();
+        let widths = column_widths(fields, &issues);
+        let header: Vec<String> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, &field)| {
+                let width = column_width(&widths, i, columns).padding();
+                format!("{:<width$}", field.header().bold())
+            })
+            .collect();
+        println!("{}", header.join(" "));
         println!("{}", "-".repeat(80));
         for issue in &issues {
-            let labels = db::get_issue_labels(db.conn(), issue.id)?;
-            let label_str = if labels.is_empty() {
-                "-".dimmed().to_string()
-            } else {
-                labels
-                    .iter()
-                    .map(|l| format_label_colored(&l.name, l.color.as_deref()))
-                    .collect::<Vec<_>>()
-                    .join(",")
-            };
-            println!(
-                "{:<6} {:<8} {:<8} {:<20} {}",
-                format!("#{}", issue.id),
-                format_type_colored(issue.issue_type),
-                format_state_colored(issue.state),
-                label_str,
-                issue.title
-            );
+            let row: Vec<String> = fields
+                .iter()
+                .enumerate()
+                .map(|(i, &field)| {
+                    let width = column_width(&widths, i, columns);
+                    table_cell(issue, field, width, &prefixes, args.max_labels)
+                })
+                .collect();
+            let row = row.join(" ");
+            match issue.deleted_at {
+                Some(_) => println!("{}", row.dimmed()),
+                None => println!("{}", row),
+            }
         }
     }
 
     Ok(())
 }
 
+/// Narrowest the last table column gets, however little room `COLUMNS` leaves
+const MIN_LAST_COLUMN: usize = 10;
+
+/// Terminal width from `COLUMNS`, which limits the last table column
+fn terminal_columns() -> Option<usize> {
+    std::env::var("COLUMNS")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&n| n > 0)
+}
+
+/// How much room an `issue list` table cell gets, in display columns
+#[derive(Clone, Copy)]
+enum ColumnWidth {
+    /// Padded or cut to exactly this width
+    Fixed(usize),
+    /// The last column: never padded, cut only when it would pass this width
+    AtMost(usize),
+}
+
+impl ColumnWidth {
+    /// Width to pad the cell to
+    fn padding(self) -> usize {
+        match self {
+            ColumnWidth::Fixed(width) => width,
+            ColumnWidth::AtMost(_) => 0,
+        }
+    }
+
+    /// Widest the cell's text may be
+    fn limit(self) -> usize {
+        match self {
+            ColumnWidth::Fixed(width) | ColumnWidth::AtMost(width) => width,
+        }
+    }
+
+    /// Cut `text` to the column, padding it if the column is fixed
+    fn fit(self, text: &str) -> String {
+        match self {
+            ColumnWidth::Fixed(width) => fit_to_width(text, width),
+            ColumnWidth::AtMost(width) => truncate_to_width(text, width),
+        }
+    }
+}
+
+/// Widths of the `fields` columns for `issues`. The ID column fits the
+/// widest `#id` shown, so it stays aligned however large the numbers get.
+fn column_widths(fields: &[IssueField], issues: &[Issue]) -> Vec<usize> {
+    let id_width = issues
+        .iter()
+        .map(|issue| format!("#{}", issue.id).len())
+        .chain([IssueField::Id.header().len()])
+        .max()
+        .unwrap_or_default();
+    fields
+        .iter()
+        .map(|&field| match field {
+            IssueField::Id => id_width,
+            _ => field.width(),
+        })
+        .collect()
+}
+
+/// Width of column `i`; the last column takes whatever `columns` leaves
+fn column_width(widths: &[usize], i: usize, columns: Option<usize>) -> ColumnWidth {
+    if i + 1 < widths.len() {
+        return ColumnWidth::Fixed(widths[i]);
+    }
+    let used: usize = widths[..i].iter().map(|w| w + 1).sum();
+    let limit = columns.map_or(usize::MAX, |c| c.saturating_sub(used).max(MIN_LAST_COLUMN));
+    ColumnWidth::AtMost(limit)
+}
+
+/// One `issue list` table cell, fitted to `width`
+fn table_cell(
+    issue: &Issue,
+    field: IssueField,
+    width: ColumnWidth,
+    prefixes: &TitlePrefixes,
+    max_labels: usize,
+) -> String {
+    let pad = width.padding();
+    match field {
+        IssueField::Id => format!("{:<pad$}", format!("#{}", issue.id)),
+        IssueField::Type => format!("{:<pad$}", format_type_colored(issue.issue_type)),
+        IssueField::State => format!("{:<pad$}", format_state_colored(issue.state)),
+        IssueField::Labels => labels_cell(
+            issue.labels.as_deref().unwrap_or_default(),
+            width,
+            max_labels,
+        ),
+        IssueField::Updated => width.fit(&format_relative_time(issue.updated_at)),
+        IssueField::Deleted => match issue.deleted_at {
+            Some(_) => format!("{:<pad$}", "deleted".red()),
+            None => format!("{}{}", "-".dimmed(), " ".repeat(pad.saturating_sub(1))),
+        },
+        IssueField::Title => match issue.deleted_at {
+            Some(_) => {
+                let title = prefixes.strip(&issue.title);
+                let room = width.limit().saturating_sub(DELETED_SUFFIX.len());
+                width.fit(&format!(
+                    "{}{}",
+                    truncate_to_width(title, room),
+                    DELETED_SUFFIX
+                ))
+            }
+            None => width.fit(prefixes.strip(&issue.title)),
+        },
+    }
+}
+
+/// Appended to the title of a soft-deleted issue in the `issue list` table
+const DELETED_SUFFIX: &str = " [deleted]";
+
+/// Comma-separated colored labels, cut and padded by their visible text
+/// rather than by the length of the color codes. At most `max_labels` are
+/// shown, fewer if they would not fit, with a `+k more` note for the rest.
+fn labels_cell(labels: &[LabelView], width: ColumnWidth, max_labels: usize) -> String {
+    if labels.is_empty() {
+        let pad = width.padding().saturating_sub(1);
+        return format!("{}{}", "-".dimmed(), " ".repeat(pad));
+    }
+    let names: Vec<&str> = labels.iter().map(|l| l.name.as_str()).collect();
+    let count = labels_that_fit(&names, max_labels, width.limit());
+    let (labels, more) = (&labels[..count], more_labels(names.len() - count));
+    let plain = names[..count].join(",");
+    let room = width.limit().saturating_sub(display_width(&more));
+    let shown = truncate_to_width(&plain, room);
+    let kept = shown
+        .strip_suffix('…')
+        .filter(|_| shown != plain)
+        .unwrap_or(&shown)
+        .len();
+
+    let mut cell = Vec::new();
+    let mut start = 0;
+    for label in labels {
+        let end = (start + label.name.len()).min(kept);
+        if end <= start {
+            break;
+        }
+        cell.push(format_label_colored(
+            &plain[start..end],
+            label.color.as_deref(),
+        ));
+        start += label.name.len() + 1;
+    }
+    let mut cell = cell.join(",");
+    cell.push_str(&shown[kept..]);
+    cell.push_str(&more.dimmed().to_string());
+    let pad = width
+        .padding()
+        .saturating_sub(display_width(&shown) + display_width(&more));
+    cell.push_str(&" ".repeat(pad));
+    cell
+}
+
+/// One `issue list --format tsv` cell
+fn tsv_cell(issue: &Issue, field: IssueField, prefixes: &TitlePrefixes) -> String {
+    match field {
+        IssueField::Id => issue.id.to_string(),
+        IssueField::Type => issue.issue_type.to_string(),
+        IssueField::State => issue.state.to_string(),
+        IssueField::Labels => issue
+            .labels
+            .iter()
+            .flatten()
+            .map(|l| l.name.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+        IssueField::Updated => issue.updated_at.to_rfc3339(),
+        IssueField::Deleted => issue.deleted_at.map(|d| d.to_rfc3339()).unwrap_or_default(),
+        IssueField::Title => prefixes.strip(&issue.title).to_string(),
+    }
+}
+
+/// A bound SQL parameter as JSON, for `issue list --explain`
+fn sql_value_to_json(value: rusqlite::types::Value) -> serde_json::Value {
+    use rusqlite::types::Value;
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(n) => n.into(),
+        Value::Real(f) => f.into(),
+        Value::Text(s) => s.into(),
+        Value::Blob(b) => b.into(),
+    }
+}
+
+pub fn next(args: IssueNextArgs) -> Result<()> {
+    let issue_type = args
+        .issue_type
+        .as_deref()
+        .map(IssueType::from_str)
+        .transpose()?;
+    let db = SkisDb::open()?;
+
+    let filter = IssueFilter {
+        state: Some(IssueState::Open),
+        issue_type,
+        labels: args.labels,
+        strict_labels: true,
+        limit: i64::MAX as usize,
+        ..Default::default()
+    };
+    let issues = db::list_issues_with_labels(db.conn(), &filter)?;
+    let blocked = db::blocked_issue_ids(db.conn())?;
+    let ranked = rank_next(&issues, &blocked, Utc::now(), &NextWeights::default());
+
+    match ranked.first() {
+        Some(issue) if args.json => print_json(&issue_view(db.conn(), issue.id)?),
+        Some(issue) => print_issue_view(db.conn(), issue, false),
+        None if args.json => print_json(&None::<IssueView>),
+        None => {
+            println!("Nothing to do 🎉");
+            Ok(())
+        }
+    }
+}
+
 pub fn view(args: IssueViewArgs) -> Result<()> {
+    if args.open {
+        return crate::commands::browse::open_issue(args.number, args.comments);
+    }
+
     let db = SkisDb::open()?;
-    let issue = db::get_issue(db.conn(), args.number)?
+    let issue = db::get_issue_with_labels(db.conn(), args.number)?
         .ok_or_else(|| ski::error::Error::IssueNotFound(args.number))?;
 
     if args.json {
         // Build enriched view with labels and linked issues
-        let labels = db::get_issue_labels(db.conn(), issue.id)?;
         let linked_issues = db::get_linked_issues_with_titles(db.conn(), issue.id)?;
 
-        let view = IssueView {
-            id: issue.id,
-            title: issue.title.clone(),
-            body: issue.body.clone(),
-            issue_type: issue.issue_type,
-            state: issue.state,
-            state_reason: issue.state_reason,
-            labels: labels.into_iter().map(Into::into).collect(),
-            linked_issues,
-            created_at: issue.created_at,
-            updated_at: issue.updated_at,
-            closed_at: issue.closed_at,
-            deleted_at: issue.deleted_at,
-        };
-        println!("{}", serde_json::to_string_pretty(&view)?);
+        let view = IssueView::new(issue, linked_issues);
+        println!("{}", crate::commands::to_json(&view, args.compact)?);
+    } else if args.markdown {
+        let labels: Vec<String> = db::get_issue_labels(db.conn(), issue.id)?
+            .into_iter()
+            .map(|l| l.name)
+            .collect();
+        let referenced = referenced_issues(db.conn(), &issue)?;
+        print!("{}", render_issue(&issue, &labels, &referenced));
     } else {
         print_issue_view(db.conn(), &issue, args.comments)?;
     }
@@ -267,16 +597,85 @@ pub fn view(args: IssueViewArgs) -> Result<()> {
     Ok(())
 }
 
-fn print_issue_view(
+/// Result of `issue body --json`
+#[derive(Serialize)]
+struct IssueBody {
+    id: i64,
+    body: Option<String>,
+}
+
+pub fn body(args: IssueBodyArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let issue = db::get_issue(db.conn(), args.number)?.ok_or(Error::IssueNotFound(args.number))?;
+
+    if args.json {
+        return print_json(&IssueBody {
+            id: issue.id,
+            body: issue.body,
+        });
+    }
+    // Raw text for pipes: nothing at all for an empty body
+    if let Some(body) = issue.body.filter(|b| !b.is_empty()) {
+        if body.ends_with('\n') {
+            print!("{}", body);
+        } else {
+            println!("{}", body);
+        }
+    }
+    Ok(())
+}
+
+/// Exit status of `issue get` when the issue does not exist
+const GET_NOT_FOUND_EXIT: i32 = 3;
+
+pub fn get(args: IssueGetArgs) -> Result<()> {
+    let field = ViewField::from_str(&args.field)?;
+    let db = SkisDb::open()?;
+    match issue_view(db.conn(), args.number) {
+        Ok(view) => {
+            println!("{}", field.value(&view));
+            Ok(())
+        }
+        Err(e @ Error::IssueNotFound(_)) => {
+            eprintln!("error: {}", e);
+            std::process::exit(GET_NOT_FOUND_EXIT);
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn print_issue_view(
     conn: &rusqlite::Connection,
     issue: &Issue,
     show_comments: bool,
 ) -> Result<()> {
-    println!(
-        "{} {}",
-        format!("#{}", issue.id).bold(),
-        issue.title.bold()
-    );
+    println!("{} {}", format!("#{}", issue.id).bold(), issue.title.bold());
+    if issue.deleted_at.is_some() {
+        let reason = issue
+            .deleted_reason
+            .as_ref()
+            .map(|r| format!(" ({})", r))
+            .unwrap_or_default();
+        println!(
+            "{}",
+            format!(
+                "This issue is deleted{}. Restore it with `skis issue restore {}`.",
+                reason, issue.id
+            )
+            .red()
+            .bold()
+        );
+    }
+    if issue.archived_at.is_some() {
+        println!(
+            "{}",
+            format!(
+                "This issue is archived. Unarchive it with `skis issue unarchive {}`.",
+                issue.id
+            )
+            .yellow()
+        );
+    }
     println!(
         "Type: {}  State: {}",
         format_type_colored(issue.issue_type),
@@ -285,6 +684,9 @@ fn print_issue_view(
     if let Some(reason) = &issue.state_reason {
         println!("Closed: {}", reason);
     }
+    if let Some(points) = issue.points {
+        println!("Points: {}", points);
+    }
     println!("Created: {}", format_timestamp(issue.created_at).dimmed());
     println!("Updated: {}", format_timestamp(issue.updated_at).dimmed());
 
@@ -298,26 +700,71 @@ fn print_issue_view(
         println!("Labels: {}", label_strs.join(", "));
     }
 
-    // Show linked issues
-    let linked = db::get_linked_issues(conn, issue.id)?;
+    // Show linked issues, marking any that have since been deleted
+    let linked = db::get_linked_issues_with_titles(conn, issue.id)?;
     if !linked.is_empty() {
-        let linked_str: Vec<String> = linked.iter().map(|id| format!("#{}", id)).collect();
-        println!("Linked: {}", linked_str.join(", "));
+        let linked_str: Vec<String> = linked
+            .iter()
+            .map(|l| {
+                let mut s = format!("#{}", l.id);
+                if l.link_type != LinkType::Relates {
+                    s.push_str(&format!(" ({})", l.link_type.to_string().replace('_', " ")));
+                }
+                if l.deleted {
+                    s.push_str(&format!(" {}", "(deleted)".red()));
+                }
+                if let Some(note) = &l.note {
+                    s.push_str(&format!(" — {}", note.dimmed()));
+                }
+                s
+            })
+            .collect();
+        // Notes may contain commas, so give each link its own line
+        if linked.iter().any(|l| l.note.is_some()) {
+            println!("Linked:");
+            for s in &linked_str {
+                println!("  {}", s);
+            }
+        } else {
+            println!("Linked: {}", linked_str.join(", "));
+        }
     }
 
+    // Bodies saved with normalization turned off may still carry CRs
     if let Some(body) = &issue.body {
-        println!("\n{}", body);
+        println!("\n{}", ski::util::normalize_line_endings(body));
+    }
+    let referenced = referenced_issues(conn, issue)?;
+    if let Some(footer) = references_footer(&referenced, ReferenceFormat::Plain) {
+        println!("\n{}", footer.dimmed());
     }
 
-    // Show comments if requested
+    // Show comments if requested, pinned ones first
     if show_comments {
-        let comments = db::get_comments(conn, issue.id)?;
+        let mut comments = db::get_comments(conn, issue.id)?;
+        comments.sort_by_key(|c| !c.pinned);
         if !comments.is_empty() {
             println!("\nComments:");
             println!("{}", "-".repeat(40));
             for comment in comments {
-                println!("[{}]", format_timestamp(comment.created_at));
-                println!("{}", comment.body);
+                let pin = if comment.pinned {
+                    format!(" {}", "pinned".yellow())
+                } else {
+                    String::new()
+                };
+                let edited = if comment.edited {
+                    format!(" {}", "(edited)".dimmed())
+                } else {
+                    String::new()
+                };
+                println!(
+                    "{} · #{}{}{}",
+                    format_relative_time(comment.created_at).dimmed(),
+                    comment.id,
+                    pin,
+                    edited
+                );
+                println!("{}", ski::util::normalize_line_endings(&comment.body));
                 println!();
             }
         }
@@ -326,84 +773,482 @@ fn print_issue_view(
     Ok(())
 }
 
-pub fn edit(args: IssueEditArgs) -> Result<()> {
+/// Add `created_relative` and `updated_relative` to an issue's JSON, for `--with-relative`
+fn insert_relative_times(row: &mut serde_json::Value, issue: &Issue) {
+    if let Some(row) = row.as_object_mut() {
+        row.insert(
+            "created_relative".to_string(),
+            format_relative_time(issue.created_at).into(),
+        );
+        row.insert(
+            "updated_relative".to_string(),
+            format_relative_time(issue.updated_at).into(),
+        );
+    }
+}
+
+/// The existing issues that `issue`'s body mentions as `#N`, other than itself
+fn referenced_issues(conn: &rusqlite::Connection, issue: &Issue) -> Result<Vec<Issue>> {
+    let mut ids = scan_references(issue.body.as_deref().unwrap_or_default());
+    ids.retain(|&id| id != issue.id);
+    db::get_issues(conn, &ids)
+}
+
+pub fn copy(args: IssueCopyArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let issue = db::get_issue(db.conn(), args.number)?
+        .ok_or_else(|| ski::error::Error::IssueNotFound(args.number))?;
+
+    let format = if args.markdown {
+        ReferenceFormat::Markdown
+    } else {
+        ReferenceFormat::Plain
+    };
+    let reference = format_issue_reference(&issue, format);
+    println!("{}", reference);
+
+    if args.clipboard {
+        let copied = arboard::Clipboard::new().and_then(|mut cb| cb.set_text(reference.as_str()));
+        if let Err(e) = copied {
+            eprintln!("warning: could not copy to clipboard: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn edit(mut args: IssueEditArgs) -> Result<()> {
     let db = SkisDb::open()?;
 
+    // With no change flags, ask what to edit rather than doing a no-op update
+    let mut picked_body = None;
+    if !has_edit_flags(&args) {
+        if !std::io::stdin().is_terminal() {
+            return Err(Error::NothingToEdit);
+        }
+        let issue =
+            db::get_issue(db.conn(), args.number)?.ok_or(Error::IssueNotFound(args.number))?;
+        picked_body = pick_edit(&issue, &mut args)?;
+        if picked_body.is_none() && !has_edit_flags(&args) {
+            println!("No changes to issue #{}", args.number);
+            return Ok(());
+        }
+    }
+
     let issue_type = args
         .issue_type
         .map(|t| IssueType::from_str(&t))
         .transpose()?;
 
-    let body = resolve_body(args.body, args.body_file, args.editor)?;
+    let body = match picked_body {
+        Some(body) => Some(body),
+        None => resolve_body(args.body, args.body_file, args.editor)?,
+    };
 
     let update = IssueUpdate {
         title: args.title,
         body,
         issue_type,
+        points: if args.clear_points {
+            Some(None)
+        } else {
+            args.points.map(Some)
+        },
+        expected_version: None,
     };
 
-    let issue = db::update_issue(db.conn(), args.number, &update)?;
+    let format = db.config()?.body;
+    let issue = db.with_retry(|conn| db::update_issue_with(conn, args.number, &update, &format))?;
 
     // Handle label additions
-    for label in &args.add_labels {
-        db::add_label_to_issue(db.conn(), args.number, label)?;
+    for label in &split_labels(args.add_labels) {
+        db.with_retry(|conn| db::add_label_to_issue(conn, args.number, label, false))?;
     }
 
     // Handle label removals
-    for label in &args.remove_labels {
-        db::remove_label_from_issue(db.conn(), args.number, label)?;
+    for label in &split_labels(args.remove_labels) {
+        db.with_retry(|conn| db::remove_label_from_issue(conn, args.number, label, false))?;
     }
 
+    if args.json {
+        return print_json(&issue_view(db.conn(), issue.id)?);
+    }
     println!("Updated issue #{}", issue.id);
     Ok(())
 }
 
+/// Whether any flag that changes the issue was passed
+fn has_edit_flags(args: &IssueEditArgs) -> bool {
+    args.title.is_some()
+        || args.body.is_some()
+        || args.body_file.is_some()
+        || args.editor
+        || args.issue_type.is_some()
+        || !args.add_labels.is_empty()
+        || !args.remove_labels.is_empty()
+        || args.points.is_some()
+        || args.clear_points
+}
+
+/// Print `message` to stderr and read one trimmed line from stdin
+fn prompt(message: &str) -> Result<String> {
+    eprint!("{}", message);
+    std::io::stderr().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+/// Ask which field of `issue` to edit and fill the matching flag in `args`.
+/// A new body is returned rather than stored, so it isn't re-read from a file.
+fn pick_edit(issue: &Issue, args: &mut IssueEditArgs) -> Result<Option<String>> {
+    eprintln!("Edit issue #{}: {}", issue.id, issue.title);
+    eprintln!("  1) title\n  2) body\n  3) type\n  4) labels");
+    match prompt("Field to edit [1-4]: ")?.to_lowercase().as_str() {
+        "1" | "title" => {
+            let title = prompt(&format!("New title [{}]: ", issue.title))?;
+            args.title = Some(title).filter(|t| !t.is_empty() && *t != issue.title);
+        }
+        "2" | "body" => {
+            let current = issue.body.as_deref().unwrap_or("");
+            return Ok(read_body_from_editor(current)?.filter(|b| b != current.trim()));
+        }
+        "3" | "type" => {
+            let issue_type = prompt(&format!(
+                "Type (epic, task, bug, request) [{}]: ",
+                issue.issue_type
+            ))?;
+            args.issue_type = Some(issue_type).filter(|t| !t.is_empty());
+        }
+        "4" | "labels" => {
+            for change in prompt("Labels (+name to add, -name to remove): ")?.split_whitespace() {
+                match change.strip_prefix('-') {
+                    Some(name) => args.remove_labels.push(name.to_string()),
+                    None => args
+                        .add_labels
+                        .push(change.trim_start_matches('+').to_string()),
+                }
+            }
+        }
+        "" => {}
+        other => eprintln!("Unknown field '{}'", other),
+    }
+    Ok(None)
+}
+
+/// Issue numbers to act on: the positional numbers, or with `--stdin` the
+/// whitespace-separated numbers read from stdin (a leading `#` is allowed)
+fn target_numbers(numbers: Vec<i64>, from_stdin: bool) -> Result<Vec<i64>> {
+    if !from_stdin {
+        return Ok(numbers);
+    }
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    input
+        .split_whitespace()
+        .map(|s| {
+            s.trim_start_matches('#')
+                .parse()
+                .map_err(|_| Error::InvalidIssueNumber(s.to_string()))
+        })
+        .collect()
+}
+
+/// Issue numbers selected with `--matching`: every issue `filter` matches
+/// (searching for `search` if given), in the filter's sort order
+fn matching_numbers(
+    conn: &rusqlite::Connection,
+    search: Option<&str>,
+    filter: &IssueFilter,
+) -> Result<Vec<i64>> {
+    let issues = match search {
+        Some(query) => db::search_issues(conn, query, filter)?,
+        None => db::list_issues(conn, filter)?,
+    };
+    Ok(issues.into_iter().map(|i| i.id).collect())
+}
+
+/// Run `op` on each issue. A single issue fails as usual; for several, each
+/// failure is reported and the rest still run. When all of several succeed,
+/// `summary` (e.g. "Closed") is followed by the count, as in "Closed 3 issues".
+fn for_each_issue(
+    numbers: &[i64],
+    summary: Option<&str>,
+    mut op: impl FnMut(i64) -> Result<()>,
+) -> Result<()> {
+    if let [number] = numbers {
+        return op(*number);
+    }
+
+    let mut failed = 0;
+    for &number in numbers {
+        if let Err(e) = op(number) {
+            eprintln!("error: {}", e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(Error::BulkFailed(failed, numbers.len()));
+    }
+    if let (Some(verb), false) = (summary, numbers.is_empty()) {
+        println!("{} {} issues", verb, numbers.len());
+    }
+    Ok(())
+}
+
+/// The issue as `issue view --json` shows it: with labels and linked issues
+fn issue_view(conn: &rusqlite::Connection, number: i64) -> Result<IssueView> {
+    let issue = db::get_issue_with_labels(conn, number)?.ok_or(Error::IssueNotFound(number))?;
+    let linked_issues = db::get_linked_issues_with_titles(conn, issue.id)?;
+    Ok(IssueView::new(issue, linked_issues))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print the results of a `--json` mutation: an array for a `batch` (several
+/// numbers, or `--stdin`), otherwise the single object
+fn print_json_results<T: Serialize>(results: &[T], batch: bool) -> Result<()> {
+    match results {
+        [result] if !batch => print_json(result),
+        [] if !batch => Ok(()),
+        _ => print_json(&results),
+    }
+}
+
+/// Result of a mutation that leaves nothing to show, e.g. `{"ok": true, "id": 5}`
+#[derive(Serialize)]
+struct Ack {
+    ok: bool,
+    id: i64,
+}
+
+pub fn retype(args: IssueRetypeArgs) -> Result<()> {
+    let issue_type = IssueType::from_str(&args.issue_type)?;
+    let db = SkisDb::open()?;
+
+    let mut numbers = args.numbers;
+    let mut seen = HashSet::new();
+    numbers.retain(|n| seen.insert(*n));
+    let issues = db::get_issues(db.conn(), &numbers)?;
+    if let Some(&missing) = numbers.iter().find(|n| !issues.iter().any(|i| i.id == **n)) {
+        return Err(Error::IssueNotFound(missing));
+    }
+
+    // Only touch issues whose type changes, so the rest keep their update time
+    let changing: Vec<i64> = issues
+        .iter()
+        .filter(|i| i.issue_type != issue_type)
+        .map(|i| i.id)
+        .collect();
+    let update = IssueUpdate {
+        issue_type: Some(issue_type),
+        ..Default::default()
+    };
+    db.with_retry(|conn| db::update_issues(conn, &changing, &update))?;
+
+    for issue in &issues {
+        if issue.issue_type == issue_type {
+            println!("Issue #{} already has type {}", issue.id, issue_type);
+        } else {
+            println!(
+                "Changed #{} type: {} → {}",
+                issue.id, issue.issue_type, issue_type
+            );
+        }
+    }
+    Ok(())
+}
+
 pub fn close(args: IssueCloseArgs) -> Result<()> {
     let db = SkisDb::open()?;
+    let numbers = if args.matching {
+        let filter = args.filter.to_bulk_filter(StateFilter::Open)?;
+        matching_numbers(db.conn(), args.filter.search.as_deref(), &filter)?
+    } else {
+        target_numbers(args.numbers, args.stdin)?
+    };
     let reason = StateReason::from_str(&args.reason)?;
-    let issue = db::close_issue_with_comment(
-        db.conn(),
-        args.number,
-        reason,
-        args.comment.as_deref(),
-    )?;
-
-    println!("Closed issue #{} as {}", issue.id, args.reason);
-    Ok(())
+    let format = db.config()?.body;
+    let mut views = Vec::new();
+    let result = for_each_issue(&numbers, (!args.json).then_some("Closed"), |number| {
+        let issue = db.with_retry(|conn| {
+            db::close_issue_with_comment(conn, number, reason, args.comment.as_deref(), &format)
+        })?;
+        if args.json {
+            views.push(issue_view(db.conn(), issue.id)?);
+        } else {
+            println!("Closed issue #{} as {}", issue.id, args.reason);
+        }
+        Ok(())
+    });
+    if args.json {
+        print_json_results(&views, args.stdin || args.matching || numbers.len() > 1)?;
+    }
+    result
 }
 
 pub fn reopen(args: IssueReopenArgs) -> Result<()> {
     let db = SkisDb::open()?;
-    let issue = db::reopen_issue(db.conn(), args.number)?;
-    println!("Reopened issue #{}", issue.id);
-    Ok(())
+    let numbers = if args.matching {
+        let filter = args.filter.to_bulk_filter(StateFilter::Closed)?;
+        matching_numbers(db.conn(), args.filter.search.as_deref(), &filter)?
+    } else {
+        target_numbers(args.numbers, args.stdin)?
+    };
+    let mut views = Vec::new();
+    let result = for_each_issue(&numbers, (!args.json).then_some("Reopened"), |number| {
+        let issue = db.with_retry(|conn| db::reopen_issue(conn, number))?;
+        if args.json {
+            views.push(issue_view(db.conn(), issue.id)?);
+        } else {
+            println!("Reopened issue #{}", issue.id);
+        }
+        Ok(())
+    });
+    if args.json {
+        print_json_results(&views, args.stdin || args.matching || numbers.len() > 1)?;
+    }
+    result
 }
 
 pub fn delete(args: IssueDeleteArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let numbers = if args.matching {
+        let filter = args.filter.to_bulk_filter(StateFilter::Open)?;
+        matching_numbers(db.conn(), args.filter.search.as_deref(), &filter)?
+    } else {
+        target_numbers(args.numbers, args.stdin)?
+    };
+    let batch = args.stdin || args.matching || numbers.len() > 1;
     if !args.yes {
-        eprint!("Delete issue #{}? [y/N] ", args.number);
+        let targets: Vec<String> = numbers.iter().map(|n| format!("#{}", n)).collect();
+        let noun = if batch { "issues" } else { "issue" };
+        eprint!("Delete {} {}? [y/N] ", noun, targets.join(", "));
         let mut input = String::new();
         std::io::stdin().read_line(&mut input)?;
         if !input.trim().eq_ignore_ascii_case("y") {
+            if args.json {
+                let acks: Vec<Ack> = numbers.iter().map(|&id| Ack { ok: false, id }).collect();
+                return print_json_results(&acks, batch);
+            }
             println!("Cancelled");
             return Ok(());
         }
     }
 
-    let db = SkisDb::open()?;
-    db::delete_issue(db.conn(), args.number)?;
-    println!("Deleted issue #{}", args.number);
-    Ok(())
+    let mut acks = Vec::new();
+    let result = for_each_issue(&numbers, (!args.json).then_some("Deleted"), |number| {
+        db.with_retry(|conn| db::delete_issue_with_reason(conn, number, args.reason.as_deref()))?;
+        if args.json {
+            acks.push(Ack {
+                ok: true,
+                id: number,
+            });
+        } else {
+            println!("Deleted issue #{}", number);
+        }
+        Ok(())
+    });
+    if args.json {
+        print_json_results(&acks, batch)?;
+    }
+    result
 }
 
 pub fn restore(args: IssueRestoreArgs) -> Result<()> {
     let db = SkisDb::open()?;
-    let issue = db::restore_issue(db.conn(), args.number)?;
-    println!("Restored issue #{}", issue.id);
-    Ok(())
+    let numbers = if args.matching {
+        let mut filter = args.filter.to_bulk_filter(StateFilter::All)?;
+        filter.only_deleted = true;
+        matching_numbers(db.conn(), args.filter.search.as_deref(), &filter)?
+    } else {
+        target_numbers(args.numbers, args.stdin)?
+    };
+    let mut views = Vec::new();
+    let result = for_each_issue(&numbers, (!args.json).then_some("Restored"), |number| {
+        let issue = db.with_retry(|conn| db::restore_issue(conn, number))?;
+        if args.json {
+            views.push(issue_view(db.conn(), issue.id)?);
+        } else {
+            println!("Restored issue #{}", issue.id);
+        }
+        Ok(())
+    });
+    if args.json {
+        print_json_results(&views, args.stdin || args.matching || numbers.len() > 1)?;
+    }
+    result
+}
+
+pub fn archive(args: IssueArchiveArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let mut views = Vec::new();
+    let result = for_each_issue(
+        &args.numbers,
+        (!args.json).then_some("Archived"),
+        |number| {
+            let issue = db.with_retry(|conn| db::archive_issue(conn, number))?;
+            if args.json {
+                views.push(issue_view(db.conn(), issue.id)?);
+            } else {
+                println!("Archived issue #{}", issue.id);
+            }
+            Ok(())
+        },
+    );
+    if args.json {
+        print_json_results(&views, args.numbers.len() > 1)?;
+    }
+    result
+}
+
+pub fn unarchive(args: IssueArchiveArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let mut views = Vec::new();
+    let result = for_each_issue(
+        &args.numbers,
+        (!args.json).then_some("Unarchived"),
+        |number| {
+            let issue = db.with_retry(|conn| db::unarchive_issue(conn, number))?;
+            if args.json {
+                views.push(issue_view(db.conn(), issue.id)?);
+            } else {
+                println!("Unarchived issue #{}", issue.id);
+            }
+            Ok(())
+        },
+    );
+    if args.json {
+        print_json_results(&views, args.numbers.len() > 1)?;
+    }
+    result
 }
 
 pub fn comment(args: IssueCommentArgs) -> Result<()> {
+    if let Some((comment_id, pinned)) = args
+        .pin
+        .map(|id| (id, true))
+        .or(args.unpin.map(|id| (id, false)))
+    {
+        let db = SkisDb::open()?;
+        let comment = db.with_retry(|conn| db::set_comment_pinned(conn, comment_id, pinned))?;
+        if args.json {
+            return print_json(&comment);
+        }
+        let action = if pinned { "Pinned" } else { "Unpinned" };
+        println!(
+            "{} comment #{} on issue #{}",
+            action, comment.id, comment.issue_id
+        );
+        return Ok(());
+    }
+
+    // clap requires the issue number whenever --pin/--unpin are absent
+    let number = args.number.expect("issue number");
     let body = resolve_body(args.body, args.body_file, args.editor)?;
     let body = match body {
         Some(b) => b,
@@ -414,21 +1259,111 @@ pub fn comment(args: IssueCommentArgs) -> Result<()> {
     };
 
     let db = SkisDb::open()?;
-    let comment = db::add_comment(db.conn(), args.number, &body)?;
-    println!("Added comment #{} to issue #{}", comment.id, args.number);
+    let format = db.config()?.body;
+    let comment =
+        db.with_retry(|conn| db::add_comment_with(conn, number, &body, &format, args.force))?;
+    if args.json {
+        return print_json(&comment);
+    }
+    println!("Added comment #{} to issue #{}", comment.id, number);
     Ok(())
 }
 
 pub fn link(args: IssueLinkArgs) -> Result<()> {
+    let link_type = LinkType::from_str(&args.link_type)?;
     let db = SkisDb::open()?;
-    db::add_link(db.conn(), args.issue_a, args.issue_b)?;
-    println!("Linked issue #{} and #{}", args.issue_a, args.issue_b);
+
+    if let Some(issue_b) = args.issue_b {
+        db.with_retry(|conn| {
+            db::add_link_with_note(
+                conn,
+                args.issue_a,
+                issue_b,
+                link_type,
+                args.note.as_deref(),
+                args.force,
+            )
+        })?;
+        if args.json {
+            return print_json(&issue_view(db.conn(), args.issue_a)?);
+        }
+        println!("Linked issue #{} and #{}", args.issue_a, issue_b);
+        return Ok(());
+    }
+
+    let results = db.with_retry(|conn| {
+        db::add_links(
+            conn,
+            args.issue_a,
+            &args.to,
+            link_type,
+            args.note.as_deref(),
+            args.force,
+        )
+    })?;
+    if args.json {
+        return print_json(&issue_view(db.conn(), args.issue_a)?);
+    }
+    println!(
+        "{}",
+        summarize_links(
+            &format!("Linked #{} to", args.issue_a),
+            &results,
+            "already linked"
+        )
+    );
     Ok(())
 }
 
 pub fn unlink(args: IssueUnlinkArgs) -> Result<()> {
     let db = SkisDb::open()?;
-    db::remove_link(db.conn(), args.issue_a, args.issue_b)?;
-    println!("Unlinked issue #{} and #{}", args.issue_a, args.issue_b);
+
+    if let Some(issue_b) = args.issue_b {
+        db.with_retry(|conn| db::remove_link(conn, args.issue_a, issue_b))?;
+        if args.json {
+            return print_json(&issue_view(db.conn(), args.issue_a)?);
+        }
+        println!("Unlinked issue #{} and #{}", args.issue_a, issue_b);
+        return Ok(());
+    }
+
+    let results = db.with_retry(|conn| db::remove_links(conn, args.issue_a, &args.to))?;
+    if args.json {
+        return print_json(&issue_view(db.conn(), args.issue_a)?);
+    }
+    println!(
+        "{}",
+        summarize_links(
+            &format!("Unlinked #{} from", args.issue_a),
+            &results,
+            "not linked"
+        )
+    );
     Ok(())
 }
+
+/// Summarize a batch link/unlink, e.g. "Linked #5 to #7, #8; skipped #9 (already linked)"
+fn summarize_links(action: &str, results: &[(i64, LinkStatus)], skip_reason: &str) -> String {
+    let (done, skipped): (Vec<_>, Vec<_>) = results
+        .iter()
+        .partition(|(_, status)| matches!(status, LinkStatus::Linked | LinkStatus::Unlinked));
+    let ids = |items: &[&(i64, LinkStatus)]| {
+        items
+            .iter()
+            .map(|(id, _)| format!("#{}", id))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match (done.is_empty(), skipped.is_empty()) {
+        (false, true) => format!("{} {}", action, ids(&done)),
+        (true, _) => format!("Skipped {} ({})", ids(&skipped), skip_reason),
+        (false, false) => format!(
+            "{} {}; skipped {} ({})",
+            action,
+            ids(&done),
+            ids(&skipped),
+            skip_reason
+        ),
+    }
+}
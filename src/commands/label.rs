@@ -1,18 +1,123 @@
+use std::str::FromStr;
+
+use regex::Regex;
+use serde::Serialize;
 use ski::db::{self, SkisDb};
-use ski::error::Result;
+use ski::error::{Error, Result};
+use ski::models::{plan_label_renames, Label, LabelRename, LabelSort, LabelUsage};
+use ski::output::{tsv_row, OutputFormat};
 
-use crate::{LabelCreateArgs, LabelDeleteArgs, LabelListArgs};
+use crate::{
+    LabelCreateArgs, LabelDeleteArgs, LabelFindArgs, LabelListArgs, LabelPruneArgs,
+    LabelRenameBulkArgs,
+};
 
 pub fn list(args: LabelListArgs) -> Result<()> {
+    let format = crate::commands::output_format(args.format.as_deref(), args.json)?;
+    let sort = args
+        .sort
+        .as_deref()
+        .map(LabelSort::from_str)
+        .transpose()?
+        .unwrap_or_default();
     let db = SkisDb::open()?;
-    let labels = db::list_labels(db.conn())?;
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&labels)?);
+    if sort == LabelSort::Usage {
+        // Alphabetical within equal counts, as the query returns them by name
+        let mut usages = db::list_labels_with_usage(db.conn())?;
+        usages.sort_by_key(|u| std::cmp::Reverse(u.total_count));
+        return print_label_usage(&usages, format, args.compact);
+    }
+
+    if format == OutputFormat::Tsv {
+        for usage in db::list_labels_with_usage(db.conn())? {
+            println!(
+                "{}",
+                tsv_row(&[
+                    &usage.label.name,
+                    usage.label.color.as_deref().unwrap_or(""),
+                    usage.label.description.as_deref().unwrap_or(""),
+                    &usage.open_count.to_string(),
+                    &usage.total_count.to_string(),
+                ])
+            );
+        }
+        return Ok(());
+    }
+
+    print_labels(&db::list_labels(db.conn())?, format, args.compact)
+}
+
+/// Print labels with their issue counts, for `label list --sort usage`
+fn print_label_usage(usages: &[LabelUsage], format: OutputFormat, compact: bool) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", crate::commands::to_json(usages, compact)?),
+        OutputFormat::Tsv => {
+            for usage in usages {
+                println!(
+                    "{}",
+                    tsv_row(&[
+                        &usage.label.name,
+                        usage.label.color.as_deref().unwrap_or(""),
+                        usage.label.description.as_deref().unwrap_or(""),
+                        &usage.open_count.to_string(),
+                        &usage.total_count.to_string(),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table if usages.is_empty() => println!("No labels found"),
+        OutputFormat::Table => {
+            println!(
+                "{:<20} {:<10} {:>6} {:>6}  DESCRIPTION",
+                "NAME", "COLOR", "OPEN", "TOTAL"
+            );
+            println!("{}", "-".repeat(60));
+            for usage in usages {
+                println!(
+                    "{:<20} {:<10} {:>6} {:>6}  {}",
+                    usage.label.name,
+                    usage.label.color.as_deref().unwrap_or("-"),
+                    usage.open_count,
+                    usage.total_count,
+                    usage.label.description.as_deref().unwrap_or("")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn find(args: LabelFindArgs) -> Result<()> {
+    let format = crate::commands::output_format(args.format.as_deref(), args.json)?;
+    let db = SkisDb::open()?;
+    let labels = db::find_labels(db.conn(), &args.text)?;
+
+    if format == OutputFormat::Tsv {
+        for label in &labels {
+            println!(
+                "{}",
+                tsv_row(&[
+                    &label.name,
+                    label.color.as_deref().unwrap_or(""),
+                    label.description.as_deref().unwrap_or(""),
+                ])
+            );
+        }
+        return Ok(());
+    }
+
+    print_labels(&labels, format, args.compact)
+}
+
+/// Print labels as JSON or a table
+fn print_labels(labels: &[Label], format: OutputFormat, compact: bool) -> Result<()> {
+    if format == OutputFormat::Json {
+        println!("{}", crate::commands::to_json(labels, compact)?);
     } else if labels.is_empty() {
         println!("No labels found");
     } else {
-        println!("{:<20} {:<10} {}", "NAME", "COLOR", "DESCRIPTION");
+        println!("{:<20} {:<10} DESCRIPTION", "NAME", "COLOR");
         println!("{}", "-".repeat(60));
         for label in labels {
             println!(
@@ -29,12 +134,14 @@ pub fn list(args: LabelListArgs) -> Result<()> {
 
 pub fn create(args: LabelCreateArgs) -> Result<()> {
     let db = SkisDb::open()?;
-    let label = db::create_label(
-        db.conn(),
-        &args.name,
-        args.description.as_deref(),
-        args.color.as_deref(),
-    )?;
+    let label = db.with_retry(|conn| {
+        db::create_label(
+            conn,
+            &args.name,
+            args.description.as_deref(),
+            args.color.as_deref(),
+        )
+    })?;
     println!("Created label '{}'", label.name);
     Ok(())
 }
@@ -51,7 +158,114 @@ pub fn delete(args: LabelDeleteArgs) -> Result<()> {
     }
 
     let db = SkisDb::open()?;
-    db::delete_label(db.conn(), &args.name)?;
+    db.with_retry(|conn| db::delete_label(conn, &args.name))?;
     println!("Deleted label '{}'", args.name);
     Ok(())
 }
+
+/// Result of `label prune --json`
+#[derive(Serialize)]
+struct PruneReport {
+    dry_run: bool,
+    pruned: Vec<String>,
+}
+
+pub fn prune(args: LabelPruneArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let names: Vec<String> = db::list_unused_labels(db.conn(), args.include_deleted)?
+        .into_iter()
+        .map(|l| l.name)
+        .collect();
+
+    if !args.dry_run && !args.yes && !names.is_empty() {
+        eprint!(
+            "Delete {} unused label(s): {}? [y/N] ",
+            names.len(),
+            names.join(", ")
+        );
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    if !args.dry_run {
+        db.with_retry(|conn| db::delete_labels(conn, &names))?;
+    }
+
+    if args.json {
+        let report = PruneReport {
+            dry_run: args.dry_run,
+            pruned: names,
+        };
+        println!("{}", crate::commands::to_json(&report, false)?);
+        return Ok(());
+    }
+    if names.is_empty() {
+        println!("No unused labels");
+        return Ok(());
+    }
+    let verb = if args.dry_run {
+        "Would delete"
+    } else {
+        "Deleted"
+    };
+    println!("{} {} unused label(s):", verb, names.len());
+    for name in &names {
+        println!("  {}", name);
+    }
+    Ok(())
+}
+
+/// Result of `label rename-bulk --json`
+#[derive(Serialize)]
+struct RenameReport<'a> {
+    dry_run: bool,
+    renamed: &'a [LabelRename],
+}
+
+pub fn rename_bulk(args: LabelRenameBulkArgs) -> Result<()> {
+    let pattern = Regex::new(&args.from_regex)
+        .map_err(|e| Error::InvalidRegex(args.from_regex.clone(), e.to_string()))?;
+    let db = SkisDb::open()?;
+    let renames = plan_label_renames(&db::list_labels(db.conn())?, &pattern, &args.to)?;
+
+    if !args.dry_run && !args.yes && !renames.is_empty() {
+        eprint!("Rename {} label(s)? [y/N] ", renames.len());
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    if !args.dry_run {
+        db.with_retry(|conn| db::rename_labels(conn, &renames))?;
+    }
+
+    if args.json {
+        let report = RenameReport {
+            dry_run: args.dry_run,
+            renamed: &renames,
+        };
+        println!("{}", crate::commands::to_json(&report, false)?);
+        return Ok(());
+    }
+    if renames.is_empty() {
+        println!("No labels match '{}'", args.from_regex);
+        return Ok(());
+    }
+    let verb = if args.dry_run {
+        "Would rename"
+    } else {
+        "Renamed"
+    };
+    println!("{} {} label(s):", verb, renames.len());
+    for rename in &renames {
+        println!("  {} → {}", rename.from, rename.to);
+    }
+    Ok(())
+}
@@ -1,4 +1,149 @@
+pub mod browse;
+pub mod changelog;
+pub mod deinit;
+pub mod digest;
+pub mod doctor;
+pub mod export;
+pub mod fixtures;
 pub mod init;
 pub mod issue;
 pub mod label;
 pub mod log_path;
+pub mod reindex;
+pub mod release_notes;
+pub mod stats;
+pub mod status;
+
+use std::str::FromStr;
+
+use chrono::Utc;
+use serde::Serialize;
+use ski::config::ListConfig;
+use ski::error::{Error, Result};
+use ski::models::{
+    IssueFilter, IssueState, IssueType, SearchScope, SortField, SortOrder, StateFilter,
+};
+use ski::output::OutputFormat;
+
+use crate::FilterArgs;
+
+/// `--limit` of `issue list` when none is given
+const DEFAULT_LIST_LIMIT: usize = 30;
+
+/// Resolve `--format`, treating `--json` as an alias for `--format json`
+pub fn output_format(format: Option<&str>, json: bool) -> Result<OutputFormat> {
+    if json {
+        return Ok(OutputFormat::Json);
+    }
+    format.map_or(Ok(OutputFormat::Table), OutputFormat::from_str)
+}
+
+/// Serialize `value` as pretty JSON, or on a single line with `--compact`
+pub fn to_json<T: Serialize + ?Sized>(value: &T, compact: bool) -> Result<String> {
+    Ok(if compact {
+        serde_json::to_string(value)?
+    } else {
+        serde_json::to_string_pretty(value)?
+    })
+}
+
+impl FilterArgs {
+    /// Parse and validate the flags into a CLI filter (unknown labels are errors).
+    /// Without `--state`, the states listed come from `list`.
+    pub fn to_filter(&self, list: &ListConfig) -> Result<IssueFilter> {
+        let closed_after = self
+            .closed_since
+            .as_deref()
+            .map(|s| {
+                let d = ski::util::parse_duration(s)?;
+                Utc::now()
+                    .checked_sub_signed(d)
+                    .ok_or_else(|| Error::InvalidDuration(s.to_string()))
+            })
+            .transpose()?;
+
+        let state = match list.resolve_state(self.state.as_deref())? {
+            _ if closed_after.is_some() => Some(IssueState::Closed),
+            state => state.state(),
+        };
+
+        let issue_type = self
+            .issue_type
+            .as_deref()
+            .map(IssueType::from_str)
+            .transpose()?;
+
+        let default_sort = if closed_after.is_some() {
+            "closed"
+        } else {
+            "updated"
+        };
+        let sort = self.sort.as_deref().unwrap_or(default_sort);
+        let sort_by = match sort.to_lowercase().as_str() {
+            "updated" => SortField::Updated,
+            "created" => SortField::Created,
+            "closed" => SortField::Closed,
+            "activity" => SortField::Activity,
+            "comments" => SortField::Comments,
+            "id" => SortField::Id,
+            "title" => SortField::Title,
+            _ => return Err(Error::InvalidSortField(sort.to_string())),
+        };
+
+        let sort_order = match self.order.as_deref().map(str::to_lowercase).as_deref() {
+            None => sort_by.default_order(),
+            Some("asc") => SortOrder::Asc,
+            Some("desc") => SortOrder::Desc,
+            Some(order) => return Err(Error::InvalidSortOrder(order.to_string())),
+        };
+
+        let search_scope = match self.search_in.to_lowercase().as_str() {
+            "all" => SearchScope::All,
+            "title" => SearchScope::Title,
+            "body" => SearchScope::Body,
+            _ => return Err(Error::InvalidSearchScope(self.search_in.clone())),
+        };
+
+        Ok(IssueFilter {
+            state,
+            issue_type,
+            labels: self.labels.clone(),
+            exclude_labels: self.exclude_labels.clone(),
+            strict_labels: true,
+            label_prefix: self.label_prefix.clone(),
+            closed_after,
+            closed_before: None,
+            has_link: match (self.has_link, self.no_link) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            },
+            has_comments: match (self.commented, self.uncommented) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            },
+            min_points: self.min_points,
+            search_scope,
+            after: None,
+            include_deleted: self.deleted,
+            only_deleted: self.only_deleted,
+            include_archived: self.archived,
+            sort_by,
+            sort_order,
+            limit: self.limit.unwrap_or(DEFAULT_LIST_LIMIT),
+            offset: self.offset,
+        })
+    }
+
+    /// Filter for commands that act on every issue it selects (`--matching`,
+    /// export): a missing `--state` means `default_state`, and without
+    /// `--limit` no issue is left out
+    pub fn to_bulk_filter(&self, default_state: StateFilter) -> Result<IssueFilter> {
+        let mut filter = self.to_filter(&ListConfig { default_state })?;
+        if self.limit.is_none() {
+            filter.limit = i64::MAX as usize;
+        }
+        Ok(filter)
+    }
+}
@@ -0,0 +1,9 @@
+use ski::db::{self, SkisDb};
+use ski::error::Result;
+
+pub fn run() -> Result<()> {
+    let db = SkisDb::open()?;
+    db::rebuild_fts(db.conn())?;
+    println!("Rebuilt the search index");
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use ski::db::SkisDb;
+use ski::error::{Error, Result};
+use ski::models::{IssueFilter, IssueState, SortField, SortOrder};
+use ski::output::render_release_notes;
+
+use crate::commands::changelog::{closed_entries, resolve_since};
+use crate::ReleaseNotesArgs;
+
+pub fn run(args: ReleaseNotesArgs) -> Result<()> {
+    if !matches!(args.format.to_lowercase().as_str(), "markdown" | "md") {
+        return Err(Error::InvalidReleaseNotesFormat(args.format));
+    }
+
+    let db = SkisDb::open()?;
+    let closed_after = args
+        .since
+        .as_deref()
+        .map(|since| resolve_since(since, db.discovered_root()))
+        .transpose()?;
+
+    let filter = IssueFilter {
+        state: Some(IssueState::Closed),
+        labels: args.label.iter().cloned().collect(),
+        strict_labels: true,
+        closed_after,
+        sort_by: SortField::Id,
+        sort_order: SortOrder::Asc,
+        limit: i64::MAX as usize,
+        ..Default::default()
+    };
+    let entries = closed_entries(&db, &filter, args.include_wontfix)?;
+
+    let heading = match (&args.label, &args.since) {
+        (Some(label), Some(since)) => format!("Release notes: {} since {}", label, since),
+        (Some(label), None) => format!("Release notes: {}", label),
+        (None, Some(since)) => format!("Release notes since {}", since),
+        (None, None) => unreachable!("clap requires --label or --since"),
+    };
+    let document = render_release_notes(&heading, &entries);
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, document)?;
+            println!("Wrote release notes to {}", path);
+        }
+        None => print!("{}", document),
+    }
+    Ok(())
+}
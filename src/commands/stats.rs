@@ -0,0 +1,46 @@
+use colored::Colorize;
+use ski::db::{self, SkisDb};
+use ski::error::Result;
+
+use crate::StatsTypesArgs;
+
+pub fn types(args: StatsTypesArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let counts = db::issue_counts_by_type(db.conn())?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&counts)?);
+        return Ok(());
+    }
+    if counts.is_empty() {
+        println!("No issues");
+        return Ok(());
+    }
+
+    println!(
+        "{:<8} {:>6} {:>6} {:>6}",
+        "TYPE".bold(),
+        "OPEN".bold(),
+        "CLOSED".bold(),
+        "TOTAL".bold()
+    );
+    for count in &counts {
+        println!(
+            "{:<8} {:>6} {:>6} {:>6}",
+            count.issue_type.to_string(),
+            count.open,
+            count.closed,
+            count.open + count.closed
+        );
+    }
+    let open: i64 = counts.iter().map(|c| c.open).sum();
+    let closed: i64 = counts.iter().map(|c| c.closed).sum();
+    println!(
+        "{:<8} {:>6} {:>6} {:>6}",
+        "total",
+        open,
+        closed,
+        open + closed
+    );
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use colored::Colorize;
+use serde::Serialize;
+use ski::db::{self, SkisDb};
+use ski::error::Result;
+use ski::models::PointsTotal;
+
+use crate::StatusArgs;
+
+#[derive(Serialize)]
+struct Status {
+    issues: i64,
+    unestimated: i64,
+    points: i64,
+    by_type: Vec<PointsTotal>,
+}
+
+pub fn run(args: StatusArgs) -> Result<()> {
+    let db = SkisDb::open()?;
+    let by_type = db::open_points_by_type(db.conn())?;
+    let status = Status {
+        issues: by_type.iter().map(|t| t.issues).sum(),
+        unestimated: by_type.iter().map(|t| t.unestimated).sum(),
+        points: by_type.iter().map(|t| t.points).sum(),
+        by_type,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+    if status.issues == 0 {
+        println!("No open issues");
+        return Ok(());
+    }
+
+    println!(
+        "{:<8} {:>6} {:>6} {:>11}",
+        "TYPE".bold(),
+        "ISSUES".bold(),
+        "POINTS".bold(),
+        "UNESTIMATED".bold()
+    );
+    for total in &status.by_type {
+        println!(
+            "{:<8} {:>6} {:>6} {:>11}",
+            total.issue_type.to_string(),
+            total.issues,
+            total.points,
+            total.unestimated
+        );
+    }
+    println!(
+        "{:<8} {:>6} {:>6} {:>11}",
+        "total", status.issues, status.points, status.unestimated
+    );
+    Ok(())
+}
@@ -0,0 +1,409 @@
+//! Per-repository settings read from `.skis/config.toml`
+//!
+//! The file is optional; a missing file means every setting takes its default.
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::models::{IssueType, StateFilter, DEFAULT_MAX_COMMENT_LENGTH};
+use crate::util::normalize_line_endings;
+
+/// Name of the config file inside `.skis/`
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Default for `max_list_limit`
+pub const DEFAULT_MAX_LIST_LIMIT: usize = 1000;
+
+/// Repository settings
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Title prefixes applied when creating issues, e.g. `title_prefix.bug = "[BUG] "`
+    pub title_prefix: TitlePrefixes,
+    /// Clean-up applied to issue and comment bodies when they are saved
+    pub body: BodyFormat,
+    /// Only serve commands run inside the git repository this `.skis/` sits in;
+    /// from a nested repository without its own `.skis/`, discovery fails instead
+    pub search_stop_at_git_root: bool,
+    /// Largest `issue list --limit` honoured; bigger values are lowered with a warning
+    pub max_list_limit: usize,
+    /// How writes that find the database busy are retried
+    pub retry: RetryPolicy,
+    /// Defaults for `issue list` and the GUI's issue list
+    pub list: ListConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            title_prefix: TitlePrefixes::default(),
+            body: BodyFormat::default(),
+            search_stop_at_git_root: false,
+            max_list_limit: DEFAULT_MAX_LIST_LIMIT,
+            retry: RetryPolicy::default(),
+            list: ListConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Load `config.toml` from a `.skis/` directory, or the defaults if it doesn't exist
+    pub fn load(skis_dir: &Path) -> Result<Self> {
+        let path = skis_dir.join(CONFIG_FILE);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        toml::from_str(&text)
+            .map_err(|e| Error::InvalidConfig(path.display().to_string(), e.message().to_string()))
+    }
+}
+
+/// Title prefix for each issue type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TitlePrefixes {
+    pub epic: Option<String>,
+    pub task: Option<String>,
+    pub bug: Option<String>,
+    pub request: Option<String>,
+}
+
+impl TitlePrefixes {
+    /// The configured prefix for `issue_type`, if any
+    pub fn get(&self, issue_type: IssueType) -> Option<&str> {
+        match issue_type {
+            IssueType::Epic => self.epic.as_deref(),
+            IssueType::Task => self.task.as_deref(),
+            IssueType::Bug => self.bug.as_deref(),
+            IssueType::Request => self.request.as_deref(),
+        }
+        .filter(|p| !p.trim().is_empty())
+    }
+
+    /// Prefix `title` for `issue_type` unless it already starts with the prefix
+    pub fn apply(&self, issue_type: IssueType, title: &str) -> String {
+        match self.get(issue_type) {
+            Some(prefix) if !has_prefix(title, prefix) => format!("{}{}", prefix, title),
+            _ => title.to_string(),
+        }
+    }
+
+    /// Remove any configured prefix from the start of `title`
+    pub fn strip<'a>(&self, title: &'a str) -> &'a str {
+        [&self.epic, &self.task, &self.bug, &self.request]
+            .into_iter()
+            .flatten()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .find_map(|p| title.strip_prefix(p))
+            .map_or(title, str::trim_start)
+    }
+}
+
+/// How bodies are cleaned up and checked on write, e.g. `body.trim_trailing_whitespace = true`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BodyFormat {
+    /// Convert CRLF and lone CR line endings to `\n` (on by default)
+    pub normalize_line_endings: bool,
+    /// Strip spaces and tabs from the end of each line (off by default)
+    pub trim_trailing_whitespace: bool,
+    /// Longest comment accepted, in characters (65536 by default)
+    pub max_comment_length: usize,
+}
+
+impl Default for BodyFormat {
+    fn default() -> Self {
+        Self {
+            normalize_line_endings: true,
+            trim_trailing_whitespace: false,
+            max_comment_length: DEFAULT_MAX_COMMENT_LENGTH,
+        }
+    }
+}
+
+impl BodyFormat {
+    /// Apply the enabled clean-ups to `body`
+    pub fn apply(&self, body: &str) -> String {
+        let body = if self.normalize_line_endings {
+            normalize_line_endings(body)
+        } else {
+            body.to_string()
+        };
+        if !self.trim_trailing_whitespace {
+            return body;
+        }
+        body.split('\n')
+            .map(|line| line.trim_end_matches([' ', '\t']))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Issue list settings, e.g. `list.default_state = "all"`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListConfig {
+    /// States listed when no state is asked for (open by default)
+    pub default_state: StateFilter,
+}
+
+impl ListConfig {
+    /// The states to list: those named by `flag` if given, else `default_state`
+    pub fn resolve_state(&self, flag: Option<&str>) -> Result<StateFilter> {
+        flag.map_or(Ok(self.default_state), str::parse)
+    }
+}
+
+/// Retries of a write that fails with SQLITE_BUSY or SQLITE_LOCKED even after
+/// the connection's busy timeout, e.g. `retry.attempts = 5`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Times a busy write is tried again before the error is returned (3 by default)
+    pub attempts: u32,
+    /// Pause before the first retry in milliseconds, doubled for each later one (25 by default)
+    pub backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff_ms: 25,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Pause before retry number `attempt` (counting from 0)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        Duration::from_millis(self.backoff_ms.saturating_mul(1 << attempt.min(16)))
+    }
+}
+
+/// Whether `title` already carries `prefix`, ignoring the prefix's trailing space
+fn has_prefix(title: &str, prefix: &str) -> bool {
+    title.starts_with(prefix.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn prefixes() -> TitlePrefixes {
+        TitlePrefixes {
+            bug: Some("[BUG] ".to_string()),
+            epic: Some("[EPIC] ".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn apply_adds_prefix_for_type() {
+        let p = prefixes();
+        assert_eq!(
+            p.apply(IssueType::Bug, "Crash on save"),
+            "[BUG] Crash on save"
+        );
+        assert_eq!(p.apply(IssueType::Epic, "Sync"), "[EPIC] Sync");
+        assert_eq!(p.apply(IssueType::Task, "Tidy up"), "Tidy up");
+    }
+
+    #[test]
+    fn apply_is_idempotent() {
+        let p = prefixes();
+        let once = p.apply(IssueType::Bug, "Crash on save");
+        assert_eq!(p.apply(IssueType::Bug, &once), once);
+        // Already prefixed without the trailing space
+        assert_eq!(p.apply(IssueType::Bug, "[BUG]Crash"), "[BUG]Crash");
+    }
+
+    #[test]
+    fn strip_removes_any_known_prefix() {
+        let p = prefixes();
+        assert_eq!(p.strip("[BUG] Crash on save"), "Crash on save");
+        assert_eq!(p.strip("[EPIC]Sync"), "Sync");
+        assert_eq!(p.strip("[TODO] Other"), "[TODO] Other");
+        assert_eq!(p.strip(&p.apply(IssueType::Bug, "Crash")), "Crash");
+    }
+
+    #[test]
+    fn blank_prefix_is_ignored() {
+        let p = TitlePrefixes {
+            task: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(p.apply(IssueType::Task, "Tidy up"), "Tidy up");
+        assert_eq!(p.strip("  Tidy up"), "  Tidy up");
+    }
+
+    #[test]
+    fn body_format_normalizes_line_endings_by_default() {
+        let format = BodyFormat::default();
+        assert_eq!(format.apply("one\r\ntwo\rthree  \n"), "one\ntwo\nthree  \n");
+    }
+
+    #[test]
+    fn body_format_trims_trailing_whitespace_when_enabled() {
+        let format = BodyFormat {
+            trim_trailing_whitespace: true,
+            ..Default::default()
+        };
+        assert_eq!(format.apply("one \t\r\n  two  \n"), "one\n  two\n");
+    }
+
+    #[test]
+    fn body_format_can_be_turned_off() {
+        let format = BodyFormat {
+            normalize_line_endings: false,
+            trim_trailing_whitespace: false,
+            ..Default::default()
+        };
+        assert_eq!(format.apply("one\r\ntwo "), "one\r\ntwo ");
+    }
+
+    #[test]
+    fn load_missing_file_gives_defaults() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.title_prefix, TitlePrefixes::default());
+        assert_eq!(config.body, BodyFormat::default());
+        assert!(!config.search_stop_at_git_root);
+        assert_eq!(config.max_list_limit, DEFAULT_MAX_LIST_LIMIT);
+    }
+
+    #[test]
+    fn load_reads_max_list_limit() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "max_list_limit = 50
+",
+        )
+        .unwrap();
+        assert_eq!(Config::load(dir.path()).unwrap().max_list_limit, 50);
+    }
+
+    #[test]
+    fn load_reads_title_prefixes() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "title_prefix.bug = \"[BUG] \"\ntitle_prefix.epic = \"[EPIC] \"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.title_prefix, prefixes());
+    }
+
+    #[test]
+    fn load_reads_body_format() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[body]\nnormalize_line_endings = false\ntrim_trailing_whitespace = true\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert!(!config.body.normalize_line_endings);
+        assert!(config.body.trim_trailing_whitespace);
+    }
+
+    #[test]
+    fn load_rejects_unknown_keys() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), "title_prefix.bgu = \"x\"\n").unwrap();
+        assert!(matches!(
+            Config::load(dir.path()),
+            Err(Error::InvalidConfig(_, _))
+        ));
+    }
+
+    #[test]
+    fn load_reads_retry_policy() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), "[retry]\nattempts = 5\n").unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.retry.attempts, 5);
+        assert_eq!(config.retry.backoff_ms, 25);
+    }
+
+    #[test]
+    fn load_reads_max_comment_length() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[body]\nmax_comment_length = 100\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.body.max_comment_length, 100);
+        assert!(config.body.normalize_line_endings);
+    }
+
+    #[test]
+    fn retry_delay_doubles() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff_ms: 10,
+        };
+        assert_eq!(policy.delay(0), Duration::from_millis(10));
+        assert_eq!(policy.delay(2), Duration::from_millis(40));
+        assert_eq!(
+            RetryPolicy {
+                attempts: 100,
+                backoff_ms: u64::MAX,
+            }
+            .delay(99),
+            Duration::from_millis(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn list_state_prefers_flag_then_config_then_open() {
+        let dir = TempDir::new().unwrap();
+        let list = Config::load(dir.path()).unwrap().list;
+        assert_eq!(list.resolve_state(None).unwrap(), StateFilter::Open);
+
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[list]\ndefault_state = \"all\"\n",
+        )
+        .unwrap();
+        let list = Config::load(dir.path()).unwrap().list;
+        assert_eq!(list.resolve_state(None).unwrap(), StateFilter::All);
+        assert_eq!(
+            list.resolve_state(Some("closed")).unwrap(),
+            StateFilter::Closed
+        );
+        assert!(matches!(
+            list.resolve_state(Some("shut")),
+            Err(Error::InvalidStateFilter(_))
+        ));
+    }
+
+    #[test]
+    fn load_rejects_unknown_default_state() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "list.default_state = \"shut\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            Config::load(dir.path()),
+            Err(Error::InvalidConfig(_, _))
+        ));
+    }
+}
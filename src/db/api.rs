@@ -0,0 +1,255 @@
+//! High-level methods on `SkisDb`, the recommended API for embedders.
+//!
+//! Each method wraps the matching free function in `ski::db` so callers never
+//! handle the underlying `rusqlite::Connection`. Methods that write are retried
+//! while another process holds the database; see [`SkisDb::with_retry`].
+//!
+//! ```
+//! use ski::{IssueCreate, IssueFilter, IssueState, IssueType, SkisDb, StateReason};
+//!
+//! let dir = tempfile::TempDir::new()?;
+//! let db = SkisDb::init(dir.path())?;
+//!
+//! db.create_label("backend", None, None)?;
+//! let issue = db.create_issue(&IssueCreate {
+//!     title: "Crash on save".to_string(),
+//!     issue_type: IssueType::Bug,
+//!     labels: vec!["backend".to_string()],
+//!     ..Default::default()
+//! })?;
+//!
+//! let open = IssueFilter {
+//!     state: Some(IssueState::Open),
+//!     ..Default::default()
+//! };
+//! assert_eq!(db.list(&open)?.len(), 1);
+//!
+//! db.close(issue.id, StateReason::Completed)?;
+//! assert!(db.list(&open)?.is_empty());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{Error, Result};
+use crate::models::{
+    Comment, CreateOpts, Issue, IssueCreate, IssueFilter, IssueUpdate, Label, LinkType,
+    LinkedIssueRef, StateReason,
+};
+
+use super::{queries, SkisDb};
+
+impl SkisDb {
+    // Issues
+
+    /// Create an issue with optional labels
+    pub fn create_issue(&self, create: &IssueCreate) -> Result<Issue> {
+        self.with_retry(|conn| queries::create_issue(conn, create))
+    }
+
+    /// Create an issue, applying options such as title prefixes
+    pub fn create_issue_with(&self, create: &IssueCreate, opts: &CreateOpts) -> Result<Issue> {
+        self.with_retry(|conn| queries::create_issue_with(conn, create, opts))
+    }
+
+    /// Get an issue by number, with its labels
+    pub fn issue(&self, id: i64) -> Result<Issue> {
+        queries::get_issue_with_labels(self.conn(), id)?.ok_or(Error::IssueNotFound(id))
+    }
+
+    /// List issues matching `filter`, with their labels
+    pub fn list(&self, filter: &IssueFilter) -> Result<Vec<Issue>> {
+        queries::list_issues_with_labels(self.conn(), filter)
+    }
+
+    /// Full-text search within `filter`, with labels
+    pub fn search(&self, query: &str, filter: &IssueFilter) -> Result<Vec<Issue>> {
+        queries::search_issues_with_labels(self.conn(), query, filter)
+    }
+
+    /// Count issues matching `filter` (sorting and pagination are ignored)
+    pub fn count(&self, filter: &IssueFilter) -> Result<i64> {
+        queries::count_issues(self.conn(), filter)
+    }
+
+    /// Update an issue's title, body or type
+    pub fn update(&self, id: i64, update: &IssueUpdate) -> Result<Issue> {
+        self.with_retry(|conn| queries::update_issue(conn, id, update))
+    }
+
+    /// Close an issue with a reason
+    pub fn close(&self, id: i64, reason: StateReason) -> Result<Issue> {
+        self.with_retry(|conn| queries::close_issue(conn, id, reason))
+    }
+
+    /// Reopen a closed issue
+    pub fn reopen(&self, id: i64) -> Result<Issue> {
+        self.with_retry(|conn| queries::reopen_issue(conn, id))
+    }
+
+    /// Soft-delete an issue
+    pub fn delete(&self, id: i64) -> Result<()> {
+        self.with_retry(|conn| queries::delete_issue(conn, id))
+    }
+
+    /// Restore a soft-deleted issue
+    pub fn restore(&self, id: i64) -> Result<Issue> {
+        self.with_retry(|conn| queries::restore_issue(conn, id))
+    }
+
+    /// Archive an issue, hiding it from listings
+    pub fn archive(&self, id: i64) -> Result<Issue> {
+        self.with_retry(|conn| queries::archive_issue(conn, id))
+    }
+
+    /// Return an archived issue to listings
+    pub fn unarchive(&self, id: i64) -> Result<Issue> {
+        self.with_retry(|conn| queries::unarchive_issue(conn, id))
+    }
+
+    // Comments
+
+    /// Add a comment to an issue
+    pub fn comment(&self, issue_id: i64, body: &str) -> Result<Comment> {
+        self.with_retry(|conn| queries::add_comment(conn, issue_id, body))
+    }
+
+    /// Comments on an issue, oldest first
+    pub fn comments(&self, issue_id: i64) -> Result<Vec<Comment>> {
+        queries::get_comments(self.conn(), issue_id)
+    }
+
+    // Links
+
+    /// Link two issues; `link_type` is how `issue_a` relates to `issue_b`
+    pub fn link(&self, issue_a: i64, issue_b: i64, link_type: LinkType) -> Result<()> {
+        self.with_retry(|conn| queries::add_link(conn, issue_a, issue_b, link_type, false))
+    }
+
+    /// Remove the link between two issues
+    pub fn unlink(&self, issue_a: i64, issue_b: i64) -> Result<()> {
+        self.with_retry(|conn| queries::remove_link(conn, issue_a, issue_b))
+    }
+
+    /// Issues linked to `id`, with titles and link types
+    pub fn linked_issues(&self, id: i64) -> Result<Vec<LinkedIssueRef>> {
+        queries::get_linked_issues_with_titles(self.conn(), id)
+    }
+
+    // Labels
+
+    /// Create a label; a color is generated when `color` is `None`
+    pub fn create_label(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        color: Option<&str>,
+    ) -> Result<Label> {
+        self.with_retry(|conn| queries::create_label(conn, name, description, color))
+    }
+
+    /// All labels, by name
+    pub fn labels(&self) -> Result<Vec<Label>> {
+        queries::list_labels(self.conn())
+    }
+
+    /// Delete a label, removing it from any issues
+    pub fn delete_label(&self, name: &str) -> Result<()> {
+        self.with_retry(|conn| queries::delete_label(conn, name))
+    }
+
+    /// Add an existing label to an issue
+    pub fn add_label(&self, issue_id: i64, name: &str) -> Result<()> {
+        self.with_retry(|conn| queries::add_label_to_issue(conn, issue_id, name, false))
+    }
+
+    /// Remove a label from an issue
+    pub fn remove_label(&self, issue_id: i64, name: &str) -> Result<()> {
+        self.with_retry(|conn| queries::remove_label_from_issue(conn, issue_id, name, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IssueState, IssueType};
+    use tempfile::TempDir;
+
+    fn test_db() -> (SkisDb, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let db = SkisDb::init(dir.path()).unwrap();
+        (db, dir)
+    }
+
+    fn create(db: &SkisDb, title: &str) -> Issue {
+        db.create_issue(&IssueCreate {
+            title: title.to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn issue_lifecycle() {
+        let (db, _dir) = test_db();
+        let issue = create(&db, "Crash on save");
+
+        let issue = db
+            .update(
+                issue.id,
+                &IssueUpdate {
+                    issue_type: Some(IssueType::Bug),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(issue.issue_type, IssueType::Bug);
+
+        let closed = db.close(issue.id, StateReason::NotPlanned).unwrap();
+        assert_eq!(closed.state, IssueState::Closed);
+        assert_eq!(db.reopen(issue.id).unwrap().state, IssueState::Open);
+
+        db.delete(issue.id).unwrap();
+        assert!(db.list(&IssueFilter::default()).unwrap().is_empty());
+        assert!(db.restore(issue.id).unwrap().deleted_at.is_none());
+        assert_eq!(db.count(&IssueFilter::default()).unwrap(), 1);
+
+        assert!(db.archive(issue.id).unwrap().archived_at.is_some());
+        assert_eq!(db.count(&IssueFilter::default()).unwrap(), 0);
+        assert!(db.unarchive(issue.id).unwrap().archived_at.is_none());
+    }
+
+    #[test]
+    fn issue_reports_missing_number() {
+        let (db, _dir) = test_db();
+        assert!(matches!(db.issue(42), Err(Error::IssueNotFound(42))));
+    }
+
+    #[test]
+    fn labels_comments_and_links() {
+        let (db, _dir) = test_db();
+        let a = create(&db, "Login page");
+        let b = create(&db, "Session store");
+
+        db.create_label("ui", Some("User interface"), None).unwrap();
+        db.add_label(a.id, "ui").unwrap();
+        let labels = db.issue(a.id).unwrap().labels.unwrap();
+        assert_eq!(labels[0].name, "ui");
+        db.remove_label(a.id, "ui").unwrap();
+        assert!(db.issue(a.id).unwrap().labels.unwrap().is_empty());
+        db.delete_label("ui").unwrap();
+        assert!(db.labels().unwrap().is_empty());
+
+        db.comment(a.id, "Blocked on the store").unwrap();
+        assert_eq!(db.comments(a.id).unwrap().len(), 1);
+
+        db.link(b.id, a.id, LinkType::Blocks).unwrap();
+        let linked = db.linked_issues(a.id).unwrap();
+        assert_eq!(linked[0].id, b.id);
+        assert_eq!(linked[0].link_type, LinkType::BlockedBy);
+        db.unlink(a.id, b.id).unwrap();
+        assert!(db.linked_issues(a.id).unwrap().is_empty());
+
+        let results = db.search("session", &IssueFilter::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, b.id);
+    }
+}
@@ -1,97 +1,429 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 
+use crate::config::{Config, RetryPolicy};
 use crate::error::{Error, Result};
+use crate::models::IssueType;
 
 use super::migrations;
 
 const SKIS_DIR: &str = ".skis";
 const DB_FILE: &str = "issues.db";
+/// Marks a git repository root (a directory, or a file in worktrees and submodules)
+const GIT_DIR: &str = ".git";
+
+/// Environment variable naming the `.skis/` directory to use instead of discovery
+pub const SKIS_DIR_ENV: &str = "SKIS_DIR";
+
+/// Environment variable naming a database file to use directly, bypassing
+/// `.skis/` discovery and `SKIS_DIR`
+pub const SKIS_DB_ENV: &str = "SKIS_DB";
+
+/// Environment variable holding the key of an encrypted database, used by
+/// every constructor that is not given a key explicitly
+#[cfg(feature = "sqlcipher")]
+pub const SKIS_KEY_ENV: &str = "SKIS_KEY";
+
+/// How long a connection waits on a locked database before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Apply the settings every SKIS connection relies on.
+/// All constructors must go through this so enforcement can't silently be lost.
+fn configure(conn: &Connection) -> Result<()> {
+    conn.pragma_update(None, "foreign_keys", true)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // Label names compare ignoring case and accents (`name = ?1 COLLATE NOACCENT`)
+    conn.create_collation("NOACCENT", |a, b| {
+        crate::util::fold_accents(a).cmp(&crate::util::fold_accents(b))
+    })?;
+    Ok(())
+}
+
+/// Open a connection, unlock it with `key` and configure it
+fn connect(db_path: &Path, flags: OpenFlags, key: Option<&str>) -> Result<Connection> {
+    let conn = Connection::open_with_flags(db_path, flags)?;
+    apply_key(&conn, key)?;
+    configure(&conn)?;
+    Ok(conn)
+}
+
+/// Run `PRAGMA key` before anything else touches the database. SQLCipher only
+/// reads the file on the first query, where a wrong or missing key surfaces as
+/// "file is not a database"; probe for that here so it maps to
+/// `Error::InvalidKey`.
+#[cfg(feature = "sqlcipher")]
+fn apply_key(conn: &Connection, key: Option<&str>) -> Result<()> {
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key)?;
+    }
+    match conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())) {
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if e.code == rusqlite::ErrorCode::NotADatabase =>
+        {
+            Err(Error::InvalidKey)
+        }
+        result => Ok(result?),
+    }
+}
 
-/// Database handle for SKIS operations
+#[cfg(not(feature = "sqlcipher"))]
+fn apply_key(_conn: &Connection, _key: Option<&str>) -> Result<()> {
+    Ok(())
+}
+
+/// Key from `$SKIS_KEY`, used by constructors not given one explicitly
+#[cfg(feature = "sqlcipher")]
+fn default_key() -> Option<String> {
+    std::env::var(SKIS_KEY_ENV).ok().filter(|k| !k.is_empty())
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+fn default_key() -> Option<String> {
+    None
+}
+
+/// `Error::NotARepository` if the database file is missing, or
+/// `Error::CorruptRepository` if it is empty. SQLite would happily treat an
+/// empty file as a new database, hiding whatever truncated it.
+fn check_db_file(db_path: &Path) -> Result<()> {
+    match std::fs::metadata(db_path) {
+        Ok(meta) if meta.len() == 0 => Err(corrupt(db_path)),
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(Error::NotARepository),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn corrupt(db_path: &Path) -> Error {
+    Error::CorruptRepository(db_path.display().to_string())
+}
+
+/// First free `issues.db.broken`, `issues.db.broken.1`, ... next to `db_path`
+fn backup_path(db_path: &Path) -> PathBuf {
+    let base = format!("{}.broken", db_path.display());
+    let mut backup = PathBuf::from(&base);
+    let mut n = 0;
+    while backup.exists() {
+        n += 1;
+        backup = PathBuf::from(format!("{}.{}", base, n));
+    }
+    backup
+}
+
+/// Database handle for SKIS operations.
+///
+/// Its methods (`create_issue`, `list`, `close`, ...) are the recommended API;
+/// they wrap the free functions in `ski::db` without exposing the connection.
+///
+/// ```
+/// use ski::{IssueCreate, IssueFilter, IssueState, IssueType, StateReason};
+///
+/// let (db, _dir) = ski::test_support::temp_repo()?;
+/// db.create_label("ui", None, None)?;
+/// let bug = db.create_issue(&IssueCreate {
+///     title: "Button misaligned".to_string(),
+///     issue_type: IssueType::Bug,
+///     labels: vec!["ui".to_string()],
+///     ..Default::default()
+/// })?;
+///
+/// let open_ui = IssueFilter {
+///     state: Some(IssueState::Open),
+///     labels: vec!["ui".to_string()],
+///     ..Default::default()
+/// };
+/// assert_eq!(db.list(&open_ui)?[0].id, bug.id);
+///
+/// let closed = db.close(bug.id, StateReason::Completed)?;
+/// assert_eq!(closed.state, IssueState::Closed);
+/// assert!(db.list(&open_ui)?.is_empty());
+/// # Ok::<(), ski::Error>(())
+/// ```
 #[derive(Debug)]
 pub struct SkisDb {
     conn: Connection,
+    dir: PathBuf,
+    /// The `[retry]` settings, read once when the database is opened
+    retry: RetryPolicy,
 }
 
 impl SkisDb {
+    fn new(conn: Connection, dir: PathBuf) -> Self {
+        // A broken config.toml is reported by the commands that read it
+        let retry = Config::load(&dir).map(|c| c.retry).unwrap_or_default();
+        Self { conn, dir, retry }
+    }
+
     /// Initialize a new SKIS repository at the given path.
-    /// Creates `.skis/` directory and initializes the database.
+    /// Creates `.skis/` directory and initializes the database; if that fails
+    /// part way, the new `.skis/` is removed again.
     pub fn init(path: &Path) -> Result<Self> {
+        Self::init_keyed(path, default_key().as_deref())
+    }
+
+    /// Like `init`, but encrypt the new database with `key`
+    #[cfg(feature = "sqlcipher")]
+    pub fn init_with_key(path: &Path, key: Option<&str>) -> Result<Self> {
+        Self::init_keyed(path, key)
+    }
+
+    fn init_keyed(path: &Path, key: Option<&str>) -> Result<Self> {
         let skis_dir = path.join(SKIS_DIR);
 
         if skis_dir.exists() {
-            return Err(Error::AlreadyInitialized);
+            let db_path = skis_dir.join(DB_FILE);
+            return Err(match check_db_file(&db_path) {
+                Err(Error::NotARepository) => corrupt(&db_path),
+                Err(e) => e,
+                Ok(()) => Error::AlreadyInitialized,
+            });
         }
 
+        // A half-made `.skis/` would make every later `init` fail
         std::fs::create_dir_all(&skis_dir)?;
+        match Self::create_db(&skis_dir.join(DB_FILE), key) {
+            Ok(db) => Ok(db),
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&skis_dir);
+                Err(e)
+            }
+        }
+    }
+
+    /// Like `init`, but an existing `.skis/` whose database is missing or
+    /// empty gets a new database. The broken file is renamed first; its new
+    /// path is returned. A healthy repository still fails with
+    /// `Error::AlreadyInitialized`.
+    pub fn init_force(path: &Path) -> Result<(Self, Option<PathBuf>)> {
+        let skis_dir = path.join(SKIS_DIR);
+        if !skis_dir.exists() {
+            return Ok((Self::init(path)?, None));
+        }
 
         let db_path = skis_dir.join(DB_FILE);
-        let conn = Connection::open(&db_path)?;
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
+        let backup = match check_db_file(&db_path) {
+            Ok(()) => return Err(Error::AlreadyInitialized),
+            Err(Error::NotARepository) => None,
+            Err(Error::CorruptRepository(_)) => {
+                let backup = backup_path(&db_path);
+                std::fs::rename(&db_path, &backup)?;
+                Some(backup)
+            }
+            Err(e) => return Err(e),
+        };
+        Ok((Self::create_db(&db_path, default_key().as_deref())?, backup))
+    }
+
+    /// Create a database file outside the `.skis/` layout, as `init` does for
+    /// `$SKIS_DB`. A file that fails to initialize is removed again.
+    pub fn init_file(db_path: &Path) -> Result<Self> {
+        if db_path.exists() {
+            check_db_file(db_path)?;
+            return Err(Error::AlreadyInitialized);
+        }
+
+        match Self::create_db(db_path, default_key().as_deref()) {
+            Ok(db) => Ok(db),
+            Err(e) => {
+                let _ = std::fs::remove_file(db_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// Create and migrate a database; its directory must already exist
+    fn create_db(db_path: &Path, key: Option<&str>) -> Result<Self> {
+        let conn = connect(db_path, OpenFlags::default(), key)?;
 
         migrations::run_migrations(&conn)?;
 
-        Ok(Self { conn })
+        Ok(Self::new(conn, db_dir(db_path)))
     }
 
-    /// Open database, searching up from cwd for `.skis/` directory
+    /// Open the database file at `$SKIS_DB` when set; otherwise search up
+    /// from cwd for `.skis/` directory (or use `$SKIS_DIR` when set)
     pub fn open() -> Result<Self> {
+        if let Some(db_path) = db_path_from_env()? {
+            return Self::open_file(&db_path);
+        }
         let skis_dir = find_skis_dir()?;
         Self::open_at(&skis_dir)
     }
 
-    /// Open database at a specific `.skis/` directory path
+    /// Open a database file directly, upgrading an older schema. The file's
+    /// directory stands in for `.skis/`, e.g. for `config.toml`.
+    pub fn open_file(db_path: &Path) -> Result<Self> {
+        Self::open_file_keyed(db_path, default_key().as_deref())
+    }
+
+    fn open_file_keyed(db_path: &Path, key: Option<&str>) -> Result<Self> {
+        check_db_file(db_path)?;
+
+        let conn = connect(db_path, OpenFlags::default(), key)?;
+        migrations::run_migrations(&conn)?;
+        Ok(Self::new(conn, db_dir(db_path)))
+    }
+
+    /// Open the repository containing `start`, a directory or a file inside it.
+    /// `$SKIS_DIR` is not consulted.
+    pub fn open_from(start: &Path) -> Result<Self> {
+        Self::open_at(&find_skis_dir_from(start)?)
+    }
+
+    /// Open database at a specific `.skis/` directory path, upgrading an
+    /// older schema. Fails with `Error::SchemaTooNew` if a newer SKIS wrote it.
     pub fn open_at(skis_dir: &Path) -> Result<Self> {
-        let db_path = skis_dir.join(DB_FILE);
-        if !db_path.exists() {
-            return Err(Error::NotARepository);
-        }
+        Self::open_file(&skis_dir.join(DB_FILE))
+    }
 
-        let conn = Connection::open(&db_path)?;
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
-        Ok(Self { conn })
+    /// Like `open_at`, but unlock an encrypted database with `key`.
+    /// Fails with `Error::InvalidKey` if the key is wrong or missing.
+    #[cfg(feature = "sqlcipher")]
+    pub fn open_at_with_key(skis_dir: &Path, key: Option<&str>) -> Result<Self> {
+        Self::open_file_keyed(&skis_dir.join(DB_FILE), key)
+    }
+
+    /// Open the database at a `.skis/` directory without write access
+    pub fn open_read_only_at(skis_dir: &Path) -> Result<Self> {
+        let db_path = skis_dir.join(DB_FILE);
+        check_db_file(&db_path)?;
+
+        let conn = connect(
+            &db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            default_key().as_deref(),
+        )?;
+        migrations::check_schema_version(&conn)?;
+        Ok(Self::new(conn, skis_dir.to_path_buf()))
     }
 
     /// Get a reference to the underlying connection
     pub fn conn(&self) -> &Connection {
         &self.conn
     }
-}
 
-/// Walk up from current directory looking for `.skis/` directory
-pub fn find_skis_dir() -> Result<PathBuf> {
-    let mut current = std::env::current_dir()?;
+    /// The `.skis/` directory this database lives in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The project root, i.e. the directory containing `.skis/`
+    pub fn discovered_root(&self) -> &Path {
+        self.dir.parent().unwrap_or(&self.dir)
+    }
+
+    /// Load the repository's `config.toml` (defaults if absent)
+    pub fn config(&self) -> Result<Config> {
+        Config::load(&self.dir)
+    }
 
+    /// Run a write on the connection, retrying it as the repository's `[retry]`
+    /// settings say while the database is busy
+    pub fn with_retry<T>(&self, mut op: impl FnMut(&Connection) -> Result<T>) -> Result<T> {
+        retry_busy(&self.retry, || op(&self.conn))
+    }
+
+    /// Body template for a new issue of `issue_type`
+    pub fn body_template(&self, issue_type: IssueType) -> Result<String> {
+        crate::templates::body_template(&self.dir, issue_type)
+    }
+
+    /// Schema version of the open database
+    pub fn schema_version(&self) -> Result<i32> {
+        migrations::schema_version(&self.conn)
+    }
+}
+
+/// Run `op`, and run it again after a growing pause each time it fails because
+/// the database is busy, up to `policy.attempts` more times. Any other error,
+/// or the last busy one, is returned as is.
+///
+/// `op` must leave nothing behind when it fails, which holds for the query
+/// functions: each writes in a single transaction or statement.
+fn retry_busy<T>(policy: &RetryPolicy, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 0;
     loop {
-        let skis_dir = current.join(SKIS_DIR);
-        if skis_dir.is_dir() {
-            return Ok(skis_dir);
+        match op() {
+            Err(e) if e.is_busy() && attempt < policy.attempts => {
+                std::thread::sleep(policy.delay(attempt));
+                attempt += 1;
+            }
+            result => return result,
         }
+    }
+}
 
-        if !current.pop() {
+/// The absolute path in `$SKIS_DB`, if it is set and non-empty
+pub fn db_path_from_env() -> Result<Option<PathBuf>> {
+    match std::env::var_os(SKIS_DB_ENV).filter(|p| !p.is_empty()) {
+        Some(path) => Ok(Some(std::path::absolute(path)?)),
+        None => Ok(None),
+    }
+}
+
+/// Directory holding a database file, which plays the part of `.skis/`
+fn db_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new(".")).to_path_buf()
+}
+
+/// Use `$SKIS_DIR` if set, otherwise walk up from current directory looking
+/// for `.skis/` directory
+pub fn find_skis_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(SKIS_DIR_ENV).filter(|d| !d.is_empty()) {
+        let dir = std::path::absolute(dir)?;
+        if !dir.is_dir() {
             return Err(Error::NotARepository);
         }
+        return Ok(dir);
     }
+
+    find_skis_dir_from(&std::env::current_dir()?)
 }
 
-/// Find `.skis/` directory starting from a specific path (for testing)
+/// Walk up from `start` (a directory or a file inside one, which need not
+/// exist yet) looking for `.skis/`. If `start` goes through a symlink, the
+/// walk is retried from the real path of its nearest existing ancestor.
 pub fn find_skis_dir_from(start: &Path) -> Result<PathBuf> {
+    let err = match walk_up(start) {
+        Ok(dir) => return Ok(dir),
+        Err(e) => e,
+    };
+    match start.ancestors().find_map(|p| p.canonicalize().ok()) {
+        Some(real) if real != start => walk_up(&real),
+        _ => Err(err),
+    }
+}
+
+fn walk_up(start: &Path) -> Result<PathBuf> {
     let mut current = start.to_path_buf();
+    let mut passed_git_root = false;
 
     loop {
         let skis_dir = current.join(SKIS_DIR);
         if skis_dir.is_dir() {
+            // A `.skis/` above an unrelated git repository only counts if its
+            // config doesn't confine it to its own repository
+            if passed_git_root && stops_at_git_root(&skis_dir) {
+                return Err(Error::NotARepository);
+            }
             return Ok(skis_dir);
         }
 
+        passed_git_root |= current.join(GIT_DIR).exists();
         if !current.pop() {
             return Err(Error::NotARepository);
         }
     }
 }
 
+/// Whether the config in `skis_dir` sets `search_stop_at_git_root`. An
+/// unreadable config is reported when the repository is used, not here.
+fn stops_at_git_root(skis_dir: &Path) -> bool {
+    Config::load(skis_dir).is_ok_and(|c| c.search_stop_at_git_root)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,6 +472,67 @@ mod tests {
         assert!(matches!(result.unwrap_err(), Error::NotARepository));
     }
 
+    #[test]
+    fn finds_skis_from_file_path() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir(dir.path().join(SKIS_DIR)).unwrap();
+        let file = dir.path().join("src/main.rs");
+        std::fs::write(&file, "").unwrap();
+
+        assert_eq!(
+            find_skis_dir_from(&file).unwrap(),
+            dir.path().join(SKIS_DIR)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finds_skis_through_symlinked_dir() {
+        let repo = TempDir::new().unwrap();
+        let deep = repo.path().join("a/b");
+        std::fs::create_dir_all(&deep).unwrap();
+        SkisDb::init(repo.path()).unwrap();
+
+        // A link elsewhere pointing deep into the repository
+        let elsewhere = TempDir::new().unwrap();
+        let link = elsewhere.path().join("link");
+        std::os::unix::fs::symlink(&deep, &link).unwrap();
+
+        let found = find_skis_dir_from(&link.join("file.rs")).unwrap();
+        assert_eq!(
+            found.canonicalize().unwrap(),
+            repo.path().join(SKIS_DIR).canonicalize().unwrap()
+        );
+
+        let db = SkisDb::open_from(&link).unwrap();
+        assert_eq!(
+            db.discovered_root().canonicalize().unwrap(),
+            repo.path().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn open_from_reports_discovered_root() {
+        let dir = TempDir::new().unwrap();
+        SkisDb::init(dir.path()).unwrap();
+        let subdir = dir.path().join("crates/core");
+        std::fs::create_dir_all(&subdir).unwrap();
+
+        let db = SkisDb::open_from(&subdir).unwrap();
+        assert_eq!(db.discovered_root(), dir.path());
+        assert_eq!(db.dir(), dir.path().join(SKIS_DIR));
+    }
+
+    #[test]
+    fn open_from_outside_repository_errors() {
+        let dir = TempDir::new().unwrap();
+        assert!(matches!(
+            SkisDb::open_from(dir.path()),
+            Err(Error::NotARepository)
+        ));
+    }
+
     #[test]
     fn init_creates_skis_directory() {
         let dir = TempDir::new().unwrap();
@@ -161,6 +554,86 @@ mod tests {
         assert!(matches!(result.unwrap_err(), Error::AlreadyInitialized));
     }
 
+    #[test]
+    fn failed_init_removes_skis_directory() {
+        let dir = TempDir::new().unwrap();
+        // SQLite refuses paths over 512 bytes, which the filesystem allows: the
+        // `.skis/` directory is created but the database can't be
+        let deep = (0..6).fold(dir.path().to_path_buf(), |p, i| {
+            p.join(format!("{i}").repeat(100))
+        });
+        std::fs::create_dir_all(&deep).unwrap();
+
+        assert!(SkisDb::init(&deep).is_err());
+        assert!(!deep.join(SKIS_DIR).exists());
+    }
+
+    #[test]
+    fn empty_database_is_reported_as_corrupt() {
+        let dir = TempDir::new().unwrap();
+        let skis_dir = dir.path().join(SKIS_DIR);
+        std::fs::create_dir(&skis_dir).unwrap();
+        std::fs::write(skis_dir.join(DB_FILE), "").unwrap();
+
+        assert!(matches!(
+            SkisDb::open_at(&skis_dir),
+            Err(Error::CorruptRepository(p)) if p.ends_with(DB_FILE)
+        ));
+        assert!(matches!(
+            SkisDb::open_read_only_at(&skis_dir),
+            Err(Error::CorruptRepository(_))
+        ));
+        assert!(matches!(
+            SkisDb::init(dir.path()),
+            Err(Error::CorruptRepository(_))
+        ));
+        // Opening must not have turned the empty file into a database
+        assert_eq!(std::fs::metadata(skis_dir.join(DB_FILE)).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn init_reports_missing_database_as_corrupt() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(SKIS_DIR)).unwrap();
+        assert!(matches!(
+            SkisDb::init(dir.path()),
+            Err(Error::CorruptRepository(_))
+        ));
+    }
+
+    #[test]
+    fn init_force_backs_up_and_recreates_empty_database() {
+        let dir = TempDir::new().unwrap();
+        let skis_dir = dir.path().join(SKIS_DIR);
+        std::fs::create_dir(&skis_dir).unwrap();
+        let db_path = skis_dir.join(DB_FILE);
+        std::fs::write(&db_path, "").unwrap();
+        std::fs::write(skis_dir.join("issues.db.broken"), "older").unwrap();
+
+        let (_db, backup) = SkisDb::init_force(dir.path()).unwrap();
+        assert_eq!(backup, Some(skis_dir.join("issues.db.broken.1")));
+        assert!(SkisDb::open_at(&skis_dir).is_ok());
+
+        // A healthy repository is left alone
+        assert!(matches!(
+            SkisDb::init_force(dir.path()),
+            Err(Error::AlreadyInitialized)
+        ));
+    }
+
+    #[test]
+    fn init_force_creates_missing_database_and_new_repositories() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(SKIS_DIR)).unwrap();
+        let (_db, backup) = SkisDb::init_force(dir.path()).unwrap();
+        assert_eq!(backup, None);
+
+        let fresh = TempDir::new().unwrap();
+        let (_db, backup) = SkisDb::init_force(fresh.path()).unwrap();
+        assert_eq!(backup, None);
+        assert!(fresh.path().join(SKIS_DIR).join(DB_FILE).exists());
+    }
+
     #[test]
     fn open_succeeds_after_init() {
         let dir = TempDir::new().unwrap();
@@ -180,6 +653,261 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn open_upgrades_older_schema() {
+        let dir = TempDir::new().unwrap();
+        let db = SkisDb::init(dir.path()).unwrap();
+        db.conn()
+            .execute_batch(
+                "DROP TRIGGER issues_update_timestamp;
+                 CREATE TRIGGER issues_update_timestamp AFTER UPDATE ON issues BEGIN
+                     UPDATE issues SET updated_at = datetime('now') WHERE id = new.id;
+                 END;
+                 ALTER TABLE issues DROP COLUMN archived_at;
+                 ALTER TABLE issues DROP COLUMN version;
+                 ALTER TABLE issues DROP COLUMN points;
+                 ALTER TABLE issues DROP COLUMN deleted_reason;
+                 ALTER TABLE issue_links DROP COLUMN note;
+                 ALTER TABLE issue_links DROP COLUMN link_type;
+                 ALTER TABLE comments DROP COLUMN pinned;
+                 PRAGMA user_version = 1;",
+            )
+            .unwrap();
+        drop(db);
+
+        let db = SkisDb::open_at(&dir.path().join(SKIS_DIR)).unwrap();
+        assert_eq!(
+            db.schema_version().unwrap(),
+            migrations::LATEST_SCHEMA_VERSION
+        );
+    }
+
+    #[test]
+    fn init_file_and_open_file_use_a_plain_database_file() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("ci.db");
+        assert!(matches!(
+            SkisDb::open_file(&db_path),
+            Err(Error::NotARepository)
+        ));
+
+        let db = SkisDb::init_file(&db_path).unwrap();
+        assert_eq!(db.dir(), dir.path());
+        assert!(!dir.path().join(SKIS_DIR).exists());
+        drop(db);
+        assert!(matches!(
+            SkisDb::init_file(&db_path),
+            Err(Error::AlreadyInitialized)
+        ));
+
+        let db = SkisDb::open_file(&db_path).unwrap();
+        assert_eq!(
+            db.schema_version().unwrap(),
+            migrations::LATEST_SCHEMA_VERSION
+        );
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn encrypted_repository_round_trips() {
+        let dir = TempDir::new().unwrap();
+        let db = SkisDb::init_with_key(dir.path(), Some("s3cret")).unwrap();
+        db.create_issue(&crate::models::IssueCreate {
+            title: "Invoice for ACME".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        drop(db);
+
+        let skis_dir = dir.path().join(SKIS_DIR);
+        let raw = std::fs::read(skis_dir.join(DB_FILE)).unwrap();
+        assert!(!raw.starts_with(b"SQLite format 3"));
+
+        let db = SkisDb::open_at_with_key(&skis_dir, Some("s3cret")).unwrap();
+        assert_eq!(db.issue(1).unwrap().title, "Invoice for ACME");
+        drop(db);
+        assert!(matches!(
+            SkisDb::open_at_with_key(&skis_dir, Some("wrong")),
+            Err(Error::InvalidKey)
+        ));
+        assert!(matches!(
+            SkisDb::open_at_with_key(&skis_dir, None),
+            Err(Error::InvalidKey)
+        ));
+    }
+
+    #[test]
+    fn open_rejects_newer_schema() {
+        let dir = TempDir::new().unwrap();
+        let db = SkisDb::init(dir.path()).unwrap();
+        db.conn().pragma_update(None, "user_version", 99).unwrap();
+        drop(db);
+
+        let skis_dir = dir.path().join(SKIS_DIR);
+        assert!(matches!(
+            SkisDb::open_at(&skis_dir),
+            Err(Error::SchemaTooNew(99, _))
+        ));
+        assert!(matches!(
+            SkisDb::open_read_only_at(&skis_dir),
+            Err(Error::SchemaTooNew(99, _))
+        ));
+    }
+
+    /// Check the settings applied by `configure` are live on a connection
+    fn assert_pragmas(conn: &Connection) {
+        let foreign_keys: bool = conn
+            .pragma_query_value(None, "foreign_keys", |row| row.get(0))
+            .unwrap();
+        assert!(foreign_keys, "foreign_keys pragma is off");
+
+        let busy_timeout: u64 = conn
+            .pragma_query_value(None, "busy_timeout", |row| row.get(0))
+            .unwrap();
+        assert_eq!(busy_timeout, BUSY_TIMEOUT.as_millis() as u64);
+    }
+
+    #[test]
+    fn init_configures_connection() {
+        let dir = TempDir::new().unwrap();
+        let db = SkisDb::init(dir.path()).unwrap();
+        assert_pragmas(db.conn());
+    }
+
+    #[test]
+    fn open_at_configures_connection() {
+        let dir = TempDir::new().unwrap();
+        SkisDb::init(dir.path()).unwrap();
+
+        let db = SkisDb::open_at(&dir.path().join(SKIS_DIR)).unwrap();
+        assert_pragmas(db.conn());
+    }
+
+    #[test]
+    fn read_only_open_configures_connection() {
+        let dir = TempDir::new().unwrap();
+        SkisDb::init(dir.path()).unwrap();
+
+        let db = SkisDb::open_read_only_at(&dir.path().join(SKIS_DIR)).unwrap();
+        assert_pragmas(db.conn());
+
+        let result = db
+            .conn()
+            .execute("INSERT INTO issues (title) VALUES ('nope')", []);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn concurrent_connections_are_each_configured() {
+        let dir = TempDir::new().unwrap();
+        SkisDb::init(dir.path()).unwrap();
+        let skis_dir = dir.path().join(SKIS_DIR);
+
+        let pool: Vec<SkisDb> = (0..4)
+            .map(|_| SkisDb::open_at(&skis_dir).unwrap())
+            .collect();
+        for db in &pool {
+            assert_pragmas(db.conn());
+        }
+    }
+
+    /// A second connection holding the write lock, and a first that gives up on
+    /// it at once instead of waiting out the busy timeout
+    fn contended_repo() -> (TempDir, SkisDb, SkisDb) {
+        contended_repo_with_config("")
+    }
+
+    /// Like `contended_repo`, with `config` in `config.toml` before either opens
+    fn contended_repo_with_config(config: &str) -> (TempDir, SkisDb, SkisDb) {
+        let dir = TempDir::new().unwrap();
+        SkisDb::init(dir.path()).unwrap();
+        let skis_dir = dir.path().join(SKIS_DIR);
+        std::fs::write(skis_dir.join(crate::config::CONFIG_FILE), config).unwrap();
+        let writer = SkisDb::open_at(&skis_dir).unwrap();
+        writer.conn().busy_timeout(Duration::ZERO).unwrap();
+        let holder = SkisDb::open_at(&skis_dir).unwrap();
+        holder.conn().execute_batch("BEGIN EXCLUSIVE").unwrap();
+        (dir, writer, holder)
+    }
+
+    fn insert_issue(conn: &Connection) -> Result<usize> {
+        Ok(conn.execute("INSERT INTO issues (title) VALUES ('Busy')", [])?)
+    }
+
+    #[test]
+    fn retry_busy_succeeds_once_the_lock_is_released() {
+        let (_dir, writer, holder) = contended_repo();
+        assert!(insert_issue(writer.conn()).unwrap_err().is_busy());
+
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            holder.conn().execute_batch("COMMIT").unwrap();
+        });
+        let policy = RetryPolicy {
+            attempts: 10,
+            backoff_ms: 5,
+        };
+        let mut tries = 0;
+        let inserted = retry_busy(&policy, || {
+            tries += 1;
+            insert_issue(writer.conn())
+        })
+        .unwrap();
+        release.join().unwrap();
+
+        assert_eq!(inserted, 1);
+        assert!(tries > 1);
+    }
+
+    #[test]
+    fn retry_busy_gives_up_after_the_configured_attempts() {
+        let (_dir, writer, _holder) = contended_repo();
+        let policy = RetryPolicy {
+            attempts: 2,
+            backoff_ms: 1,
+        };
+        let mut tries = 0;
+        let result = retry_busy(&policy, || {
+            tries += 1;
+            insert_issue(writer.conn())
+        });
+
+        assert!(result.unwrap_err().is_busy());
+        assert_eq!(tries, 3);
+    }
+
+    #[test]
+    fn retry_busy_does_not_retry_other_errors() {
+        let mut tries = 0;
+        let result: Result<()> = retry_busy(&RetryPolicy::default(), || {
+            tries += 1;
+            Err(Error::IssueNotFound(1))
+        });
+
+        assert!(matches!(result, Err(Error::IssueNotFound(1))));
+        assert_eq!(tries, 1);
+    }
+
+    #[test]
+    fn skis_db_with_retry_uses_the_config_read_at_open() {
+        let (dir, writer, _holder) =
+            contended_repo_with_config("[retry]\nattempts = 1\nbackoff_ms = 1\n");
+        // Later edits only apply to databases opened after them
+        std::fs::write(
+            dir.path().join(SKIS_DIR).join(crate::config::CONFIG_FILE),
+            "[retry]\nattempts = 5\nbackoff_ms = 1\n",
+        )
+        .unwrap();
+        let mut tries = 0;
+        let result = writer.with_retry(|conn| {
+            tries += 1;
+            insert_issue(conn)
+        });
+
+        assert!(result.unwrap_err().is_busy());
+        assert_eq!(tries, 2);
+    }
+
     #[test]
     fn foreign_keys_are_enforced() {
         let dir = TempDir::new().unwrap();
@@ -195,4 +923,59 @@ mod tests {
         let err = result.unwrap_err();
         assert!(err.to_string().contains("FOREIGN KEY constraint failed"));
     }
+
+    /// `outer/.skis` with `outer/inner/.git`, optionally confined to its git root
+    fn nested_git_layout(stop_at_git_root: bool) -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("inner/src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir(dir.path().join("inner").join(GIT_DIR)).unwrap();
+        let skis_dir = dir.path().join(SKIS_DIR);
+        std::fs::create_dir(&skis_dir).unwrap();
+        if stop_at_git_root {
+            std::fs::write(
+                skis_dir.join(crate::config::CONFIG_FILE),
+                "search_stop_at_git_root = true\n",
+            )
+            .unwrap();
+        }
+        (dir, src)
+    }
+
+    #[test]
+    fn finds_skis_above_nested_git_repo_by_default() {
+        let (dir, src) = nested_git_layout(false);
+        assert_eq!(find_skis_dir_from(&src).unwrap(), dir.path().join(SKIS_DIR));
+    }
+
+    #[test]
+    fn stops_at_git_root_when_configured() {
+        let (dir, src) = nested_git_layout(true);
+        assert!(matches!(
+            find_skis_dir_from(&src),
+            Err(Error::NotARepository)
+        ));
+        // Still found from inside its own tree, outside the nested repository
+        assert_eq!(
+            find_skis_dir_from(dir.path()).unwrap(),
+            dir.path().join(SKIS_DIR)
+        );
+    }
+
+    #[test]
+    fn stop_at_git_root_allows_skis_beside_git() {
+        let (dir, src) = nested_git_layout(true);
+        let inner = dir.path().join("inner");
+        std::fs::create_dir(inner.join(SKIS_DIR)).unwrap();
+        assert_eq!(find_skis_dir_from(&src).unwrap(), inner.join(SKIS_DIR));
+
+        // `.git` and `.skis` side by side at the top
+        std::fs::create_dir(dir.path().join(GIT_DIR)).unwrap();
+        let other = dir.path().join("other");
+        std::fs::create_dir(&other).unwrap();
+        assert_eq!(
+            find_skis_dir_from(&other).unwrap(),
+            dir.path().join(SKIS_DIR)
+        );
+    }
 }
@@ -1,21 +1,65 @@
-use rusqlite::Connection;
+use rusqlite::{Connection, Transaction, TransactionBehavior};
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 
-#[allow(dead_code)] // Used in tests
-pub const LATEST_SCHEMA_VERSION: i32 = 1;
+/// Schema version this build creates and understands
+pub const LATEST_SCHEMA_VERSION: i32 = 9;
 
-/// Run all pending migrations on the database
+/// The database's schema version (`PRAGMA user_version`)
+pub fn schema_version(conn: &Connection) -> Result<i32> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+/// Fail with `Error::SchemaTooNew` if a newer SKIS has migrated the database
+pub fn check_schema_version(conn: &Connection) -> Result<i32> {
+    let version = schema_version(conn)?;
+    if version > LATEST_SCHEMA_VERSION {
+        return Err(Error::SchemaTooNew(version, LATEST_SCHEMA_VERSION));
+    }
+    Ok(version)
+}
+
+/// A step from one schema version to the next
+type Migration = fn(&Connection) -> Result<()>;
+
+/// Migration steps in order: step `i` upgrades version `i` to `i + 1`
+const MIGRATIONS: [Migration; LATEST_SCHEMA_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+];
+
+/// Run all pending migrations on the database, each in its own transaction.
+/// An up-to-date database is left alone without taking the write lock.
 pub fn run_migrations(conn: &Connection) -> Result<()> {
-    let current_version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let current_version = check_schema_version(conn)?;
 
-    if current_version < 1 {
-        migrate_v0_to_v1(conn)?;
+    for (from, migrate) in (0..).zip(MIGRATIONS).skip(current_version.max(0) as usize) {
+        run_step(conn, from, migrate)?;
     }
 
     Ok(())
 }
 
+/// Run the step from version `from` under the write lock, unless another
+/// connection has already run it while this one waited for the lock. A step
+/// that fails is rolled back whole, so no trigger is left dropped.
+fn run_step(conn: &Connection, from: i32, migrate: Migration) -> Result<()> {
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    if schema_version(&tx)? > from {
+        return Ok(());
+    }
+    migrate(&tx)?;
+    tx.commit()?;
+    Ok(())
+}
+
 /// Initial schema creation (v0 -> v1)
 fn migrate_v0_to_v1(conn: &Connection) -> Result<()> {
     conn.execute_batch(
@@ -111,6 +155,155 @@ fn migrate_v0_to_v1(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Typed links (v1 -> v2). `link_type` is stored from `issue_a_id`'s point of view.
+fn migrate_v1_to_v2(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE issue_links ADD COLUMN link_type TEXT NOT NULL DEFAULT 'relates'
+            CHECK (link_type IN ('relates', 'blocks', 'blocked_by'));
+
+        PRAGMA user_version = 2;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// Accent-insensitive full-text search (v2 -> v3). The FTS table is recreated
+/// with diacritic removal and rebuilt from the existing issues.
+fn migrate_v2_to_v3(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        DROP TRIGGER issues_ai;
+        DROP TRIGGER issues_ad;
+        DROP TRIGGER issues_au;
+        DROP TABLE issues_fts;
+
+        CREATE VIRTUAL TABLE issues_fts USING fts5(
+            title,
+            body,
+            content='issues',
+            content_rowid='id',
+            tokenize='unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER issues_ai AFTER INSERT ON issues BEGIN
+            INSERT INTO issues_fts(rowid, title, body) VALUES (new.id, new.title, new.body);
+        END;
+
+        CREATE TRIGGER issues_ad AFTER DELETE ON issues BEGIN
+            INSERT INTO issues_fts(issues_fts, rowid, title, body) VALUES('delete', old.id, old.title, old.body);
+        END;
+
+        CREATE TRIGGER issues_au AFTER UPDATE ON issues BEGIN
+            INSERT INTO issues_fts(issues_fts, rowid, title, body) VALUES('delete', old.id, old.title, old.body);
+            INSERT INTO issues_fts(rowid, title, body) VALUES (new.id, new.title, new.body);
+        END;
+
+        INSERT INTO issues_fts(issues_fts) VALUES('rebuild');
+
+        PRAGMA user_version = 3;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// Pinned comments (v3 -> v4)
+fn migrate_v3_to_v4(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE comments ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+        PRAGMA user_version = 4;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// Optional note explaining a link (v4 -> v5)
+fn migrate_v4_to_v5(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE issue_links ADD COLUMN note TEXT;
+
+        PRAGMA user_version = 5;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// Optional story points per issue (v5 -> v6)
+fn migrate_v5_to_v6(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE issues ADD COLUMN points INTEGER;
+
+        PRAGMA user_version = 6;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// Optional reason recorded when an issue is soft-deleted (v6 -> v7)
+fn migrate_v6_to_v7(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE issues ADD COLUMN deleted_reason TEXT;
+
+        PRAGMA user_version = 7;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// Per-issue version for optimistic locking (v7 -> v8). The update trigger
+/// bumps it alongside `updated_at`, so every UPDATE counts.
+///
+/// SQLite runs the newest trigger first, and the FTS trigger must see the
+/// row before the timestamp trigger rewrites it, so it is recreated last.
+fn migrate_v7_to_v8(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE issues ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+
+        DROP TRIGGER issues_update_timestamp;
+        DROP TRIGGER issues_au;
+
+        CREATE TRIGGER issues_update_timestamp AFTER UPDATE ON issues BEGIN
+            UPDATE issues SET updated_at = datetime('now'), version = old.version + 1
+            WHERE id = new.id;
+        END;
+
+        CREATE TRIGGER issues_au AFTER UPDATE ON issues BEGIN
+            INSERT INTO issues_fts(issues_fts, rowid, title, body) VALUES('delete', old.id, old.title, old.body);
+            INSERT INTO issues_fts(rowid, title, body) VALUES (new.id, new.title, new.body);
+        END;
+
+        PRAGMA user_version = 8;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// When an issue was archived (v8 -> v9)
+fn migrate_v8_to_v9(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE issues ADD COLUMN archived_at TEXT;
+
+        PRAGMA user_version = 9;
+        "#,
+    )?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +328,26 @@ mod tests {
         assert_eq!(version, LATEST_SCHEMA_VERSION);
     }
 
+    #[test]
+    fn v3_rebuilds_fts_without_accents() {
+        let (conn, _dir) = test_db();
+        migrate_v0_to_v1(&conn).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        conn.execute("INSERT INTO issues (title) VALUES ('Crème brûlée')", [])
+            .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM issues_fts WHERE issues_fts MATCH 'creme brulee'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
     #[test]
     fn migration_is_idempotent() {
         let (conn, _dir) = test_db();
@@ -247,6 +460,99 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn v1_links_migrate_as_relates() {
+        let (conn, _dir) = test_db();
+        migrate_v0_to_v1(&conn).unwrap();
+        conn.execute("INSERT INTO issues (id, title) VALUES (1, 'Issue 1')", [])
+            .unwrap();
+        conn.execute("INSERT INTO issues (id, title) VALUES (2, 'Issue 2')", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO issue_links (issue_a_id, issue_b_id) VALUES (1, 2)",
+            [],
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let link_type: String = conn
+            .query_row("SELECT link_type FROM issue_links", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(link_type, "relates");
+
+        let result = conn.execute("UPDATE issue_links SET link_type = 'owns'", []);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn v4_links_migrate_without_note() {
+        let (conn, _dir) = test_db();
+        migrate_v0_to_v1(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO issues (id, title) VALUES (1, 'Issue 1'), (2, 'Issue 2');
+             INSERT INTO issue_links (issue_a_id, issue_b_id) VALUES (1, 2);",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let note: Option<String> = conn
+            .query_row("SELECT note FROM issue_links", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(note, None);
+    }
+
+    #[test]
+    fn newer_schema_is_rejected() {
+        let (conn, _dir) = test_db();
+        run_migrations(&conn).unwrap();
+        conn.pragma_update(None, "user_version", LATEST_SCHEMA_VERSION + 1)
+            .unwrap();
+
+        let result = run_migrations(&conn);
+        assert!(matches!(
+            result,
+            Err(Error::SchemaTooNew(found, supported))
+                if found == LATEST_SCHEMA_VERSION + 1 && supported == LATEST_SCHEMA_VERSION
+        ));
+    }
+
+    #[test]
+    fn step_already_run_by_another_connection_is_skipped() {
+        let (conn, _dir) = test_db();
+        run_migrations(&conn).unwrap();
+
+        // As if this connection read version 8 before another one upgraded it
+        run_step(&conn, 8, migrate_v8_to_v9).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), LATEST_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn failed_step_is_rolled_back() {
+        let (conn, _dir) = test_db();
+        run_migrations(&conn).unwrap();
+
+        let result = run_step(&conn, LATEST_SCHEMA_VERSION, |conn| {
+            conn.execute_batch(
+                "DROP TRIGGER issues_au; PRAGMA user_version = 10; SELECT * FROM missing;",
+            )?;
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(schema_version(&conn).unwrap(), LATEST_SCHEMA_VERSION);
+        let triggers: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'trigger' AND name = 'issues_au'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(triggers, 1);
+    }
+
     #[test]
     fn updated_at_trigger_fires() {
         let (conn, _dir) = test_db();
@@ -295,6 +601,6 @@ mod tests {
         // After should be different from our manually set timestamp
         assert_ne!(before, after);
         // After should be much more recent than 2020
-        assert!(after > "2024-01-01 00:00:00".to_string());
+        assert!(after.as_str() > "2024-01-01 00:00:00");
     }
 }
@@ -1,12 +1,27 @@
+mod api;
 mod connection;
 mod migrations;
 mod queries;
 
-pub use connection::SkisDb;
+#[cfg(feature = "sqlcipher")]
+pub use connection::SKIS_KEY_ENV;
+pub use connection::{
+    db_path_from_env, find_skis_dir, find_skis_dir_from, SkisDb, SKIS_DB_ENV, SKIS_DIR_ENV,
+};
+pub use migrations::LATEST_SCHEMA_VERSION;
 pub use queries::{
-    add_comment, add_label_to_issue, add_link, close_issue, close_issue_with_comment, create_issue,
-    create_label, delete_comment, delete_issue, delete_label, get_comments, get_issue,
-    get_issue_labels, get_linked_issues, get_linked_issues_with_titles, list_issues, list_labels,
-    remove_label_from_issue, remove_link, reopen_issue, restore_issue, search_issues, update_comment,
-    update_issue,
+    add_comment, add_comment_with, add_label_to_issue, add_link, add_link_with_note, add_links,
+    archive_issue, attach_labels, backdate_comment, backdate_issues, blocked_issue_ids,
+    build_list_query, build_search_query, close_issue, close_issue_with_comment, count_issues,
+    count_search_results, create_issue, create_issue_with, create_label, delete_comment,
+    delete_issue, delete_issue_with_reason, delete_label, delete_labels, digest,
+    find_empty_comments, find_labels, get_comments, get_issue, get_issue_labels,
+    get_issue_with_labels, get_issues, get_labels_for_issues, get_linked_issues,
+    get_linked_issues_with_titles, issue_counts_by_type, label_case_mismatches, list_issues,
+    list_issues_page, list_issues_with_activity, list_issues_with_labels, list_labels,
+    list_labels_with_usage, list_unused_labels, open_points_by_type, rebuild_fts,
+    remove_label_from_issue, remove_link, remove_links, rename_label, rename_labels, reopen_issue,
+    restore_issue, search_issues, search_issues_page, search_issues_with_activity,
+    search_issues_with_labels, set_comment_pinned, suggest_label, unarchive_issue, update_comment,
+    update_comment_with, update_issue, update_issue_with, update_issues,
 };
@@ -8,14 +8,62 @@ pub enum Error {
     #[error("Already initialized")]
     AlreadyInitialized,
 
+    #[error("The repository already has issues or labels. Pass --force to add fixtures anyway.")]
+    RepositoryNotEmpty,
+
+    #[error("The database at {0} is missing or empty. Run 'skis init --force' to back it up and create a new one.")]
+    CorruptRepository(String),
+
+    #[cfg(feature = "sqlcipher")]
+    #[error("Wrong or missing key for the encrypted database. Set SKIS_KEY or pass --key-file.")]
+    InvalidKey,
+
+    #[error("Database schema version {0} is newer than this version of SKIS supports ({1}). Update SKIS to open it.")]
+    SchemaTooNew(i32, i32),
+
+    #[error("The SKIS repository belongs to {0}, not the current directory. Run 'skis deinit' from there.")]
+    NotRepositoryRoot(String),
+
     #[error("Issue #{0} not found")]
     IssueNotFound(i64),
 
+    #[error("Invalid issue number '{0}'")]
+    InvalidIssueNumber(String),
+
+    #[error("{0} of {1} issues failed")]
+    BulkFailed(usize, usize),
+
+    #[error("Nothing to edit — pass --title/--body/--body-file/--editor/--type/--add-label/--remove-label/--points")]
+    NothingToEdit,
+
+    #[error("--body - and --body-file - both read stdin; pass only one")]
+    BodyStdinTwice,
+
     #[error("Comment #{0} not found")]
     CommentNotFound(i64),
 
-    #[error("Label '{0}' not found. Create it with: skis label create {0}")]
-    LabelNotFound(String),
+    #[error("Comment cannot be empty")]
+    EmptyComment,
+
+    #[error("Comment is too long ({0} characters, maximum is {1})")]
+    CommentTooLong(usize, usize),
+
+    /// The second field is the closest existing label name, if any is near
+    #[error("Label '{0}' not found.{} Create it with: skis label create {0}", did_you_mean(.1))]
+    LabelNotFound(String, Option<String>),
+
+    #[error("Label '{0}' already exists")]
+    LabelExists(String),
+
+    /// One line per clash found while planning a bulk rename
+    #[error("Label renames clash, nothing was renamed:\n{0}")]
+    LabelRenameConflict(String),
+
+    #[error("Invalid regex '{0}': {1}")]
+    InvalidRegex(String, String),
+
+    #[error("Invalid config file {0}: {1}")]
+    InvalidConfig(String, String),
 
     #[error("Issue #{0} is already {1}")]
     InvalidStateTransition(i64, String),
@@ -29,12 +77,79 @@ pub enum Error {
     #[error("Invalid state reason '{0}': must be completed or not_planned")]
     InvalidStateReason(String),
 
+    #[error("Issue #{0} is deleted. Restore it first with: skis issue restore {0}")]
+    IssueDeleted(i64),
+
+    #[error("Issue #{0} is not archived")]
+    NotArchived(i64),
+
+    #[error("The issue was changed elsewhere (it is now at version {current_version}). Reload it and try again.")]
+    Conflict { current_version: i64 },
+
+    #[error("Invalid link type '{0}': must be relates, blocks, or blocked-by")]
+    InvalidLinkType(String),
+
     #[error("Cannot link issue to itself")]
     SelfLink,
 
     #[error("Link already exists between issues #{0} and #{1}")]
     DuplicateLink(i64, i64),
 
+    #[error("Invalid duration '{0}': use a number followed by m, h, d, or w (e.g., 14d)")]
+    InvalidDuration(String),
+
+    #[error("Invalid --since '{0}': expected a duration (14d), a date (2024-01-31), or a git tag")]
+    InvalidSince(String),
+
+    #[error("invalid state '{0}', must be open, closed, or all")]
+    InvalidStateFilter(String),
+
+    #[error(
+        "invalid sort field '{0}', must be updated, created, closed, activity, comments, id, or title"
+    )]
+    InvalidSortField(String),
+
+    #[error("invalid sort order '{0}', must be asc or desc")]
+    InvalidSortOrder(String),
+
+    #[error("invalid search scope '{0}', must be title, body, or all")]
+    InvalidSearchScope(String),
+
+    #[error("Invalid format '{0}': must be table, json, or tsv")]
+    InvalidOutputFormat(String),
+
+    #[error("Invalid reference format '{0}': must be plain or markdown")]
+    InvalidReferenceFormat(String),
+
+    #[error("Invalid field '{0}': must be id, type, state, labels, updated, deleted, or title")]
+    InvalidField(String),
+
+    #[error(
+        "Invalid field '{0}': must be one of id, title, body, type, state, state_reason, points, labels, created_at, updated_at, closed_at, deleted_at, archived_at"
+    )]
+    InvalidGetField(String),
+
+    #[error("Invalid time format '{0}': must be rfc3339 or epoch")]
+    InvalidTimeFormat(String),
+
+    #[error("Invalid digest format '{0}': must be text or html")]
+    InvalidDigestFormat(String),
+
+    #[error("Invalid release notes format '{0}': must be markdown")]
+    InvalidReleaseNotesFormat(String),
+
+    #[error("Invalid --color '{0}': must be auto, always, or never")]
+    InvalidColorChoice(String),
+
+    #[error("Invalid theme '{0}': must be light or dark")]
+    InvalidTheme(String),
+
+    #[error("Invalid label sort '{0}': must be name or usage")]
+    InvalidLabelSort(String),
+
+    #[error("Invalid page cursor '{0}'")]
+    InvalidCursor(String),
+
     #[error("{0}: not yet implemented")]
     NotImplemented(String),
 
@@ -50,6 +165,27 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Whether SQLite gave up because another connection holds the database
+    /// (SQLITE_BUSY or SQLITE_LOCKED); such a write may succeed if tried again
+    pub fn is_busy(&self) -> bool {
+        match self {
+            Error::Sqlite(rusqlite::Error::SqliteFailure(e, _)) => matches!(
+                e.code,
+                rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+            ),
+            _ => false,
+        }
+    }
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    suggestion
+        .as_ref()
+        .map(|s| format!(" Did you mean '{}'?", s))
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,12 +199,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_corrupt_repository_suggests_force() {
+        let msg = Error::CorruptRepository("/work/.skis/issues.db".to_string()).to_string();
+        assert!(msg.contains("/work/.skis/issues.db is missing or empty"));
+        assert!(msg.contains("skis init --force"));
+    }
+
+    #[test]
+    fn error_not_repository_root_names_owner() {
+        let err = Error::NotRepositoryRoot("/work/project".to_string());
+        let msg = err.to_string();
+        assert!(msg.contains("belongs to /work/project"));
+        assert!(msg.contains("skis deinit"));
+    }
+
+    #[test]
+    fn error_nothing_to_edit_lists_flags() {
+        let msg = Error::NothingToEdit.to_string();
+        assert!(msg.starts_with("Nothing to edit"));
+        assert!(msg.contains("--title"));
+        assert!(msg.contains("--add-label"));
+    }
+
     #[test]
     fn error_label_not_found_suggests_create() {
-        let err = Error::LabelNotFound("bug".to_string());
+        let err = Error::LabelNotFound("bug".to_string(), None);
         let msg = err.to_string();
         assert!(msg.contains("Label 'bug' not found"));
         assert!(msg.contains("skis label create bug"));
+        assert!(!msg.contains("Did you mean"));
+    }
+
+    #[test]
+    fn error_label_not_found_suggests_near_miss() {
+        let err = Error::LabelNotFound("backnd".to_string(), Some("backend".to_string()));
+        assert_eq!(
+            err.to_string(),
+            "Label 'backnd' not found. Did you mean 'backend'? Create it with: skis label create backnd"
+        );
+    }
+
+    #[test]
+    fn error_invalid_config_message() {
+        let err = Error::InvalidConfig(
+            ".skis/config.toml".to_string(),
+            "unknown field `bgu`".to_string(),
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid config file .skis/config.toml: unknown field `bgu`"
+        );
+    }
+
+    #[test]
+    fn error_label_exists_message() {
+        let err = Error::LabelExists("café".to_string());
+        assert_eq!(err.to_string(), "Label 'café' already exists");
+    }
+
+    #[test]
+    fn error_schema_too_new_shows_both_versions() {
+        let err = Error::SchemaTooNew(3, 2);
+        let msg = err.to_string();
+        assert!(msg.contains("version 3"));
+        assert!(msg.contains("supports (2)"));
+        assert!(msg.contains("Update SKIS"));
     }
 
     #[test]
@@ -91,6 +287,26 @@ mod tests {
         assert_eq!(err.to_string(), "Issue #999 not found");
     }
 
+    #[test]
+    fn error_invalid_issue_number_message() {
+        let err = Error::InvalidIssueNumber("abc".to_string());
+        assert_eq!(err.to_string(), "Invalid issue number 'abc'");
+    }
+
+    #[test]
+    fn error_bulk_failed_message() {
+        let err = Error::BulkFailed(2, 5);
+        assert_eq!(err.to_string(), "2 of 5 issues failed");
+    }
+
+    #[test]
+    fn error_issue_deleted_suggests_restore() {
+        let err = Error::IssueDeleted(9);
+        let msg = err.to_string();
+        assert!(msg.contains("Issue #9 is deleted"));
+        assert!(msg.contains("skis issue restore 9"));
+    }
+
     #[test]
     fn error_invalid_issue_type_message() {
         let err = Error::InvalidIssueType("foo".to_string());
@@ -106,4 +322,126 @@ mod tests {
         assert!(msg.contains("Invalid state reason 'bar'"));
         assert!(msg.contains("completed or not_planned"));
     }
+
+    #[test]
+    fn error_comment_too_long_message() {
+        let err = Error::CommentTooLong(70000, 65536);
+        assert_eq!(
+            err.to_string(),
+            "Comment is too long (70000 characters, maximum is 65536)"
+        );
+    }
+
+    #[test]
+    fn error_invalid_field_lists_choices() {
+        let msg = Error::InvalidField("assignee".to_string()).to_string();
+        assert!(msg.contains("'assignee'"));
+        assert!(msg.contains("id, type, state, labels, updated, deleted, or title"));
+    }
+
+    #[test]
+    fn error_invalid_time_format_message() {
+        let msg = Error::InvalidTimeFormat("unix".to_string()).to_string();
+        assert_eq!(msg, "Invalid time format 'unix': must be rfc3339 or epoch");
+    }
+
+    #[test]
+    fn error_invalid_digest_format_message() {
+        let msg = Error::InvalidDigestFormat("pdf".to_string()).to_string();
+        assert_eq!(msg, "Invalid digest format 'pdf': must be text or html");
+    }
+
+    #[test]
+    fn error_invalid_theme_message() {
+        let msg = Error::InvalidTheme("sepia".to_string()).to_string();
+        assert_eq!(msg, "Invalid theme 'sepia': must be light or dark");
+    }
+
+    #[test]
+    fn error_invalid_label_sort_message() {
+        let msg = Error::InvalidLabelSort("color".to_string()).to_string();
+        assert_eq!(msg, "Invalid label sort 'color': must be name or usage");
+    }
+
+    #[test]
+    fn error_conflict_message() {
+        let msg = Error::Conflict { current_version: 4 }.to_string();
+        assert!(msg.contains("version 4"));
+        assert!(msg.contains("Reload"));
+    }
+
+    #[test]
+    fn error_invalid_release_notes_format_message() {
+        let msg = Error::InvalidReleaseNotesFormat("html".to_string()).to_string();
+        assert_eq!(msg, "Invalid release notes format 'html': must be markdown");
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn error_invalid_key_message() {
+        let msg = Error::InvalidKey.to_string();
+        assert!(msg.contains("Wrong or missing key"));
+        assert!(msg.contains("SKIS_KEY"));
+    }
+
+    #[test]
+    fn error_not_archived_message() {
+        let msg = Error::NotArchived(4).to_string();
+        assert_eq!(msg, "Issue #4 is not archived");
+    }
+
+    #[test]
+    fn error_invalid_color_choice_message() {
+        let msg = Error::InvalidColorChoice("sometimes".to_string()).to_string();
+        assert_eq!(
+            msg,
+            "Invalid --color 'sometimes': must be auto, always, or never"
+        );
+    }
+
+    #[test]
+    fn error_invalid_get_field_lists_choices() {
+        let msg = Error::InvalidGetField("assignee".to_string()).to_string();
+        assert!(msg.contains("'assignee'"));
+        assert!(msg.contains("state_reason, points, labels, created_at"));
+    }
+
+    #[test]
+    fn error_label_rename_conflict_message() {
+        let err = Error::LabelRenameConflict("  P1, p1 -> 'prio/p1': same new name".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Label renames clash, nothing was renamed:\n  P1, p1 -> 'prio/p1': same new name"
+        );
+        let err = Error::InvalidRegex("^P(".to_string(), "unclosed group".to_string());
+        assert_eq!(err.to_string(), "Invalid regex '^P(': unclosed group");
+    }
+
+    #[test]
+    fn error_body_stdin_twice_message() {
+        assert_eq!(
+            Error::BodyStdinTwice.to_string(),
+            "--body - and --body-file - both read stdin; pass only one"
+        );
+    }
+
+    #[test]
+    fn error_invalid_list_options_match_cli_wording() {
+        assert_eq!(
+            Error::InvalidStateFilter("shut".to_string()).to_string(),
+            "invalid state 'shut', must be open, closed, or all"
+        );
+        assert_eq!(
+            Error::InvalidSortField("name".to_string()).to_string(),
+            "invalid sort field 'name', must be updated, created, closed, activity, comments, id, or title"
+        );
+        assert_eq!(
+            Error::InvalidSortOrder("up".to_string()).to_string(),
+            "invalid sort order 'up', must be asc or desc"
+        );
+        assert_eq!(
+            Error::InvalidSearchScope("labels".to_string()).to_string(),
+            "invalid search scope 'labels', must be title, body, or all"
+        );
+    }
 }
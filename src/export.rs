@@ -0,0 +1,180 @@
+//! Whole-repository JSON export
+//!
+//! The output is deterministic so exports can be committed and diffed:
+//! issues are ordered by id, labels by name, comments by id and linked
+//! issues by id.
+
+use chrono::{DateTime, SubsecRound, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::db;
+use crate::error::Result;
+use crate::models::{Comment, IssueFilter, IssueView, Label, SortField, SortOrder};
+
+/// An exported issue: the `issue view --json` shape plus its comments
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedIssue {
+    #[serde(flatten)]
+    pub issue: IssueView,
+    pub comments: Vec<Comment>,
+}
+
+/// An export of the selected issues (by default every non-deleted one, archived
+/// ones included) and all labels
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportData {
+    pub issues: Vec<ExportedIssue>,
+    pub labels: Vec<Label>,
+    /// Omitted from canonical exports so repeated exports are byte-identical
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exported_at: Option<DateTime<Utc>>,
+}
+
+/// Export every non-deleted issue, archived or not, with its labels, links
+/// and comments.
+///
+/// A `canonical` export truncates timestamps to whole seconds (RFC 3339, `Z`)
+/// and leaves out `exported_at`.
+pub fn export_all(conn: &Connection, canonical: bool) -> Result<ExportData> {
+    let filter = IssueFilter {
+        sort_by: SortField::Id,
+        sort_order: SortOrder::Asc,
+        limit: i64::MAX as usize,
+        include_archived: true,
+        ..Default::default()
+    };
+    export_issues(conn, None, &filter, canonical)
+}
+
+/// Export the issues `filter` selects (searching for `search` if given), like
+/// [`export_all`]. The filter's sort order decides which issues fall within its
+/// limit; the exported issues are still ordered by id.
+pub fn export_issues(
+    conn: &Connection,
+    search: Option<&str>,
+    filter: &IssueFilter,
+    canonical: bool,
+) -> Result<ExportData> {
+    let mut selected = match search {
+        Some(query) => db::search_issues_with_labels(conn, query, filter)?,
+        None => db::list_issues_with_labels(conn, filter)?,
+    };
+    selected.sort_by_key(|i| i.id);
+
+    let mut issues = Vec::new();
+    for issue in selected {
+        let mut linked_issues = db::get_linked_issues_with_titles(conn, issue.id)?;
+        linked_issues.sort_by_key(|l| l.id);
+        let mut comments = db::get_comments(conn, issue.id)?;
+        comments.sort_by_key(|c| c.id);
+
+        let mut exported = ExportedIssue {
+            issue: IssueView::new(issue, linked_issues),
+            comments,
+        };
+        if canonical {
+            canonicalize_issue(&mut exported);
+        }
+        issues.push(exported);
+    }
+
+    let mut labels = db::list_labels(conn)?;
+    labels.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(ExportData {
+        issues,
+        labels,
+        exported_at: (!canonical).then(Utc::now),
+    })
+}
+
+fn canonicalize_issue(exported: &mut ExportedIssue) {
+    let issue = &mut exported.issue;
+    issue.created_at = issue.created_at.trunc_subsecs(0);
+    issue.updated_at = issue.updated_at.trunc_subsecs(0);
+    issue.closed_at = issue.closed_at.map(|t| t.trunc_subsecs(0));
+    issue.deleted_at = issue.deleted_at.map(|t| t.trunc_subsecs(0));
+    issue.archived_at = issue.archived_at.map(|t| t.trunc_subsecs(0));
+
+    for comment in &mut exported.comments {
+        comment.created_at = comment.created_at.trunc_subsecs(0);
+        comment.updated_at = comment.updated_at.trunc_subsecs(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SkisDb;
+    use crate::models::{IssueCreate, LinkType};
+    use tempfile::TempDir;
+
+    fn test_db() -> (SkisDb, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let db = SkisDb::init(dir.path()).unwrap();
+        (db, dir)
+    }
+
+    #[test]
+    fn canonical_export_is_byte_identical() {
+        let (db, _dir) = test_db();
+        db::create_label(db.conn(), "urgent", None, None).unwrap();
+        db::create_label(db.conn(), "bug", None, None).unwrap();
+        for title in ["First", "Second", "Third"] {
+            db::create_issue(
+                db.conn(),
+                &IssueCreate {
+                    title: title.to_string(),
+                    labels: vec!["urgent".to_string(), "bug".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        db::add_link(db.conn(), 3, 1, LinkType::Relates, false).unwrap();
+        db::add_link(db.conn(), 1, 2, LinkType::Relates, false).unwrap();
+        db::add_comment(db.conn(), 2, "A comment").unwrap();
+
+        let first = serde_json::to_string_pretty(&export_all(db.conn(), true).unwrap()).unwrap();
+        let second = serde_json::to_string_pretty(&export_all(db.conn(), true).unwrap()).unwrap();
+
+        assert_eq!(first, second);
+        assert!(!first.contains("exported_at"));
+    }
+
+    #[test]
+    fn export_orders_issues_labels_and_links() {
+        let (db, _dir) = test_db();
+        db::create_label(db.conn(), "zeta", None, None).unwrap();
+        db::create_label(db.conn(), "alpha", None, None).unwrap();
+        for title in ["One", "Two", "Three"] {
+            db::create_issue(
+                db.conn(),
+                &IssueCreate {
+                    title: title.to_string(),
+                    labels: vec!["zeta".to_string(), "alpha".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        db::add_link(db.conn(), 1, 3, LinkType::Relates, false).unwrap();
+        db::add_link(db.conn(), 1, 2, LinkType::Relates, false).unwrap();
+
+        let data = export_all(db.conn(), false).unwrap();
+
+        let ids: Vec<i64> = data.issues.iter().map(|i| i.issue.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        let labels: Vec<&str> = data.labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(labels, ["alpha", "zeta"]);
+        let links: Vec<i64> = data.issues[0]
+            .issue
+            .linked_issues
+            .iter()
+            .map(|l| l.id)
+            .collect();
+        assert_eq!(links, [2, 3]);
+        assert!(data.exported_at.is_some());
+    }
+}
@@ -1,11 +1,21 @@
+pub mod config;
 pub mod db;
 pub mod error;
+pub mod export;
 pub mod models;
+pub mod next;
 pub mod output;
+pub mod platform;
+pub mod templates;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod util;
 
 pub use db::SkisDb;
 pub use error::{Error, Result};
+pub use config::Config;
 pub use models::{
-    Comment, Issue, IssueCreate, IssueFilter, IssueLink, IssueState, IssueType, IssueUpdate,
-    Label, LinkedIssueRef, SortField, SortOrder, StateReason,
+    Comment, CreateOpts, Cursor, Issue, IssueActivity, IssueCreate, IssueFilter, IssueLink,
+    IssuePage, IssueState, IssueType, IssueUpdate, Label, LinkType, LinkedIssueRef, SearchScope,
+    SortField, SortOrder, StateFilter, StateReason,
 };
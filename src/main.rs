@@ -11,20 +11,172 @@ mod commands;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Read the key of an encrypted database from this file (overrides SKIS_KEY)
+    #[cfg(feature = "sqlcipher")]
+    #[arg(long, global = true, value_name = "FILE")]
+    key_file: Option<std::path::PathBuf>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize a new SKIS repository
-    Init,
+    Init(InitArgs),
+    /// Remove SKIS from the current directory (deletes .skis/ and all issues)
+    Deinit(DeinitArgs),
     /// Manage issues
     #[command(subcommand)]
-    Issue(IssueCommands),
+    Issue(Box<IssueCommands>),
     /// Manage labels
     #[command(subcommand)]
     Label(LabelCommands),
+    /// Generate Markdown release notes from recently closed issues
+    Changelog(ChangelogArgs),
+    /// Generate grouped release notes from issues closed under a label or since a point
+    ReleaseNotes(ReleaseNotesArgs),
+    /// Summarize recent activity for an email or status update
+    Digest(DigestArgs),
+    /// Check the database for problems
+    Doctor(DoctorArgs),
+    /// Export all issues, labels and comments as JSON
+    Export(ExportArgs),
+    /// Sum story points of open issues by type
+    Status(StatusArgs),
+    /// Count issues
+    #[command(subcommand)]
+    Stats(StatsCommands),
+    /// Rebuild the full-text search index
+    Reindex,
     /// Show the GUI log file path
     LogPath,
+    /// Fill a repository with generated issues for demos and bug reports
+    #[command(subcommand)]
+    Fixtures(FixturesCommands),
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Recreate a missing or empty database, keeping a backup of the broken file
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct DeinitArgs {
+    /// Skip confirmation prompts
+    #[arg(long)]
+    pub yes: bool,
+}
+
+#[derive(Args)]
+pub struct ChangelogArgs {
+    /// Start point: a duration (14d), a date (2024-01-31), or a git tag (v0.9)
+    #[arg(long)]
+    pub since: String,
+
+    /// Group entries by: type, label
+    #[arg(long, default_value = "type")]
+    pub group_by: String,
+
+    /// Include issues closed as not planned
+    #[arg(long)]
+    pub include_wontfix: bool,
+}
+
+#[derive(Args)]
+pub struct ReleaseNotesArgs {
+    /// Include closed issues with this label, e.g. release/1.4
+    #[arg(long, required_unless_present = "since")]
+    pub label: Option<String>,
+
+    /// Include issues closed since a duration (14d), a date (2024-01-31), or a git tag (v0.9)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Document format: markdown
+    #[arg(long, default_value = "markdown")]
+    pub format: String,
+
+    /// Write the release notes to this file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<String>,
+
+    /// Include issues closed as not planned
+    #[arg(long)]
+    pub include_wontfix: bool,
+}
+
+#[derive(Args)]
+pub struct DigestArgs {
+    /// Start point: a duration (1w), a date (2024-01-31), or a git tag (v0.9)
+    #[arg(long, default_value = "1w")]
+    pub since: String,
+
+    /// Document format: text, html
+    #[arg(long, default_value = "text")]
+    pub format: String,
+
+    /// Write the digest to this file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Repair the problems found
+    #[arg(long)]
+    pub fix: bool,
+}
+
+#[derive(Args)]
+pub struct StatusArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Subcommand)]
+enum StatsCommands {
+    /// Open and closed issues per type
+    Types(StatsTypesArgs),
+}
+
+#[derive(Subcommand)]
+enum FixturesCommands {
+    /// Add seeded pseudo-random issues, labels, comments and links
+    Generate(FixturesGenerateArgs),
+}
+
+#[derive(Args)]
+pub struct FixturesGenerateArgs {
+    /// Number of issues to create
+    #[arg(long, value_name = "N", default_value_t = 100)]
+    pub issues: usize,
+
+    /// Seed for the generator; the same seed gives the same issues
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Add to a repository that already has issues or labels
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct StatsTypesArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Omit the export time and truncate timestamps to whole seconds
+    #[arg(long)]
+    pub canonical: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
 }
 
 #[derive(Subcommand)]
@@ -33,11 +185,24 @@ enum IssueCommands {
     Create(IssueCreateArgs),
     /// List issues
     #[command(alias = "ls")]
-    List(IssueListArgs),
+    List(Box<IssueListArgs>),
     /// View an issue
     View(IssueViewArgs),
+    /// Print one field of an issue, for scripts
+    Get(IssueGetArgs),
+    /// Print just the body of an issue, for piping into other tools
+    Body(IssueBodyArgs),
+    /// Pick an open, unblocked issue to work on and show it
+    Next(IssueNextArgs),
+    /// Open an issue in the SKIS GUI (falls back to the terminal view)
+    Browse(IssueBrowseArgs),
+    /// Print a one-line reference to an issue
+    Copy(IssueCopyArgs),
     /// Edit an issue
     Edit(IssueEditArgs),
+    /// Change the type of one or more issues
+    #[command(alias = "set-type")]
+    Retype(IssueRetypeArgs),
     /// Close an issue
     Close(IssueCloseArgs),
     /// Reopen a closed issue
@@ -46,6 +211,10 @@ enum IssueCommands {
     Delete(IssueDeleteArgs),
     /// Restore a soft-deleted issue
     Restore(IssueRestoreArgs),
+    /// File an issue away, hiding it from lists unless --archived is given
+    Archive(IssueArchiveArgs),
+    /// Return an archived issue to lists
+    Unarchive(IssueArchiveArgs),
     /// Add a comment to an issue
     Comment(IssueCommentArgs),
     /// Link two issues
@@ -60,7 +229,7 @@ pub struct IssueCreateArgs {
     #[arg(short, long)]
     pub title: Option<String>,
 
-    /// Issue body
+    /// Issue body (use - for stdin)
     #[arg(short, long)]
     pub body: Option<String>,
 
@@ -76,21 +245,63 @@ pub struct IssueCreateArgs {
     #[arg(short = 'T', long = "type", default_value = "task")]
     pub issue_type: String,
 
-    /// Add label(s), can be repeated
+    /// Add label(s), can be repeated or comma-separated
     #[arg(short, long = "label", action = clap::ArgAction::Append)]
     pub labels: Vec<String>,
+
+    /// Story points estimate
+    #[arg(long, value_name = "N")]
+    pub points: Option<u32>,
+
+    /// Don't add the configured title prefix for this type
+    #[arg(long)]
+    pub no_prefix: bool,
+
+    /// Print the created issue as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
+/// Ids of the `FilterArgs` flags. Bulk commands take them only with `--matching`,
+/// never alongside issue numbers or `--stdin`.
+const FILTER_FLAGS: &[&str] = &[
+    "state",
+    "search",
+    "search_in",
+    "issue_type",
+    "labels",
+    "exclude_labels",
+    "sort",
+    "order",
+    "limit",
+    "offset",
+    "deleted",
+    "only_deleted",
+    "archived",
+    "closed_since",
+    "label_prefix",
+    "has_link",
+    "no_link",
+    "commented",
+    "uncommented",
+    "min_points",
+];
+
+/// Issue filter flags shared by commands that select issues
 #[derive(Args)]
-pub struct IssueListArgs {
-    /// Filter by state: open, closed, all
-    #[arg(short, long, default_value = "open")]
-    pub state: String,
+pub struct FilterArgs {
+    /// Filter by state: open, closed, all [default: list.default_state, or open]
+    #[arg(short, long)]
+    pub state: Option<String>,
 
     /// Search query (full-text)
     #[arg(long)]
     pub search: Option<String>,
 
+    /// Where --search looks: title, body, all
+    #[arg(long, default_value = "all", requires = "search")]
+    pub search_in: String,
+
     /// Filter by type: epic, task, bug, request
     #[arg(short = 'T', long = "type")]
     pub issue_type: Option<String>,
@@ -99,17 +310,21 @@ pub struct IssueListArgs {
     #[arg(short, long = "label", action = clap::ArgAction::Append)]
     pub labels: Vec<String>,
 
-    /// Sort by: updated, created, id
-    #[arg(long, default_value = "updated")]
-    pub sort: String,
+    /// Exclude issues with this label, can be repeated
+    #[arg(long = "exclude-label", value_name = "LABEL", action = clap::ArgAction::Append)]
+    pub exclude_labels: Vec<String>,
+
+    /// Sort by: updated, created, closed, activity, comments, id, title [default: updated]
+    #[arg(long)]
+    pub sort: Option<String>,
 
-    /// Sort direction: asc, desc
-    #[arg(long, default_value = "desc")]
-    pub order: String,
+    /// Sort direction: asc, desc [default: asc for id, desc otherwise]
+    #[arg(long)]
+    pub order: Option<String>,
 
-    /// Maximum issues to show
-    #[arg(short = 'L', long, default_value = "30")]
-    pub limit: usize,
+    /// Maximum issues to select [default: 30 for issue list without --ids, no limit otherwise]
+    #[arg(short = 'L', long)]
+    pub limit: Option<usize>,
 
     /// Skip first N issues (for pagination)
     #[arg(long, default_value = "0")]
@@ -119,9 +334,100 @@ pub struct IssueListArgs {
     #[arg(long)]
     pub deleted: bool,
 
-    /// Output as JSON
+    /// Show only soft-deleted issues
+    #[arg(long)]
+    pub only_deleted: bool,
+
+    /// Include archived issues
     #[arg(long)]
+    pub archived: bool,
+
+    /// Only issues closed within this duration, e.g. 14d, 2w (implies --state closed, --sort closed)
+    #[arg(long, value_name = "DURATION", conflicts_with = "state")]
+    pub closed_since: Option<String>,
+
+    /// Only issues with a label starting with this prefix, e.g. area/ (case-insensitive)
+    #[arg(long, value_name = "PREFIX")]
+    pub label_prefix: Option<String>,
+
+    /// Only issues linked to at least one other issue
+    #[arg(long, conflicts_with = "no_link")]
+    pub has_link: bool,
+
+    /// Only issues without any links
+    #[arg(long)]
+    pub no_link: bool,
+
+    /// Only issues with at least one comment
+    #[arg(long, conflicts_with = "uncommented")]
+    pub commented: bool,
+
+    /// Only issues nobody has commented on
+    #[arg(long)]
+    pub uncommented: bool,
+
+    /// Only issues estimated at N story points or more
+    #[arg(long, value_name = "N")]
+    pub min_points: Option<u32>,
+}
+
+#[derive(Args)]
+pub struct IssueListArgs {
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Output format: table, json, tsv
+    #[arg(long)]
+    pub format: Option<String>,
+
+    /// Output as JSON (same as --format json)
+    #[arg(long, conflicts_with = "format")]
     pub json: bool,
+
+    /// Columns to show, comma-separated: id, type, state, labels, updated, deleted, title
+    /// (also limits the keys in JSON output)
+    #[arg(long, value_name = "FIELDS")]
+    pub fields: Option<String>,
+
+    /// Print only issue numbers, one per line, for piping into --stdin (no default --limit)
+    #[arg(long, conflicts_with_all = ["format", "json", "fields"])]
+    pub ids: bool,
+
+    /// Remove configured title prefixes from the TITLE column
+    #[arg(long)]
+    pub strip_prefixes: bool,
+
+    /// Print only the number of matching issues (ignores --limit and --offset)
+    #[arg(long, conflicts_with_all = ["format", "json", "ids"])]
+    pub count: bool,
+
+    /// Print the SQL and bound parameters as JSON instead of running the query
+    #[arg(long, hide = true, conflicts_with_all = ["format", "json", "ids", "count"])]
+    pub explain: bool,
+
+    /// Timestamps in JSON output: rfc3339 (default) or epoch seconds
+    #[arg(long, value_name = "FORMAT")]
+    pub time_format: Option<String>,
+
+    /// In JSON output, also give `created_relative` and `updated_relative` ("3 days ago")
+    #[arg(long)]
+    pub with_relative: bool,
+
+    /// When to color the table: auto, always, never (auto honors NO_COLOR)
+    #[arg(long, value_name = "WHEN", default_value = "auto")]
+    pub color: String,
+
+    /// Most labels shown per table row; the rest become "+k more" (JSON and TSV show all)
+    #[arg(long, value_name = "N", default_value_t = ski::output::DEFAULT_MAX_LABELS)]
+    pub max_labels: usize,
+
+    /// Shuffle the matching issues instead of sorting them
+    #[arg(long, conflicts_with_all = ["sort", "order", "offset"])]
+    pub random: bool,
+
+    /// Print JSON on a single line instead of pretty-printed
+    #[arg(long)]
+    pub compact: bool,
 }
 
 #[derive(Args)]
@@ -136,6 +442,74 @@ pub struct IssueViewArgs {
     /// Output as JSON
     #[arg(long)]
     pub json: bool,
+
+    /// Open the issue in the SKIS GUI (falls back to the terminal view)
+    #[arg(long, conflicts_with = "json")]
+    pub open: bool,
+
+    /// Print JSON on a single line instead of pretty-printed
+    #[arg(long, requires = "json")]
+    pub compact: bool,
+
+    /// Print the issue as a Markdown document, with `#N` references linked
+    #[arg(long, conflicts_with_all = ["json", "open", "comments"])]
+    pub markdown: bool,
+}
+
+#[derive(Args)]
+pub struct IssueBodyArgs {
+    /// Issue number
+    pub number: i64,
+
+    /// Print `{"id": N, "body": ...}` as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct IssueGetArgs {
+    /// Issue number
+    pub number: i64,
+
+    /// Field to print: id, title, body, type, state, state_reason, points, labels,
+    /// created_at, updated_at, closed_at, deleted_at, or archived_at
+    #[arg(long, value_name = "NAME")]
+    pub field: String,
+}
+
+#[derive(Args)]
+pub struct IssueNextArgs {
+    /// Only consider this type: epic, task, bug, request
+    #[arg(short = 'T', long = "type")]
+    pub issue_type: Option<String>,
+
+    /// Only consider issues with this label, can be repeated (AND logic)
+    #[arg(short, long = "label", action = clap::ArgAction::Append)]
+    pub labels: Vec<String>,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct IssueCopyArgs {
+    /// Issue number
+    pub number: i64,
+
+    /// Format as a Markdown link to the issue's skis:// URL
+    #[arg(long)]
+    pub markdown: bool,
+
+    /// Also copy the reference to the system clipboard
+    #[arg(long)]
+    pub clipboard: bool,
+}
+
+#[derive(Args)]
+pub struct IssueBrowseArgs {
+    /// Issue number
+    pub number: i64,
 }
 
 #[derive(Args)]
@@ -147,7 +521,7 @@ pub struct IssueEditArgs {
     #[arg(short, long)]
     pub title: Option<String>,
 
-    /// Set new body
+    /// Set new body (use - for stdin)
     #[arg(short, long)]
     pub body: Option<String>,
 
@@ -163,19 +537,67 @@ pub struct IssueEditArgs {
     #[arg(short = 'T', long = "type")]
     pub issue_type: Option<String>,
 
-    /// Add label(s), can be repeated
+    /// Add label(s), can be repeated or comma-separated
     #[arg(long = "add-label", action = clap::ArgAction::Append)]
     pub add_labels: Vec<String>,
 
-    /// Remove label(s), can be repeated
+    /// Remove label(s), can be repeated or comma-separated
     #[arg(long = "remove-label", action = clap::ArgAction::Append)]
     pub remove_labels: Vec<String>,
+
+    /// Set the story points estimate
+    #[arg(long, value_name = "N", conflicts_with = "clear_points")]
+    pub points: Option<u32>,
+
+    /// Remove the story points estimate
+    #[arg(long)]
+    pub clear_points: bool,
+
+    /// Print the updated issue as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct IssueRetypeArgs {
+    /// Issue numbers
+    #[arg(required = true, num_args = 1..)]
+    pub numbers: Vec<i64>,
+
+    /// New type: epic, task, bug, request
+    #[arg(value_name = "TYPE")]
+    pub issue_type: String,
 }
 
 #[derive(Args)]
 pub struct IssueCloseArgs {
-    /// Issue number
-    pub number: i64,
+    /// Issue numbers
+    #[arg(
+        required_unless_present_any = ["stdin", "matching"],
+        num_args = 1..,
+        conflicts_with_all = FILTER_FLAGS
+    )]
+    pub numbers: Vec<i64>,
+
+    /// Read whitespace-separated issue numbers from stdin (requires --yes)
+    #[arg(
+        long,
+        conflicts_with = "numbers",
+        conflicts_with_all = FILTER_FLAGS,
+        requires = "yes"
+    )]
+    pub stdin: bool,
+
+    /// Close every open issue the filter flags select (requires --yes)
+    #[arg(long, conflicts_with_all = ["numbers", "stdin"], requires = "yes")]
+    pub matching: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Confirm closing issues read from stdin or selected with --matching
+    #[arg(long)]
+    pub yes: bool,
 
     /// Reason: completed, not_planned
     #[arg(short, long, default_value = "completed")]
@@ -184,36 +606,121 @@ pub struct IssueCloseArgs {
     /// Add a comment when closing
     #[arg(short = 'c', long)]
     pub comment: Option<String>,
+
+    /// Print the closed issue(s) as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args)]
 pub struct IssueReopenArgs {
-    /// Issue number
-    pub number: i64,
+    /// Issue numbers
+    #[arg(
+        required_unless_present_any = ["stdin", "matching"],
+        num_args = 1..,
+        conflicts_with_all = FILTER_FLAGS
+    )]
+    pub numbers: Vec<i64>,
+
+    /// Read whitespace-separated issue numbers from stdin
+    #[arg(long, conflicts_with = "numbers", conflicts_with_all = FILTER_FLAGS)]
+    pub stdin: bool,
+
+    /// Reopen every closed issue the filter flags select
+    #[arg(long, conflicts_with_all = ["numbers", "stdin"])]
+    pub matching: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Print the reopened issue(s) as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args)]
 pub struct IssueDeleteArgs {
-    /// Issue number
-    pub number: i64,
+    /// Issue numbers
+    #[arg(
+        required_unless_present_any = ["stdin", "matching"],
+        num_args = 1..,
+        conflicts_with_all = FILTER_FLAGS
+    )]
+    pub numbers: Vec<i64>,
+
+    /// Read whitespace-separated issue numbers from stdin (requires --yes)
+    #[arg(
+        long,
+        conflicts_with = "numbers",
+        conflicts_with_all = FILTER_FLAGS,
+        requires = "yes"
+    )]
+    pub stdin: bool,
+
+    /// Delete every open issue the filter flags select (requires --yes)
+    #[arg(long, conflicts_with_all = ["numbers", "stdin"], requires = "yes")]
+    pub matching: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
 
     /// Skip confirmation prompt
     #[arg(long)]
     pub yes: bool,
+
+    /// Why the issue is being deleted, shown by `issue view` and in JSON
+    #[arg(long)]
+    pub reason: Option<String>,
+
+    /// Print `{"ok": true, "id": N}` for each deleted issue as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args)]
 pub struct IssueRestoreArgs {
-    /// Issue number
-    pub number: i64,
+    /// Issue numbers
+    #[arg(
+        required_unless_present_any = ["stdin", "matching"],
+        num_args = 1..,
+        conflicts_with_all = FILTER_FLAGS
+    )]
+    pub numbers: Vec<i64>,
+
+    /// Read whitespace-separated issue numbers from stdin
+    #[arg(long, conflicts_with = "numbers", conflicts_with_all = FILTER_FLAGS)]
+    pub stdin: bool,
+
+    /// Restore every deleted issue the filter flags select
+    #[arg(long, conflicts_with_all = ["numbers", "stdin"])]
+    pub matching: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Print the restored issue(s) as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct IssueArchiveArgs {
+    /// Issue numbers
+    #[arg(required = true, num_args = 1..)]
+    pub numbers: Vec<i64>,
+
+    /// Print the issue(s) as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args)]
 pub struct IssueCommentArgs {
     /// Issue number
-    pub number: i64,
+    #[arg(required_unless_present_any = ["pin", "unpin"])]
+    pub number: Option<i64>,
 
-    /// Comment body
+    /// Comment body (use - for stdin)
     #[arg(short, long)]
     pub body: Option<String>,
 
@@ -224,6 +731,30 @@ pub struct IssueCommentArgs {
     /// Open $EDITOR to write body
     #[arg(short, long)]
     pub editor: bool,
+
+    /// Pin a comment by id so it is shown first
+    #[arg(
+        long,
+        value_name = "COMMENT_ID",
+        conflicts_with_all = ["number", "body", "body_file", "editor", "unpin"]
+    )]
+    pub pin: Option<i64>,
+
+    /// Unpin a comment by id
+    #[arg(
+        long,
+        value_name = "COMMENT_ID",
+        conflicts_with_all = ["number", "body", "body_file", "editor"]
+    )]
+    pub unpin: Option<i64>,
+
+    /// Comment even if the issue is soft-deleted
+    #[arg(long)]
+    pub force: bool,
+
+    /// Print the comment as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args)]
@@ -232,7 +763,28 @@ pub struct IssueLinkArgs {
     pub issue_a: i64,
 
     /// Second issue number
-    pub issue_b: i64,
+    #[arg(required_unless_present = "to")]
+    pub issue_b: Option<i64>,
+
+    /// Link the first issue to each of these issues
+    #[arg(long, num_args = 1.., conflicts_with = "issue_b")]
+    pub to: Vec<i64>,
+
+    /// How the first issue relates to the others: relates, blocks, blocked-by
+    #[arg(long = "type", default_value = "relates")]
+    pub link_type: String,
+
+    /// Why the issues are linked, shown alongside the link
+    #[arg(long)]
+    pub note: Option<String>,
+
+    /// Allow linking to soft-deleted issues
+    #[arg(long)]
+    pub force: bool,
+
+    /// Print the first issue, with its links, as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args)]
@@ -241,7 +793,16 @@ pub struct IssueUnlinkArgs {
     pub issue_a: i64,
 
     /// Second issue number
-    pub issue_b: i64,
+    #[arg(required_unless_present = "to")]
+    pub issue_b: Option<i64>,
+
+    /// Unlink the first issue from each of these issues
+    #[arg(long, num_args = 1.., conflicts_with = "issue_b")]
+    pub to: Vec<i64>,
+
+    /// Print the first issue, with its remaining links, as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Subcommand)]
@@ -249,17 +810,53 @@ enum LabelCommands {
     /// List all labels
     #[command(alias = "ls")]
     List(LabelListArgs),
+    /// Find labels whose name or description contains text
+    Find(LabelFindArgs),
     /// Create a new label
     Create(LabelCreateArgs),
     /// Delete a label
     Delete(LabelDeleteArgs),
+    /// Delete labels no issue uses
+    Prune(LabelPruneArgs),
+    /// Rename every label matching a regex, e.g. P1 -> prio/p1
+    RenameBulk(LabelRenameBulkArgs),
 }
 
 #[derive(Args)]
 pub struct LabelListArgs {
-    /// Output as JSON
+    /// Sort by: name, usage (most used first) [default: name]
+    #[arg(long)]
+    pub sort: Option<String>,
+
+    /// Output format: table, json, tsv
     #[arg(long)]
+    pub format: Option<String>,
+
+    /// Output as JSON (same as --format json)
+    #[arg(long, conflicts_with = "format")]
     pub json: bool,
+
+    /// Print JSON on a single line instead of pretty-printed
+    #[arg(long)]
+    pub compact: bool,
+}
+
+#[derive(Args)]
+pub struct LabelFindArgs {
+    /// Text to look for (case- and accent-insensitive)
+    pub text: String,
+
+    /// Output format: table, json, tsv
+    #[arg(long)]
+    pub format: Option<String>,
+
+    /// Output as JSON (same as --format json)
+    #[arg(long, conflicts_with = "format")]
+    pub json: bool,
+
+    /// Print JSON on a single line instead of pretty-printed
+    #[arg(long)]
+    pub compact: bool,
 }
 
 #[derive(Args)]
@@ -286,30 +883,105 @@ pub struct LabelDeleteArgs {
     pub yes: bool,
 }
 
+#[derive(Args)]
+pub struct LabelPruneArgs {
+    /// List the unused labels without deleting them
+    #[arg(long, conflicts_with = "yes")]
+    pub dry_run: bool,
+
+    /// Skip confirmation
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Count soft-deleted issues as using their labels
+    #[arg(long)]
+    pub include_deleted: bool,
+
+    /// Print `{"dry_run": bool, "pruned": [names]}` as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct LabelRenameBulkArgs {
+    /// Regex matched against each label name, e.g. '^P(\d)$'
+    #[arg(long, value_name = "REGEX")]
+    pub from_regex: String,
+
+    /// New name; $1 or ${name} insert capture groups, e.g. 'prio/p$1'
+    #[arg(long, value_name = "NAME")]
+    pub to: String,
+
+    /// List the renames without applying them
+    #[arg(long, conflicts_with = "yes")]
+    pub dry_run: bool,
+
+    /// Skip confirmation
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Print `{"dry_run": bool, "renamed": [{"from", "to"}]}` as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    // Every command opens the database through `SkisDb`, which reads SKIS_KEY
+    #[cfg(feature = "sqlcipher")]
+    if let Some(path) = &cli.key_file {
+        match std::fs::read_to_string(path) {
+            Ok(key) => std::env::set_var(ski::db::SKIS_KEY_ENV, key.trim_end_matches(['\r', '\n'])),
+            Err(e) => {
+                eprintln!("error: {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
     let result = match cli.command {
-        Commands::Init => commands::init::run(),
-        Commands::Issue(cmd) => match cmd {
+        Commands::Init(args) => commands::init::run(args),
+        Commands::Deinit(args) => commands::deinit::run(args),
+        Commands::Issue(cmd) => match *cmd {
             IssueCommands::Create(args) => commands::issue::create(args),
-            IssueCommands::List(args) => commands::issue::list(args),
+            IssueCommands::List(args) => commands::issue::list(*args),
             IssueCommands::View(args) => commands::issue::view(args),
+            IssueCommands::Get(args) => commands::issue::get(args),
+            IssueCommands::Body(args) => commands::issue::body(args),
+            IssueCommands::Next(args) => commands::issue::next(args),
+            IssueCommands::Browse(args) => commands::browse::run(args),
+            IssueCommands::Copy(args) => commands::issue::copy(args),
             IssueCommands::Edit(args) => commands::issue::edit(args),
+            IssueCommands::Retype(args) => commands::issue::retype(args),
             IssueCommands::Close(args) => commands::issue::close(args),
             IssueCommands::Reopen(args) => commands::issue::reopen(args),
             IssueCommands::Delete(args) => commands::issue::delete(args),
             IssueCommands::Restore(args) => commands::issue::restore(args),
+            IssueCommands::Archive(args) => commands::issue::archive(args),
+            IssueCommands::Unarchive(args) => commands::issue::unarchive(args),
             IssueCommands::Comment(args) => commands::issue::comment(args),
             IssueCommands::Link(args) => commands::issue::link(args),
             IssueCommands::Unlink(args) => commands::issue::unlink(args),
         },
         Commands::Label(cmd) => match cmd {
             LabelCommands::List(args) => commands::label::list(args),
+            LabelCommands::Find(args) => commands::label::find(args),
             LabelCommands::Create(args) => commands::label::create(args),
             LabelCommands::Delete(args) => commands::label::delete(args),
+            LabelCommands::Prune(args) => commands::label::prune(args),
+            LabelCommands::RenameBulk(args) => commands::label::rename_bulk(args),
         },
+        Commands::Changelog(args) => commands::changelog::run(args),
+        Commands::ReleaseNotes(args) => commands::release_notes::run(args),
+        Commands::Digest(args) => commands::digest::run(args),
+        Commands::Doctor(args) => commands::doctor::run(args),
+        Commands::Export(args) => commands::export::run(args),
+        Commands::Status(args) => commands::status::run(args),
+        Commands::Stats(StatsCommands::Types(args)) => commands::stats::types(args),
+        Commands::Reindex => commands::reindex::run(),
         Commands::LogPath => commands::log_path::run(),
+        Commands::Fixtures(FixturesCommands::Generate(args)) => commands::fixtures::generate(args),
     };
 
     match result {
@@ -1,6 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Error, Result};
+
+/// Default `body.max_comment_length`, in characters
+pub const DEFAULT_MAX_COMMENT_LENGTH: usize = 65_536;
+
 /// A comment on an issue
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
@@ -9,6 +14,27 @@ pub struct Comment {
     pub body: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Pinned comments are shown ahead of the rest of the thread
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Set when the body was changed after posting (`updated_at` is later than `created_at`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub edited: bool,
+}
+
+/// Trim a comment body and check it is non-empty and at most `max_len` characters
+pub fn validate_comment_body(body: &str, max_len: usize) -> Result<&str> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(Error::EmptyComment);
+    }
+
+    let len = body.chars().count();
+    if len > max_len {
+        return Err(Error::CommentTooLong(len, max_len));
+    }
+
+    Ok(body)
 }
 
 #[cfg(test)]
@@ -23,6 +49,8 @@ mod tests {
             body: "This is a comment".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            pinned: false,
+            edited: false,
         };
 
         let json = serde_json::to_string(&comment).unwrap();
@@ -30,4 +58,30 @@ mod tests {
         assert!(json.contains("\"issue_id\":42"));
         assert!(json.contains("\"body\":\"This is a comment\""));
     }
+
+    #[test]
+    fn validate_comment_body_trims() {
+        assert_eq!(validate_comment_body("  hello\n", 100).unwrap(), "hello");
+    }
+
+    #[test]
+    fn validate_comment_body_rejects_empty() {
+        assert!(matches!(
+            validate_comment_body("", 100),
+            Err(Error::EmptyComment)
+        ));
+        assert!(matches!(
+            validate_comment_body(" \n\t ", 100),
+            Err(Error::EmptyComment)
+        ));
+    }
+
+    #[test]
+    fn validate_comment_body_enforces_max_length() {
+        assert!(validate_comment_body("héllo", 5).is_ok());
+        assert!(matches!(
+            validate_comment_body("héllo!", 5),
+            Err(Error::CommentTooLong(6, 5))
+        ));
+    }
 }
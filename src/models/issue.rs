@@ -1,8 +1,11 @@
 use std::str::FromStr;
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::config::{BodyFormat, TitlePrefixes};
 use crate::error::{Error, Result};
 
 /// Issue type classification
@@ -60,6 +63,50 @@ impl std::fmt::Display for IssueState {
     }
 }
 
+/// Which states a listing shows: `open`, `closed` or `all`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateFilter {
+    #[default]
+    Open,
+    Closed,
+    All,
+}
+
+impl StateFilter {
+    /// The `IssueFilter::state` this selects, `None` for all
+    pub fn state(self) -> Option<IssueState> {
+        match self {
+            StateFilter::Open => Some(IssueState::Open),
+            StateFilter::Closed => Some(IssueState::Closed),
+            StateFilter::All => None,
+        }
+    }
+}
+
+impl FromStr for StateFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(StateFilter::Open),
+            "closed" => Ok(StateFilter::Closed),
+            "all" => Ok(StateFilter::All),
+            _ => Err(Error::InvalidStateFilter(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for StateFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateFilter::Open => write!(f, "open"),
+            StateFilter::Closed => write!(f, "closed"),
+            StateFilter::All => write!(f, "all"),
+        }
+    }
+}
+
 /// Reason for closing an issue
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -96,7 +143,25 @@ pub enum SortField {
     #[default]
     Updated,
     Created,
+    Closed,
+    /// Latest of the issue's update time and its newest comment
+    Activity,
+    /// Number of comments
+    Comments,
     Id,
+    /// Alphabetical, ignoring ASCII case
+    Title,
+}
+
+impl SortField {
+    /// Direction used when none is given: newest/most first, except ids
+    /// and titles which read naturally in creation and alphabetical order
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            SortField::Id | SortField::Title => SortOrder::Asc,
+            _ => SortOrder::Desc,
+        }
+    }
 }
 
 /// Sort order for issue listings
@@ -107,6 +172,15 @@ pub enum SortOrder {
     Desc,
 }
 
+/// Which columns a full-text search matches against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchScope {
+    #[default]
+    All,
+    Title,
+    Body,
+}
+
 /// An issue in the tracker
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
@@ -117,10 +191,85 @@ pub struct Issue {
     pub issue_type: IssueType,
     pub state: IssueState,
     pub state_reason: Option<StateReason>,
+    /// Story points for planning; unset unless someone estimated the issue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<u32>,
+    #[serde(serialize_with = "super::timestamp::serialize")]
     pub created_at: DateTime<Utc>,
+    #[serde(serialize_with = "super::timestamp::serialize")]
     pub updated_at: DateTime<Utc>,
+    #[serde(serialize_with = "super::timestamp::serialize_option")]
     pub closed_at: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "super::timestamp::serialize_option")]
     pub deleted_at: Option<DateTime<Utc>>,
+    /// Why the issue was deleted, if a reason was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_reason: Option<String>,
+    /// When the issue was filed away; archived issues are left out of
+    /// listings unless `IssueFilter::include_archived` is set
+    #[serde(default, serialize_with = "super::timestamp::serialize_option")]
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Bumped on every change; pass it back as `IssueUpdate::expected_version`
+    /// to detect concurrent edits
+    #[serde(default)]
+    pub version: i64,
+    /// Labels by name; only set by the label-enriched queries
+    /// (`list_issues_with_labels`, `get_issue_with_labels`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<super::LabelView>>,
+}
+
+/// Comment activity for an issue, computed alongside listings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueActivity {
+    pub comment_count: i64,
+    /// Latest of `updated_at` and the newest comment's `created_at`
+    pub last_activity_at: DateTime<Utc>,
+}
+
+/// Open issues of one type and their summed story points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PointsTotal {
+    #[serde(rename = "type")]
+    pub issue_type: IssueType,
+    pub issues: i64,
+    /// Issues with no estimate, which count as zero points
+    pub unestimated: i64,
+    pub points: i64,
+}
+
+/// Non-deleted issues of one type, by state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeCount {
+    #[serde(rename = "type")]
+    pub issue_type: IssueType,
+    pub open: i64,
+    pub closed: i64,
+}
+
+/// Issue activity over a period, as summarized by `skis digest`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Digest {
+    /// Start of the period
+    pub since: DateTime<Utc>,
+    /// Issues created in the period, oldest first
+    pub opened: Vec<DigestEntry>,
+    /// Issues closed in the period, by number
+    pub closed: Vec<DigestEntry>,
+    /// Issues with the most comments posted in the period, busiest first
+    pub most_commented: Vec<DigestEntry>,
+}
+
+/// One issue in a [`Digest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestEntry {
+    pub id: i64,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub issue_type: IssueType,
+    pub state_reason: Option<StateReason>,
+    /// Comments posted in the period
+    pub comments: i64,
 }
 
 /// Data for creating a new issue
@@ -130,6 +279,16 @@ pub struct IssueCreate {
     pub body: Option<String>,
     pub issue_type: IssueType,
     pub labels: Vec<String>,
+    pub points: Option<u32>,
+}
+
+/// Repository-specific behaviour applied by `create_issue_with`
+#[derive(Debug, Clone, Default)]
+pub struct CreateOpts {
+    /// Prefixes added to titles by issue type (see `Config::title_prefix`)
+    pub title_prefixes: TitlePrefixes,
+    /// Clean-up applied to the body (see `Config::body`)
+    pub body_format: BodyFormat,
 }
 
 /// Filter criteria for listing issues.
@@ -142,7 +301,32 @@ pub struct IssueFilter {
     pub state: Option<IssueState>,
     pub issue_type: Option<IssueType>,
     pub labels: Vec<String>,
+    /// Only issues with none of these labels
+    pub exclude_labels: Vec<String>,
+    /// Fail with `Error::LabelNotFound` when a `labels` or `exclude_labels`
+    /// entry doesn't exist, instead of matching nothing (or everything)
+    pub strict_labels: bool,
+    /// Only issues with a label whose name starts with this (case-insensitive)
+    pub label_prefix: Option<String>,
+    /// Only issues closed at or after this time
+    pub closed_after: Option<DateTime<Utc>>,
+    /// Only issues closed before this time
+    pub closed_before: Option<DateTime<Utc>>,
+    /// Only issues with (`Some(true)`) or without (`Some(false)`) any links
+    pub has_link: Option<bool>,
+    /// Only issues with (`Some(true)`) or without (`Some(false)`) any comments
+    pub has_comments: Option<bool>,
+    /// Only issues estimated at this many points or more
+    pub min_points: Option<u32>,
+    /// Columns matched by `search_issues`
+    pub search_scope: SearchScope,
+    /// Keyset pagination: only issues sorting strictly after this position
+    pub after: Option<Cursor>,
     pub include_deleted: bool,
+    /// Only soft-deleted issues; takes precedence over `include_deleted`
+    pub only_deleted: bool,
+    /// Also list archived issues, which are left out by default
+    pub include_archived: bool,
     pub sort_by: SortField,
     pub sort_order: SortOrder,
     pub limit: usize,
@@ -155,7 +339,19 @@ impl Default for IssueFilter {
             state: None,
             issue_type: None,
             labels: Vec::new(),
+            exclude_labels: Vec::new(),
+            strict_labels: false,
+            label_prefix: None,
+            closed_after: None,
+            closed_before: None,
+            has_link: None,
+            has_comments: None,
+            min_points: None,
+            search_scope: SearchScope::default(),
+            after: None,
             include_deleted: false,
+            only_deleted: false,
+            include_archived: false,
             sort_by: SortField::default(),
             sort_order: SortOrder::default(),
             limit: 30,
@@ -170,12 +366,70 @@ impl IssueFilter {
     }
 }
 
+/// Sort-key value of the last row on a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CursorKey {
+    Int(i64),
+    Text(String),
+}
+
+/// Position in a sorted listing: the last row's sort key and id.
+///
+/// Unlike an offset, a cursor stays valid when issues are added or
+/// changed between pages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// `None` when the sort column was NULL (e.g. `closed_at` of an open issue)
+    pub key: Option<CursorKey>,
+    pub id: i64,
+}
+
+impl Cursor {
+    /// Encode as an opaque URL-safe token
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("cursor serializes");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// Decode a token produced by `encode`
+    pub fn decode(token: &str) -> Result<Self> {
+        URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| Error::InvalidCursor(token.to_string()))
+    }
+}
+
+/// One page of a keyset-paginated listing
+#[derive(Debug, Clone)]
+pub struct IssuePage {
+    pub issues: Vec<(Issue, IssueActivity)>,
+    /// Cursor for the following page, `None` on the last page
+    pub next_cursor: Option<Cursor>,
+}
+
 /// Data for updating an existing issue
 #[derive(Debug, Clone, Default)]
 pub struct IssueUpdate {
     pub title: Option<String>,
     pub body: Option<String>,
     pub issue_type: Option<IssueType>,
+    /// `Some(None)` clears the estimate
+    pub points: Option<Option<u32>>,
+    /// Fail with `Error::Conflict` unless the issue is still at this version
+    pub expected_version: Option<i64>,
+}
+
+/// Times to write over an issue's own, e.g. for generated fixtures
+/// (see `db::backdate_issues`)
+#[derive(Debug, Clone, Default)]
+pub struct IssueTimestamps {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
 }
 
 /// A bidirectional link between two issues
@@ -186,11 +440,74 @@ pub struct IssueLink {
     pub created_at: DateTime<Utc>,
 }
 
+/// Kind of link between two issues, as seen from the issue on the near side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkType {
+    #[default]
+    Relates,
+    Blocks,
+    BlockedBy,
+}
+
+impl LinkType {
+    /// The same link seen from the other issue
+    pub fn inverse(self) -> Self {
+        match self {
+            LinkType::Relates => LinkType::Relates,
+            LinkType::Blocks => LinkType::BlockedBy,
+            LinkType::BlockedBy => LinkType::Blocks,
+        }
+    }
+}
+
+impl FromStr for LinkType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "relates" => Ok(LinkType::Relates),
+            "blocks" => Ok(LinkType::Blocks),
+            "blocked_by" | "blocked-by" => Ok(LinkType::BlockedBy),
+            _ => Err(Error::InvalidLinkType(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for LinkType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkType::Relates => write!(f, "relates"),
+            LinkType::Blocks => write!(f, "blocks"),
+            LinkType::BlockedBy => write!(f, "blocked_by"),
+        }
+    }
+}
+
+/// Per-target outcome of a batch link or unlink
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    Linked,
+    AlreadyLinked,
+    Unlinked,
+    NotLinked,
+}
+
 /// A linked issue reference for JSON output (id + title)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LinkedIssueRef {
     pub id: i64,
     pub title: String,
+    /// How this issue relates to the linked one
+    #[serde(default)]
+    pub link_type: LinkType,
+    /// Set when the linked issue has been soft-deleted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+    /// Why the issues are linked, if given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 /// Enriched issue view for JSON output (includes labels and linked issues)
@@ -203,12 +520,42 @@ pub struct IssueView {
     pub issue_type: IssueType,
     pub state: IssueState,
     pub state_reason: Option<StateReason>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<u32>,
     pub labels: Vec<super::LabelView>,
     pub linked_issues: Vec<LinkedIssueRef>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
     pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_reason: Option<String>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub version: i64,
+}
+
+impl IssueView {
+    /// Build the view from a label-enriched issue (labels default to empty)
+    pub fn new(issue: Issue, linked_issues: Vec<LinkedIssueRef>) -> Self {
+        Self {
+            id: issue.id,
+            title: issue.title,
+            body: issue.body,
+            issue_type: issue.issue_type,
+            state: issue.state,
+            state_reason: issue.state_reason,
+            points: issue.points,
+            labels: issue.labels.unwrap_or_default(),
+            linked_issues,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+            closed_at: issue.closed_at,
+            deleted_at: issue.deleted_at,
+            deleted_reason: issue.deleted_reason,
+            archived_at: issue.archived_at,
+            version: issue.version,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -248,6 +595,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn state_filter_from_str() {
+        assert_eq!(
+            StateFilter::from_str("Closed").unwrap(),
+            StateFilter::Closed
+        );
+        assert_eq!(StateFilter::from_str("all").unwrap().state(), None);
+        assert_eq!(
+            StateFilter::from_str("open").unwrap().state(),
+            Some(IssueState::Open)
+        );
+        assert!(matches!(
+            StateFilter::from_str("shut"),
+            Err(Error::InvalidStateFilter(_))
+        ));
+    }
+
+    #[test]
+    fn link_type_from_str_and_inverse() {
+        assert_eq!(LinkType::from_str("blocks").unwrap(), LinkType::Blocks);
+        assert_eq!(
+            LinkType::from_str("blocked-by").unwrap(),
+            LinkType::BlockedBy
+        );
+        assert!(LinkType::from_str("duplicates").is_err());
+
+        assert_eq!(LinkType::Blocks.inverse(), LinkType::BlockedBy);
+        assert_eq!(LinkType::BlockedBy.inverse(), LinkType::Blocks);
+        assert_eq!(LinkType::Relates.inverse(), LinkType::Relates);
+    }
+
+    #[test]
+    fn sort_field_default_orders() {
+        assert_eq!(SortField::Updated.default_order(), SortOrder::Desc);
+        assert_eq!(SortField::Created.default_order(), SortOrder::Desc);
+        assert_eq!(SortField::Closed.default_order(), SortOrder::Desc);
+        assert_eq!(SortField::Activity.default_order(), SortOrder::Desc);
+        assert_eq!(SortField::Comments.default_order(), SortOrder::Desc);
+        assert_eq!(SortField::Id.default_order(), SortOrder::Asc);
+        assert_eq!(SortField::Title.default_order(), SortOrder::Asc);
+    }
+
+    #[test]
+    fn cursor_round_trips_through_token() {
+        let cursor = Cursor {
+            key: Some(CursorKey::Text("2024-01-31 12:00:00".to_string())),
+            id: 7,
+        };
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+
+        let null_key = Cursor { key: None, id: 3 };
+        assert_eq!(Cursor::decode(&null_key.encode()).unwrap(), null_key);
+
+        assert!(matches!(
+            Cursor::decode("not a cursor"),
+            Err(Error::InvalidCursor(_))
+        ));
+    }
+
     #[test]
     fn issue_filter_default_values() {
         let filter = IssueFilter::new();
@@ -278,10 +684,15 @@ mod tests {
             issue_type: IssueType::Bug,
             state: IssueState::Open,
             state_reason: None,
+            points: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             closed_at: None,
             deleted_at: None,
+            deleted_reason: None,
+            archived_at: None,
+            version: 0,
+            labels: None,
         };
 
         let json = serde_json::to_string(&issue).unwrap();
@@ -289,6 +700,37 @@ mod tests {
         assert!(json.contains("\"title\":\"Test issue\""));
         assert!(json.contains("\"type\":\"bug\""));
         assert!(json.contains("\"state\":\"open\""));
+        // Labels are omitted unless the issue came from an enriched query
+        assert!(!json.contains("\"labels\""));
+    }
+
+    #[test]
+    fn issue_with_labels_serializes_them() {
+        let issue = Issue {
+            id: 1,
+            title: "Labeled".to_string(),
+            body: None,
+            issue_type: IssueType::Task,
+            state: IssueState::Open,
+            state_reason: None,
+            points: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            closed_at: None,
+            deleted_at: None,
+            deleted_reason: None,
+            archived_at: None,
+            version: 0,
+            labels: Some(vec![crate::models::LabelView {
+                name: "bug".to_string(),
+                color: Some("d73a4a".to_string()),
+                description: None,
+            }]),
+        };
+
+        let json = serde_json::to_value(&issue).unwrap();
+        assert_eq!(json["labels"][0]["name"], "bug");
+        assert_eq!(json["labels"][0]["color"], "d73a4a");
     }
 
     #[test]
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
+use crate::util::fold_accents;
 
 /// A label that can be applied to issues
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +16,37 @@ pub struct Label {
     pub color: Option<String>,
 }
 
+/// A label with the number of (non-deleted) issues using it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelUsage {
+    #[serde(flatten)]
+    pub label: Label,
+    pub open_count: i64,
+    pub total_count: i64,
+}
+
+/// Order of `skis label list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelSort {
+    /// Alphabetical
+    #[default]
+    Name,
+    /// Most used first, by number of non-deleted issues
+    Usage,
+}
+
+impl FromStr for LabelSort {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(LabelSort::Name),
+            "usage" => Ok(LabelSort::Usage),
+            _ => Err(Error::InvalidLabelSort(s.to_string())),
+        }
+    }
+}
+
 /// Label view for JSON output (without internal id, per PLAN.md schema)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LabelView {
@@ -39,6 +75,68 @@ impl From<&Label> for LabelView {
     }
 }
 
+/// One label's old and new name in `skis label rename-bulk`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LabelRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Plan a bulk rename: every label matching `pattern` gets its first match
+/// replaced by `replacement`, where `$1` or `${name}` insert capture groups.
+/// Labels that don't match, or whose name would not change, are left out.
+///
+/// Fails with `Error::LabelRenameConflict`, listing every clash, when two
+/// labels would get the same name (ignoring case and accents), a new name is
+/// taken by another label, or a new name is empty.
+pub fn plan_label_renames(
+    labels: &[Label],
+    pattern: &Regex,
+    replacement: &str,
+) -> Result<Vec<LabelRename>> {
+    let renames: Vec<LabelRename> = labels
+        .iter()
+        .filter(|l| pattern.is_match(&l.name))
+        .map(|l| LabelRename {
+            from: l.name.clone(),
+            to: pattern.replace(&l.name, replacement).into_owned(),
+        })
+        .filter(|r| r.to != r.from)
+        .collect();
+
+    let mut by_target: BTreeMap<String, Vec<&LabelRename>> = BTreeMap::new();
+    for rename in &renames {
+        by_target
+            .entry(fold_accents(&rename.to))
+            .or_default()
+            .push(rename);
+    }
+
+    let mut clashes = Vec::new();
+    for (key, group) in &by_target {
+        let to = &group[0].to;
+        let from: Vec<&str> = group.iter().map(|r| r.from.as_str()).collect();
+        let from = from.join(", ");
+        let taken = labels
+            .iter()
+            .find(|l| fold_accents(&l.name) == *key && !group.iter().any(|r| r.from == l.name));
+        if to.trim().is_empty() {
+            clashes.push(format!("  {} -> '{}': the new name is empty", from, to));
+        } else if group.len() > 1 {
+            clashes.push(format!("  {} -> '{}': same new name", from, to));
+        } else if let Some(label) = taken {
+            clashes.push(format!(
+                "  {} -> '{}': label '{}' already exists",
+                from, to, label.name
+            ));
+        }
+    }
+    if !clashes.is_empty() {
+        return Err(Error::LabelRenameConflict(clashes.join("\n")));
+    }
+    Ok(renames)
+}
+
 /// Validate a hex color string (6 characters, no # prefix)
 pub fn validate_color(color: &str) -> Result<()> {
     if color.len() != 6 {
@@ -52,29 +150,130 @@ pub fn validate_color(color: &str) -> Result<()> {
     Ok(())
 }
 
+/// Minimum hue distance (degrees) `generate_color_avoiding` tries to keep
+/// from existing label colors
+pub const MIN_HUE_SEPARATION: f32 = 20.0;
+
+/// Step used when probing for a free hue; coprime with 360 so every whole
+/// degree is visited once
+const HUE_PROBE_STEP: u32 = 137;
+
 /// Generate a color from a label name using a simple hash.
 /// Produces pleasant, saturated colors in HSL space then converts to hex.
 pub fn generate_color(name: &str) -> String {
-    // Simple hash: sum of bytes with position weighting
-    let hash: u32 = name
-        .to_lowercase()
-        .bytes()
-        .enumerate()
-        .fold(0u32, |acc, (i, b)| {
-            acc.wrapping_add((b as u32).wrapping_mul((i as u32).wrapping_add(1)))
-        });
-
-    // Use hash to pick hue (0-360), keep saturation and lightness fixed for pleasant colors
-    let hue = (hash % 360) as f32;
-    let saturation = 0.65;
-    let lightness = 0.45;
-
-    // Convert HSL to RGB
-    let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
+    generate_color_avoiding(name, &[])
+}
+
+/// Like `generate_color`, but nudges the hue away from `used_hues` so a new
+/// label doesn't end up indistinguishable from an existing one. The result is
+/// deterministic for a given name and set of used hues.
+pub fn generate_color_avoiding(name: &str, used_hues: &[f32]) -> String {
+    let start = name_hash(name) % 360;
+    let distance_to_used = |hue: f32| {
+        used_hues
+            .iter()
+            .map(|&used| hue_distance(hue, used))
+            .fold(f32::INFINITY, f32::min)
+    };
+
+    // Probe from the hashed hue until one is far enough from every used hue,
+    // falling back to whichever probe was furthest away
+    let mut best = (start as f32, distance_to_used(start as f32));
+    for k in 0..360 {
+        let hue = ((start + k * HUE_PROBE_STEP) % 360) as f32;
+        let distance = distance_to_used(hue);
+        if distance >= MIN_HUE_SEPARATION {
+            best = (hue, distance);
+            break;
+        }
+        if distance > best.1 {
+            best = (hue, distance);
+        }
+    }
+
+    // Saturation and lightness stay fixed so colors are neither near-black
+    // nor near-white
+    let (r, g, b) = hsl_to_rgb(best.0, 0.65, 0.45);
+
+    format!("{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Hue (0-360) of a hex color, or None if the color is invalid or grey
+pub fn color_hue(color: &str) -> Option<f32> {
+    validate_color(color).ok()?;
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).unwrap() as f32 / 255.0;
+    let (r, g, b) = (channel(0), channel(2), channel(4));
+
+    let max = r.max(g).max(b);
+    let chroma = max - r.min(g).min(b);
+    if chroma < 0.05 {
+        return None;
+    }
+
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    Some(hue.rem_euclid(360.0))
+}
+
+/// Background a label chip is drawn on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
 
+impl FromStr for Theme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "light" => Ok(Theme::Light),
+            "dark" => Ok(Theme::Dark),
+            _ => Err(Error::InvalidTheme(s.to_string())),
+        }
+    }
+}
+
+/// On dark backgrounds colors are lifted to at least this lightness...
+const DARK_MIN_LIGHTNESS: f32 = 0.70;
+/// ...and desaturated to at most this, giving pastel chips
+const DARK_MAX_SATURATION: f32 = 0.75;
+/// On light backgrounds lightness is scaled by this, so pale colors keep
+/// enough contrast with white
+const LIGHT_LIGHTNESS_SCALE: f32 = 0.85;
+
+/// Color to draw a label with on `theme`. Only the displayed color changes;
+/// the stored one is untouched. Invalid colors are returned as given.
+pub fn adjust_for_theme(color: &str, theme: Theme) -> String {
+    let Some((h, s, l)) = color_hsl(color) else {
+        return color.to_string();
+    };
+    let (s, l) = match theme {
+        Theme::Dark => (s.min(DARK_MAX_SATURATION), l.max(DARK_MIN_LIGHTNESS)),
+        Theme::Light => (s, l * LIGHT_LIGHTNESS_SCALE),
+    };
+    let (r, g, b) = hsl_to_rgb(h, s, l);
     format!("{:02x}{:02x}{:02x}", r, g, b)
 }
 
+/// FNV-1a over the lowercased name
+fn name_hash(name: &str) -> u32 {
+    name.to_lowercase().bytes().fold(0x811c_9dc5u32, |acc, b| {
+        (acc ^ b as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Distance between two hues around the color wheel
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
 /// Convert HSL to RGB (h: 0-360, s: 0-1, l: 0-1) -> (r, g, b) as u8
 fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
     let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
@@ -97,10 +296,114 @@ fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
     )
 }
 
+/// Hex color as HSL (h: 0-360, s: 0-1, l: 0-1), or None if invalid
+fn color_hsl(color: &str) -> Option<(f32, f32, f32)> {
+    validate_color(color).ok()?;
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).unwrap() as f32 / 255.0;
+    let (r, g, b) = (channel(0), channel(2), channel(4));
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let l = (max + min) / 2.0;
+    if chroma == 0.0 {
+        return Some((0.0, 0.0, l));
+    }
+
+    let s = chroma / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / chroma)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    Some((h.rem_euclid(360.0), s, l))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn named(names: &[&str]) -> Vec<Label> {
+        names
+            .iter()
+            .zip(1..)
+            .map(|(name, id)| Label {
+                id,
+                name: name.to_string(),
+                description: None,
+                color: None,
+            })
+            .collect()
+    }
+
+    fn rename(from: &str, to: &str) -> LabelRename {
+        LabelRename {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn plan_label_renames_substitutes_capture_groups() {
+        let labels = named(&["P1", "P2", "bug", "P10x"]);
+        let pattern = Regex::new(r"^P(\d)$").unwrap();
+        let plan = plan_label_renames(&labels, &pattern, "prio/p$1").unwrap();
+        assert_eq!(plan, [rename("P1", "prio/p1"), rename("P2", "prio/p2")]);
+
+        let pattern = Regex::new(r"^(?<area>\w+)-(?<kind>\w+)$").unwrap();
+        let labels = named(&["ui-bug", "db"]);
+        let plan = plan_label_renames(&labels, &pattern, "${kind}/${area}").unwrap();
+        assert_eq!(plan, [rename("ui-bug", "bug/ui")]);
+    }
+
+    #[test]
+    fn plan_label_renames_without_matches_is_empty() {
+        let labels = named(&["bug", "ui"]);
+        let pattern = Regex::new(r"^P(\d)$").unwrap();
+        assert!(plan_label_renames(&labels, &pattern, "prio/p$1")
+            .unwrap()
+            .is_empty());
+        // A match that leaves the name as it was is not a rename
+        let pattern = Regex::new("bug").unwrap();
+        assert!(plan_label_renames(&labels, &pattern, "bug")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn plan_label_renames_reports_every_collision() {
+        let labels = named(&["P1", "p-1", "P2", "prio/p2", "P3"]);
+        let pattern = Regex::new(r"^[Pp]-?(\d)$").unwrap();
+        let err = plan_label_renames(&labels, &pattern, "prio/p$1").unwrap_err();
+        let Error::LabelRenameConflict(report) = err else {
+            panic!("expected a conflict, got {:?}", err);
+        };
+        assert_eq!(
+            report,
+            "  P1, p-1 -> 'prio/p1': same new name\n  \
+             P2 -> 'prio/p2': label 'prio/p2' already exists"
+        );
+    }
+
+    #[test]
+    fn plan_label_renames_collides_ignoring_case_and_accents() {
+        let labels = named(&["Cafe", "old-café"]);
+        let pattern = Regex::new("^old-(.*)$").unwrap();
+        let err = plan_label_renames(&labels, &pattern, "$1").unwrap_err();
+        assert!(err.to_string().contains("label 'Cafe' already exists"));
+
+        // Changing only the case of a label is fine
+        let pattern = Regex::new("^Cafe$").unwrap();
+        let plan = plan_label_renames(&labels, &pattern, "cafe").unwrap();
+        assert_eq!(plan, [rename("Cafe", "cafe")]);
+
+        let pattern = Regex::new("^Cafe$").unwrap();
+        let err = plan_label_renames(&labels, &pattern, "").unwrap_err();
+        assert!(err.to_string().contains("the new name is empty"));
+    }
+
     #[test]
     fn valid_hex_colors() {
         assert!(validate_color("ff0000").is_ok());
@@ -179,4 +482,104 @@ mod tests {
         assert_ne!(bug, urgent);
         assert_ne!(feature, urgent);
     }
+
+    #[test]
+    fn color_hue_reads_back_generated_hue() {
+        assert!((color_hue("ff0000").unwrap() - 0.0).abs() < 1.0);
+        assert!((color_hue("00ff00").unwrap() - 120.0).abs() < 1.0);
+        assert!((color_hue("0000ff").unwrap() - 240.0).abs() < 1.0);
+        assert_eq!(color_hue("808080"), None);
+        assert_eq!(color_hue("nothex"), None);
+    }
+
+    #[test]
+    fn generate_color_avoiding_is_deterministic() {
+        let used = [10.0, 200.0];
+        assert_eq!(
+            generate_color_avoiding("bug", &used),
+            generate_color_avoiding("bug", &used)
+        );
+        assert_eq!(generate_color_avoiding("bug", &[]), generate_color("bug"));
+    }
+
+    #[test]
+    fn generate_color_avoiding_moves_away_from_used_hue() {
+        let taken = color_hue(&generate_color("bug")).unwrap();
+        let nudged = color_hue(&generate_color_avoiding("bug", &[taken])).unwrap();
+        assert!(hue_distance(taken, nudged) >= MIN_HUE_SEPARATION - 1.0);
+    }
+
+    #[test]
+    fn common_label_names_get_distinct_hues() {
+        let names = [
+            "bug",
+            "feature",
+            "enhancement",
+            "documentation",
+            "question",
+            "help wanted",
+            "good first issue",
+            "duplicate",
+            "invalid",
+            "wontfix",
+            "urgent",
+            "low-priority",
+        ];
+
+        let mut hues = Vec::new();
+        for name in names {
+            let hue = color_hue(&generate_color_avoiding(name, &hues)).unwrap();
+            hues.push(hue);
+        }
+
+        for (i, a) in hues.iter().enumerate() {
+            for b in &hues[i + 1..] {
+                // Allow for rounding through 8-bit RGB
+                assert!(
+                    hue_distance(*a, *b) >= MIN_HUE_SEPARATION - 1.0,
+                    "hues {a} and {b} are too close"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn adjust_for_theme_lightens_on_dark() {
+        assert_eq!(adjust_for_theme("ff0000", Theme::Dark), "eb7979");
+        assert_eq!(adjust_for_theme("000000", Theme::Dark), "b2b2b2");
+        // Already pale colors are left alone
+        assert_eq!(adjust_for_theme("ffffff", Theme::Dark), "ffffff");
+    }
+
+    #[test]
+    fn adjust_for_theme_darkens_on_light() {
+        assert_eq!(adjust_for_theme("ff0000", Theme::Light), "d80000");
+        assert_eq!(adjust_for_theme("a2eeef", Theme::Light), "6ee4e6");
+        assert_eq!(adjust_for_theme("000000", Theme::Light), "000000");
+    }
+
+    #[test]
+    fn adjust_for_theme_keeps_invalid_colors() {
+        assert_eq!(adjust_for_theme("nothex", Theme::Dark), "nothex");
+        assert_eq!(adjust_for_theme("", Theme::Light), "");
+    }
+
+    #[test]
+    fn parses_label_sort() {
+        assert_eq!("Usage".parse::<LabelSort>().unwrap(), LabelSort::Usage);
+        assert_eq!(LabelSort::default(), LabelSort::Name);
+        assert!(matches!(
+            "color".parse::<LabelSort>(),
+            Err(Error::InvalidLabelSort(s)) if s == "color"
+        ));
+    }
+
+    #[test]
+    fn parses_theme() {
+        assert_eq!("Dark".parse::<Theme>().unwrap(), Theme::Dark);
+        assert!(matches!(
+            "sepia".parse::<Theme>(),
+            Err(Error::InvalidTheme(t)) if t == "sepia"
+        ));
+    }
 }
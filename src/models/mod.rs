@@ -1,10 +1,18 @@
 mod comment;
 mod issue;
 pub mod label;
+pub mod timestamp;
 
-pub use comment::Comment;
+pub use comment::{validate_comment_body, Comment, DEFAULT_MAX_COMMENT_LENGTH};
 pub use issue::{
-    Issue, IssueCreate, IssueFilter, IssueLink, IssueState, IssueType, IssueUpdate, IssueView,
-    LinkedIssueRef, SortField, SortOrder, StateReason,
+    CreateOpts, Cursor, CursorKey, Digest, DigestEntry, Issue, IssueActivity, IssueCreate,
+    IssueFilter, IssueLink, IssuePage, IssueState, IssueTimestamps, IssueType, IssueUpdate,
+    IssueView, LinkStatus, LinkType, LinkedIssueRef, PointsTotal, SearchScope, SortField,
+    SortOrder, StateFilter, StateReason, TypeCount,
 };
-pub use label::{generate_color, validate_color, Label, LabelView};
+pub use label::{
+    adjust_for_theme, color_hue, generate_color, generate_color_avoiding, plan_label_renames,
+    validate_color, Label, LabelRename, LabelSort, LabelUsage, LabelView, Theme,
+    MIN_HUE_SEPARATION,
+};
+pub use timestamp::{with_time_format, TimeFormat};
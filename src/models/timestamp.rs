@@ -0,0 +1,126 @@
+//! Serialization of timestamps in a format chosen at runtime
+//!
+//! Timestamps serialize as RFC 3339 strings unless a caller wraps serialization
+//! in [`with_time_format`] to ask for unix epoch seconds instead.
+
+use std::cell::Cell;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+
+use crate::error::{Error, Result};
+
+/// How timestamps are written in JSON output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeFormat {
+    /// `2024-01-15T10:30:00Z`
+    #[default]
+    Rfc3339,
+    /// Whole seconds since 1970-01-01 UTC, as an integer
+    Epoch,
+}
+
+impl FromStr for TimeFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "rfc3339" => Ok(TimeFormat::Rfc3339),
+            "epoch" => Ok(TimeFormat::Epoch),
+            _ => Err(Error::InvalidTimeFormat(s.to_string())),
+        }
+    }
+}
+
+thread_local! {
+    static TIME_FORMAT: Cell<TimeFormat> = const { Cell::new(TimeFormat::Rfc3339) };
+}
+
+/// Run `f` with timestamps serializing as `format` on this thread
+pub fn with_time_format<R>(format: TimeFormat, f: impl FnOnce() -> R) -> R {
+    let previous = TIME_FORMAT.with(|cell| cell.replace(format));
+    let result = f();
+    TIME_FORMAT.with(|cell| cell.set(previous));
+    result
+}
+
+/// `serialize_with` for a `DateTime<Utc>` field
+pub fn serialize<S: Serializer>(
+    time: &DateTime<Utc>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match TIME_FORMAT.with(Cell::get) {
+        TimeFormat::Rfc3339 => time.serialize(serializer),
+        TimeFormat::Epoch => serializer.serialize_i64(time.timestamp()),
+    }
+}
+
+/// `serialize_with` for an `Option<DateTime<Utc>>` field
+pub fn serialize_option<S: Serializer>(
+    time: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => serialize(time, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize)]
+    struct Stamped {
+        #[serde(serialize_with = "serialize")]
+        at: DateTime<Utc>,
+        #[serde(serialize_with = "serialize_option")]
+        closed: Option<DateTime<Utc>>,
+    }
+
+    fn stamped() -> Stamped {
+        Stamped {
+            at: Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap(),
+            closed: None,
+        }
+    }
+
+    #[test]
+    fn rfc3339_is_the_default_and_round_trips() {
+        let json = serde_json::to_value(stamped()).unwrap();
+        assert_eq!(json["at"], "2024-01-15T10:30:00Z");
+        assert!(json["closed"].is_null());
+        let back: Stamped = serde_json::from_value(json).unwrap();
+        assert_eq!(back.at, stamped().at);
+    }
+
+    #[test]
+    fn epoch_writes_integer_seconds() {
+        let json = with_time_format(TimeFormat::Epoch, || {
+            serde_json::to_value(stamped()).unwrap()
+        });
+        assert_eq!(json["at"], 1_705_314_600);
+        assert!(json["closed"].is_null());
+        let at = DateTime::from_timestamp(json["at"].as_i64().unwrap(), 0).unwrap();
+        assert_eq!(at, stamped().at);
+
+        // The format is restored afterwards
+        assert!(serde_json::to_value(stamped()).unwrap()["at"].is_string());
+    }
+
+    #[test]
+    fn parses_time_format() {
+        assert_eq!("EPOCH".parse::<TimeFormat>().unwrap(), TimeFormat::Epoch);
+        assert_eq!(
+            "rfc3339".parse::<TimeFormat>().unwrap(),
+            TimeFormat::Rfc3339
+        );
+        assert!(matches!(
+            "unix".parse::<TimeFormat>(),
+            Err(Error::InvalidTimeFormat(f)) if f == "unix"
+        ));
+    }
+}
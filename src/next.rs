@@ -0,0 +1,169 @@
+//! Picking an issue to work on next
+//!
+//! `skis issue next` ranks open, unblocked issues by a per-type priority, then
+//! by the number of days since the issue was last updated, so a fresh bug
+//! still comes before a long-neglected task. The ranking is a pure function of
+//! its inputs; tune it through [`NextWeights`].
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{Issue, IssueState, IssueType};
+
+/// Priority of each issue type; a higher priority always ranks first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NextWeights {
+    pub bug: i64,
+    pub request: i64,
+    pub task: i64,
+    pub epic: i64,
+}
+
+impl Default for NextWeights {
+    fn default() -> Self {
+        Self {
+            bug: 3,
+            request: 2,
+            task: 1,
+            epic: 0,
+        }
+    }
+}
+
+impl NextWeights {
+    fn priority(&self, issue_type: IssueType) -> i64 {
+        match issue_type {
+            IssueType::Bug => self.bug,
+            IssueType::Request => self.request,
+            IssueType::Task => self.task,
+            IssueType::Epic => self.epic,
+        }
+    }
+
+    /// Sort key of `issue` at `now`: priority descending, then days since
+    /// the last update descending, then the lower issue number
+    pub fn rank_key(&self, issue: &Issue, now: DateTime<Utc>) -> (Reverse<i64>, Reverse<i64>, i64) {
+        let idle_days = (now - issue.updated_at).num_days().max(0);
+        (
+            Reverse(self.priority(issue.issue_type)),
+            Reverse(idle_days),
+            issue.id,
+        )
+    }
+}
+
+/// Open, non-deleted issues not in `blocked`, best candidate first (see
+/// [`NextWeights::rank_key`])
+pub fn rank_next<'a>(
+    issues: &'a [Issue],
+    blocked: &HashSet<i64>,
+    now: DateTime<Utc>,
+    weights: &NextWeights,
+) -> Vec<&'a Issue> {
+    let mut candidates: Vec<&Issue> = issues
+        .iter()
+        .filter(|i| i.state == IssueState::Open && i.deleted_at.is_none())
+        .filter(|i| !blocked.contains(&i.id))
+        .collect();
+    candidates.sort_by_key(|i| weights.rank_key(i, now));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn issue(id: i64, issue_type: IssueType, idle_days: i64, now: DateTime<Utc>) -> Issue {
+        Issue {
+            id,
+            title: format!("Issue {}", id),
+            body: None,
+            issue_type,
+            state: IssueState::Open,
+            state_reason: None,
+            points: None,
+            created_at: now - Duration::days(idle_days),
+            updated_at: now - Duration::days(idle_days),
+            closed_at: None,
+            deleted_at: None,
+            deleted_reason: None,
+            archived_at: None,
+            version: 0,
+            labels: None,
+        }
+    }
+
+    fn ids(ranked: &[&Issue]) -> Vec<i64> {
+        ranked.iter().map(|i| i.id).collect()
+    }
+
+    #[test]
+    fn ranks_by_type_priority_then_staleness() {
+        let now = Utc::now();
+        let issues = [
+            issue(1, IssueType::Task, 0, now),
+            issue(2, IssueType::Bug, 0, now),
+            issue(3, IssueType::Bug, 5, now),
+            issue(4, IssueType::Epic, 3, now),
+        ];
+        let ranked = rank_next(&issues, &HashSet::new(), now, &NextWeights::default());
+        assert_eq!(ids(&ranked), [3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn staleness_never_overtakes_a_higher_priority_type() {
+        let now = Utc::now();
+        let issues = [
+            issue(1, IssueType::Bug, 0, now),
+            issue(2, IssueType::Task, 40, now),
+        ];
+        let weights = NextWeights::default();
+        assert_eq!(
+            ids(&rank_next(&issues, &HashSet::new(), now, &weights)),
+            [1, 2]
+        );
+
+        // Weights are adjustable
+        let tasks_first = NextWeights {
+            task: 10,
+            ..weights
+        };
+        assert_eq!(
+            ids(&rank_next(&issues, &HashSet::new(), now, &tasks_first)),
+            [2, 1]
+        );
+    }
+
+    #[test]
+    fn skips_blocked_closed_and_deleted() {
+        let now = Utc::now();
+        let mut closed = issue(2, IssueType::Bug, 9, now);
+        closed.state = IssueState::Closed;
+        let mut deleted = issue(3, IssueType::Bug, 9, now);
+        deleted.deleted_at = Some(now);
+        let issues = [
+            issue(1, IssueType::Bug, 9, now),
+            closed,
+            deleted,
+            issue(4, IssueType::Task, 0, now),
+        ];
+        let blocked = HashSet::from([1]);
+        let ranked = rank_next(&issues, &blocked, now, &NextWeights::default());
+        assert_eq!(ids(&ranked), [4]);
+        assert!(rank_next(&[], &blocked, now, &NextWeights::default()).is_empty());
+    }
+
+    #[test]
+    fn ties_keep_lower_number_first() {
+        let now = Utc::now();
+        let issues = [
+            issue(5, IssueType::Task, 1, now),
+            issue(2, IssueType::Task, 1, now),
+        ];
+        let ranked = rank_next(&issues, &HashSet::new(), now, &NextWeights::default());
+        assert_eq!(ids(&ranked), [2, 5]);
+    }
+}
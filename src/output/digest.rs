@@ -0,0 +1,286 @@
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::models::{Digest, DigestEntry, StateReason};
+
+/// Document type produced by `skis digest`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DigestFormat {
+    /// Plain text, for pasting into an email body
+    #[default]
+    Text,
+    /// An HTML fragment with inline styles, which mail clients keep
+    Html,
+}
+
+impl FromStr for DigestFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "text" | "txt" => Ok(DigestFormat::Text),
+            "html" => Ok(DigestFormat::Html),
+            _ => Err(Error::InvalidDigestFormat(s.to_string())),
+        }
+    }
+}
+
+/// A titled list of issues in the digest
+struct Section<'a> {
+    heading: String,
+    /// Sub-lists, e.g. closed issues by reason; a single unnamed group otherwise
+    groups: Vec<(Option<&'static str>, Vec<&'a DigestEntry>)>,
+    show_comments: bool,
+}
+
+fn sections(digest: &Digest) -> Vec<Section<'_>> {
+    let closed_as = |reason: StateReason| -> Vec<&DigestEntry> {
+        digest
+            .closed
+            .iter()
+            .filter(|e| e.state_reason.unwrap_or_default() == reason)
+            .collect()
+    };
+    vec![
+        Section {
+            heading: format!("New issues ({})", digest.opened.len()),
+            groups: vec![(None, digest.opened.iter().collect())],
+            show_comments: false,
+        },
+        Section {
+            heading: format!("Closed issues ({})", digest.closed.len()),
+            groups: [
+                ("Completed", StateReason::Completed),
+                ("Not planned", StateReason::NotPlanned),
+            ]
+            .into_iter()
+            .map(|(name, reason)| (Some(name), closed_as(reason)))
+            .filter(|(_, entries)| !entries.is_empty())
+            .collect(),
+            show_comments: false,
+        },
+        Section {
+            heading: "Most commented".to_string(),
+            groups: vec![(None, digest.most_commented.iter().collect())],
+            show_comments: true,
+        },
+    ]
+}
+
+/// Render a digest as an email-ready document
+pub fn render_digest(digest: &Digest, format: DigestFormat) -> String {
+    match format {
+        DigestFormat::Text => render_text(digest),
+        DigestFormat::Html => render_html(digest),
+    }
+}
+
+fn title(digest: &Digest) -> String {
+    format!("SKIS digest since {}", digest.since.format("%Y-%m-%d"))
+}
+
+fn entry_text(entry: &DigestEntry, show_comments: bool) -> String {
+    let mut line = format!("#{} [{}] {}", entry.id, entry.issue_type, entry.title);
+    if show_comments {
+        line.push_str(&format!(" ({})", comment_count(entry.comments)));
+    }
+    line
+}
+
+fn comment_count(n: i64) -> String {
+    if n == 1 {
+        "1 comment".to_string()
+    } else {
+        format!("{} comments", n)
+    }
+}
+
+fn render_text(digest: &Digest) -> String {
+    let mut out = format!("{}\n", title(digest));
+    for section in sections(digest) {
+        out.push_str(&format!("\n{}\n", section.heading));
+        if section.groups.iter().all(|(_, entries)| entries.is_empty()) {
+            out.push_str("  None\n");
+            continue;
+        }
+        for (name, entries) in &section.groups {
+            let indent = match name {
+                Some(name) => {
+                    out.push_str(&format!("  {}\n", name));
+                    "    "
+                }
+                None => "  ",
+            };
+            for entry in entries {
+                out.push_str(indent);
+                out.push_str(&entry_text(entry, section.show_comments));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+const HTML_BODY_STYLE: &str = "font-family: sans-serif; font-size: 14px; color: #24292f;";
+const HTML_HEADING_STYLE: &str = "font-size: 16px; margin: 16px 0 4px;";
+const HTML_GROUP_STYLE: &str = "font-weight: bold; margin: 8px 0 0;";
+const HTML_LIST_STYLE: &str = "margin: 4px 0; padding-left: 20px;";
+const HTML_MUTED_STYLE: &str = "color: #6e7781;";
+
+fn render_html(digest: &Digest) -> String {
+    let mut out = format!(
+        "<div style=\"{}\">\n<h2 style=\"font-size: 20px; margin: 0;\">{}</h2>\n",
+        HTML_BODY_STYLE,
+        escape_html(&title(digest))
+    );
+    for section in sections(digest) {
+        out.push_str(&format!(
+            "<h3 style=\"{}\">{}</h3>\n",
+            HTML_HEADING_STYLE,
+            escape_html(&section.heading)
+        ));
+        if section.groups.iter().all(|(_, entries)| entries.is_empty()) {
+            out.push_str(&format!("<p style=\"{}\">None</p>\n", HTML_MUTED_STYLE));
+            continue;
+        }
+        for (name, entries) in &section.groups {
+            if let Some(name) = name {
+                out.push_str(&format!("<p style=\"{}\">{}</p>\n", HTML_GROUP_STYLE, name));
+            }
+            out.push_str(&format!("<ul style=\"{}\">\n", HTML_LIST_STYLE));
+            for entry in entries {
+                out.push_str(&format!(
+                    "<li>#{} <span style=\"{}\">[{}]</span> {}",
+                    entry.id,
+                    HTML_MUTED_STYLE,
+                    entry.issue_type,
+                    escape_html(&entry.title)
+                ));
+                if section.show_comments {
+                    out.push_str(&format!(
+                        " <span style=\"{}\">({})</span>",
+                        HTML_MUTED_STYLE,
+                        comment_count(entry.comments)
+                    ));
+                }
+                out.push_str("</li>\n");
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+    out.push_str("</div>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::IssueType;
+    use chrono::{TimeZone, Utc};
+
+    fn entry(id: i64, title: &str, reason: Option<StateReason>, comments: i64) -> DigestEntry {
+        DigestEntry {
+            id,
+            title: title.to_string(),
+            issue_type: IssueType::Bug,
+            state_reason: reason,
+            comments,
+        }
+    }
+
+    fn digest() -> Digest {
+        Digest {
+            since: Utc.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap(),
+            opened: vec![entry(7, "Crash on <save>", None, 0)],
+            closed: vec![
+                entry(3, "Login broken", Some(StateReason::Completed), 1),
+                entry(5, "Dark mode", Some(StateReason::NotPlanned), 0),
+            ],
+            most_commented: vec![
+                entry(4, "Slow sync", None, 3),
+                entry(3, "Login broken", Some(StateReason::Completed), 1),
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_text_digest() {
+        assert_eq!(
+            render_digest(&digest(), DigestFormat::Text),
+            "SKIS digest since 2024-01-08
+
+New issues (1)
+  #7 [bug] Crash on <save>
+
+Closed issues (2)
+  Completed
+    #3 [bug] Login broken
+  Not planned
+    #5 [bug] Dark mode
+
+Most commented
+  #4 [bug] Slow sync (3 comments)
+  #3 [bug] Login broken (1 comment)
+"
+        );
+    }
+
+    #[test]
+    fn renders_html_digest() {
+        assert_eq!(
+            render_digest(&digest(), DigestFormat::Html),
+            r#"<div style="font-family: sans-serif; font-size: 14px; color: #24292f;">
+<h2 style="font-size: 20px; margin: 0;">SKIS digest since 2024-01-08</h2>
+<h3 style="font-size: 16px; margin: 16px 0 4px;">New issues (1)</h3>
+<ul style="margin: 4px 0; padding-left: 20px;">
+<li>#7 <span style="color: #6e7781;">[bug]</span> Crash on &lt;save&gt;</li>
+</ul>
+<h3 style="font-size: 16px; margin: 16px 0 4px;">Closed issues (2)</h3>
+<p style="font-weight: bold; margin: 8px 0 0;">Completed</p>
+<ul style="margin: 4px 0; padding-left: 20px;">
+<li>#3 <span style="color: #6e7781;">[bug]</span> Login broken</li>
+</ul>
+<p style="font-weight: bold; margin: 8px 0 0;">Not planned</p>
+<ul style="margin: 4px 0; padding-left: 20px;">
+<li>#5 <span style="color: #6e7781;">[bug]</span> Dark mode</li>
+</ul>
+<h3 style="font-size: 16px; margin: 16px 0 4px;">Most commented</h3>
+<ul style="margin: 4px 0; padding-left: 20px;">
+<li>#4 <span style="color: #6e7781;">[bug]</span> Slow sync <span style="color: #6e7781;">(3 comments)</span></li>
+<li>#3 <span style="color: #6e7781;">[bug]</span> Login broken <span style="color: #6e7781;">(1 comment)</span></li>
+</ul>
+</div>
+"#
+        );
+    }
+
+    #[test]
+    fn empty_sections_say_none() {
+        let empty = Digest {
+            opened: Vec::new(),
+            closed: Vec::new(),
+            most_commented: Vec::new(),
+            ..digest()
+        };
+        let text = render_digest(&empty, DigestFormat::Text);
+        assert!(text.contains("New issues (0)\n  None\n"));
+        assert!(text.contains("Closed issues (0)\n  None\n"));
+        assert!(render_digest(&empty, DigestFormat::Html).contains(">None</p>"));
+    }
+
+    #[test]
+    fn parses_digest_format() {
+        assert_eq!("HTML".parse::<DigestFormat>().unwrap(), DigestFormat::Html);
+        assert!(matches!(
+            "pdf".parse::<DigestFormat>(),
+            Err(Error::InvalidDigestFormat(f)) if f == "pdf"
+        ));
+    }
+}
@@ -0,0 +1,321 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use crate::models::IssueView;
+
+/// A column of `issue list` output, chosen with `--fields`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueField {
+    Id,
+    Type,
+    State,
+    Labels,
+    Updated,
+    /// Whether the issue is soft-deleted; only interesting with `--deleted`
+    Deleted,
+    Title,
+}
+
+impl IssueField {
+    /// Columns shown when `--fields` is not given
+    pub const DEFAULT: &'static [IssueField] = &[
+        IssueField::Id,
+        IssueField::Type,
+        IssueField::State,
+        IssueField::Labels,
+        IssueField::Title,
+    ];
+
+    /// Default columns for `issue list --deleted`, marking deleted issues
+    pub const DEFAULT_WITH_DELETED: &'static [IssueField] = &[
+        IssueField::Id,
+        IssueField::Type,
+        IssueField::State,
+        IssueField::Deleted,
+        IssueField::Labels,
+        IssueField::Title,
+    ];
+
+    /// Parse a comma-separated field list such as `id,title`
+    pub fn parse_list(s: &str) -> Result<Vec<IssueField>> {
+        let fields = s
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(IssueField::from_str)
+            .collect::<Result<Vec<_>>>()?;
+        if fields.is_empty() {
+            return Err(Error::InvalidField(s.to_string()));
+        }
+        Ok(fields)
+    }
+
+    /// Key of this field in a serialized issue
+    pub fn json_key(self) -> &'static str {
+        match self {
+            IssueField::Id => "id",
+            IssueField::Type => "type",
+            IssueField::State => "state",
+            IssueField::Labels => "labels",
+            IssueField::Updated => "updated_at",
+            IssueField::Deleted => "deleted_at",
+            IssueField::Title => "title",
+        }
+    }
+
+    /// Table column heading
+    pub fn header(self) -> &'static str {
+        match self {
+            IssueField::Id => "ID",
+            IssueField::Type => "TYPE",
+            IssueField::State => "STATE",
+            IssueField::Labels => "LABELS",
+            IssueField::Updated => "UPDATED",
+            IssueField::Deleted => "DELETED",
+            IssueField::Title => "TITLE",
+        }
+    }
+
+    /// Table column width; the last column is never padded, and `issue list`
+    /// sizes the ID column to the numbers it shows
+    pub fn width(self) -> usize {
+        match self {
+            IssueField::Id => 6,
+            IssueField::Type | IssueField::State | IssueField::Deleted => 8,
+            IssueField::Labels => 20,
+            IssueField::Updated => 16,
+            IssueField::Title => 40,
+        }
+    }
+}
+
+impl FromStr for IssueField {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "id" => Ok(IssueField::Id),
+            "type" => Ok(IssueField::Type),
+            "state" => Ok(IssueField::State),
+            "labels" => Ok(IssueField::Labels),
+            "updated" => Ok(IssueField::Updated),
+            "deleted" => Ok(IssueField::Deleted),
+            "title" => Ok(IssueField::Title),
+            _ => Err(Error::InvalidField(s.to_string())),
+        }
+    }
+}
+
+/// A field of an issue printed on its own by `issue get --field`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewField {
+    Id,
+    Title,
+    Body,
+    Type,
+    State,
+    StateReason,
+    Points,
+    Labels,
+    CreatedAt,
+    UpdatedAt,
+    ClosedAt,
+    DeletedAt,
+    ArchivedAt,
+}
+
+impl ViewField {
+    /// The field's raw value: empty for null, one label name per line for
+    /// `labels`, and timestamps as in `issue view --json`
+    pub fn value(self, view: &IssueView) -> String {
+        fn time(t: Option<DateTime<Utc>>) -> String {
+            t.map(|t| t.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+                .unwrap_or_default()
+        }
+        match self {
+            ViewField::Id => view.id.to_string(),
+            ViewField::Title => view.title.clone(),
+            ViewField::Body => view.body.clone().unwrap_or_default(),
+            ViewField::Type => view.issue_type.to_string(),
+            ViewField::State => view.state.to_string(),
+            ViewField::StateReason => view.state_reason.map(|r| r.to_string()).unwrap_or_default(),
+            ViewField::Points => view.points.map(|p| p.to_string()).unwrap_or_default(),
+            ViewField::Labels => view
+                .labels
+                .iter()
+                .map(|l| l.name.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ViewField::CreatedAt => time(Some(view.created_at)),
+            ViewField::UpdatedAt => time(Some(view.updated_at)),
+            ViewField::ClosedAt => time(view.closed_at),
+            ViewField::DeletedAt => time(view.deleted_at),
+            ViewField::ArchivedAt => time(view.archived_at),
+        }
+    }
+}
+
+impl FromStr for ViewField {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "id" => Ok(ViewField::Id),
+            "title" => Ok(ViewField::Title),
+            "body" => Ok(ViewField::Body),
+            "type" => Ok(ViewField::Type),
+            "state" => Ok(ViewField::State),
+            "state_reason" => Ok(ViewField::StateReason),
+            "points" => Ok(ViewField::Points),
+            "labels" => Ok(ViewField::Labels),
+            "created_at" => Ok(ViewField::CreatedAt),
+            "updated_at" => Ok(ViewField::UpdatedAt),
+            "closed_at" => Ok(ViewField::ClosedAt),
+            "deleted_at" => Ok(ViewField::DeletedAt),
+            "archived_at" => Ok(ViewField::ArchivedAt),
+            _ => Err(Error::InvalidGetField(s.to_string())),
+        }
+    }
+}
+
+/// Keep only `fields` of a serialized object, in field order. Fields the object
+/// lacks (e.g. `labels` on an unlabelled query) come out as `null`.
+pub fn project_fields(value: &Value, fields: &[IssueField]) -> Value {
+    let mut map = Map::new();
+    for field in fields {
+        let key = field.json_key();
+        map.insert(
+            key.to_string(),
+            value.get(key).cloned().unwrap_or(Value::Null),
+        );
+    }
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IssueState, IssueType, LabelView, StateReason};
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn view() -> IssueView {
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+        IssueView {
+            id: 7,
+            title: "Crash on save".to_string(),
+            body: None,
+            issue_type: IssueType::Bug,
+            state: IssueState::Open,
+            state_reason: None,
+            points: None,
+            labels: Vec::new(),
+            linked_issues: Vec::new(),
+            created_at: at,
+            updated_at: at,
+            closed_at: None,
+            deleted_at: None,
+            deleted_reason: None,
+            archived_at: None,
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn parse_list_trims_and_keeps_order() {
+        assert_eq!(
+            IssueField::parse_list("title, ID,updated,deleted").unwrap(),
+            vec![
+                IssueField::Title,
+                IssueField::Id,
+                IssueField::Updated,
+                IssueField::Deleted
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_list_rejects_unknown_and_empty() {
+        assert!(matches!(
+            IssueField::parse_list("id,body"),
+            Err(Error::InvalidField(f)) if f == "body"
+        ));
+        assert!(IssueField::parse_list(" , ").is_err());
+    }
+
+    #[test]
+    fn view_field_values_of_an_open_issue() {
+        let view = view();
+        let value = |name: &str| name.parse::<ViewField>().unwrap().value(&view);
+        assert_eq!(value("id"), "7");
+        assert_eq!(value("TITLE"), "Crash on save");
+        assert_eq!(value("type"), "bug");
+        assert_eq!(value("state"), "open");
+        assert_eq!(value("created_at"), "2024-03-01T12:30:00Z");
+        assert_eq!(value("updated_at"), "2024-03-01T12:30:00Z");
+        // Nulls and empty lists print as empty strings
+        for name in [
+            "body",
+            "state_reason",
+            "points",
+            "labels",
+            "closed_at",
+            "deleted_at",
+            "archived_at",
+        ] {
+            assert_eq!(value(name), "", "{}", name);
+        }
+    }
+
+    #[test]
+    fn view_field_values_of_a_closed_labelled_issue() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 2, 8, 0, 0).unwrap();
+        let label = |name: &str| LabelView {
+            name: name.to_string(),
+            color: None,
+            description: None,
+        };
+        let view = IssueView {
+            body: Some("Line one\nLine two".to_string()),
+            state: IssueState::Closed,
+            state_reason: Some(StateReason::NotPlanned),
+            points: Some(5),
+            labels: vec![label("bug"), label("ui")],
+            closed_at: Some(at),
+            deleted_at: Some(at),
+            archived_at: Some(at),
+            ..view()
+        };
+        let value = |field: ViewField| field.value(&view);
+        assert_eq!(value(ViewField::Body), "Line one\nLine two");
+        assert_eq!(value(ViewField::State), "closed");
+        assert_eq!(value(ViewField::StateReason), "not_planned");
+        assert_eq!(value(ViewField::Points), "5");
+        assert_eq!(value(ViewField::Labels), "bug\nui");
+        assert_eq!(value(ViewField::ClosedAt), "2024-03-02T08:00:00Z");
+        assert_eq!(value(ViewField::DeletedAt), "2024-03-02T08:00:00Z");
+        assert_eq!(value(ViewField::ArchivedAt), "2024-03-02T08:00:00Z");
+    }
+
+    #[test]
+    fn view_field_rejects_unknown_names() {
+        assert!(matches!(
+            "assignee".parse::<ViewField>(),
+            Err(Error::InvalidGetField(f)) if f == "assignee"
+        ));
+    }
+
+    #[test]
+    fn project_fields_keeps_requested_keys() {
+        let issue = json!({"id": 3, "title": "Crash", "type": "bug", "updated_at": "2024-01-01"});
+        let projected = project_fields(&issue, &[IssueField::Id, IssueField::Updated]);
+        assert_eq!(projected, json!({"id": 3, "updated_at": "2024-01-01"}));
+        assert_eq!(
+            project_fields(&issue, &[IssueField::Labels]),
+            json!({"labels": null})
+        );
+    }
+}
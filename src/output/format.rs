@@ -1,4 +1,106 @@
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::error::{Error, Result};
+
+/// Output format for list commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    /// Tab-separated values, no header row
+    Tsv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "tsv" => Ok(OutputFormat::Tsv),
+            _ => Err(Error::InvalidOutputFormat(s.to_string())),
+        }
+    }
+}
+
+/// Join fields into a TSV row, replacing embedded tabs and newlines with spaces
+pub fn tsv_row(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|f| f.replace(['\t', '\r', '\n'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// Width of `s` in terminal columns (wide CJK and emoji count as two,
+/// combining marks as zero)
+pub fn display_width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// Cut `s` to at most `width` columns, ending in `…` (counted in the width) when cut
+pub fn truncate_to_width(s: &str, width: usize) -> String {
+    if display_width(s) <= width {
+        return s.to_string();
+    }
+    let budget = width.saturating_sub(1);
+    let mut used = 0;
+    let mut out = String::new();
+    for c in s.chars() {
+        let w = UnicodeWidthChar::width(c).unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    if width > 0 {
+        out.push('…');
+    }
+    out
+}
+
+/// Truncate or pad `s` with spaces to exactly `width` columns
+pub fn fit_to_width(s: &str, width: usize) -> String {
+    let mut out = truncate_to_width(s, width);
+    let used = display_width(&out);
+    out.push_str(&" ".repeat(width.saturating_sub(used)));
+    out
+}
+
+/// Default for `issue list --max-labels`
+pub const DEFAULT_MAX_LABELS: usize = 3;
+
+/// How many of `names` fit in a table cell of `width` columns, showing at
+/// most `max` and leaving room for the [`more_labels`] note on the rest.
+/// The first label is always counted; the cell cuts it if it is too wide.
+pub fn labels_that_fit(names: &[&str], max: usize, width: usize) -> usize {
+    let mut used = 0;
+    let mut shown = 0;
+    for (i, name) in names.iter().enumerate().take(max.max(1)) {
+        let sep = usize::from(i > 0);
+        let note = display_width(&more_labels(names.len() - i - 1));
+        if i > 0 && used + sep + display_width(name) + note > width {
+            break;
+        }
+        used += sep + display_width(name);
+        shown += 1;
+    }
+    shown
+}
+
+/// Note appended to a labels cell for the `hidden` labels left out of it
+pub fn more_labels(hidden: usize) -> String {
+    match hidden {
+        0 => String::new(),
+        n => format!(" +{} more", n),
+    }
+}
 
 /// Format a timestamp as a human-readable relative time string.
 /// Examples: "just now", "5 minutes ago", "2 hours ago", "3 days ago"
@@ -72,6 +174,94 @@ mod tests {
     use super::*;
     use chrono::Duration;
 
+    #[test]
+    fn output_format_from_str() {
+        assert_eq!(
+            "table".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Table
+        );
+        assert_eq!("JSON".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+        assert_eq!("tsv".parse::<OutputFormat>().unwrap(), OutputFormat::Tsv);
+        assert!("csv".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn tsv_row_escapes_separators() {
+        assert_eq!(tsv_row(&["a", "b\tc", "d\ne"]), "a\tb c\td e");
+        assert_eq!(tsv_row(&["only"]), "only");
+    }
+
+    #[test]
+    fn display_width_counts_terminal_columns() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("日本"), 4);
+        assert_eq!(display_width("🐛 bug"), 6);
+        // "e" + combining acute accent is one column
+        assert_eq!(display_width("cafe\u{301}"), 4);
+    }
+
+    #[test]
+    fn truncate_to_width_counts_the_ellipsis() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate_to_width("a longer title", 8), "a longe…");
+        assert_eq!(display_width(&truncate_to_width("a longer title", 8)), 8);
+        assert_eq!(truncate_to_width("anything", 0), "");
+    }
+
+    #[test]
+    fn truncate_to_width_never_splits_wide_or_combined_characters() {
+        // A wide character that would straddle the limit is dropped whole
+        assert_eq!(truncate_to_width("日本語のタイトル", 6), "日本…");
+        assert_eq!(truncate_to_width("日本語のタイトル", 7), "日本語…");
+        assert_eq!(truncate_to_width("🐛🐛🐛🐛", 5), "🐛🐛…");
+        // Combining marks stay with their base character
+        assert_eq!(truncate_to_width("cafe\u{301} au lait", 5), "cafe\u{301}…");
+    }
+
+    #[test]
+    fn fit_to_width_pads_by_display_width() {
+        assert_eq!(fit_to_width("ab", 4), "ab  ");
+        assert_eq!(fit_to_width("日本", 6), "日本  ");
+        assert_eq!(fit_to_width("日本語のタイトル", 6), "日本… ");
+        for title in ["Crash on save", "保存時にクラッシュ", "🔥🔥 hot fix", "re\u{301}sume\u{301}"] {
+            assert_eq!(display_width(&fit_to_width(title, 10)), 10, "{}", title);
+        }
+    }
+
+    #[test]
+    fn labels_that_fit_caps_many_short_labels() {
+        let names = ["ui", "db", "api", "cli", "docs", "perf"];
+        assert_eq!(labels_that_fit(&names, 3, 20), 3);
+        assert_eq!(more_labels(names.len() - 3), " +3 more");
+        assert_eq!(labels_that_fit(&names, 10, 20), 3);
+        assert_eq!(labels_that_fit(&names, 10, 21), 4);
+        assert_eq!(labels_that_fit(&names, 10, 40), 6);
+        assert_eq!(labels_that_fit(&names[..3], 3, 20), 3);
+        assert_eq!(more_labels(0), "");
+    }
+
+    #[test]
+    fn labels_that_fit_counts_width_not_just_labels() {
+        let names = ["needs-design-review", "bug", "ui"];
+        // One long label fills the cell, leaving only the note for the rest
+        assert_eq!(labels_that_fit(&names, 3, 20), 1);
+        assert_eq!(
+            labels_that_fit(&["bug", "needs-design-review", "ui"], 3, 20),
+            1
+        );
+        assert_eq!(
+            labels_that_fit(&["bug", "ui", "needs-design-review"], 3, 20),
+            2
+        );
+        // Wide characters count double
+        assert_eq!(labels_that_fit(&["日本語", "中文"], 3, 11), 2);
+        assert_eq!(labels_that_fit(&["日本語", "中文"], 3, 10), 1);
+        // The first label is kept however narrow the cell
+        assert_eq!(labels_that_fit(&names, 3, 5), 1);
+        assert_eq!(labels_that_fit(&names, 0, 20), 1);
+    }
+
     #[test]
     fn format_relative_time_seconds() {
         let now = Utc::now();
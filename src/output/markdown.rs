@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+
+use super::reference::{link_references, references_footer, ReferenceFormat};
+use crate::models::{Issue, IssueType};
+
+/// How changelog entries are grouped into sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangelogGroup {
+    #[default]
+    Type,
+    Label,
+    /// Two sections: bug fixes, and features for every other type
+    Release,
+}
+
+/// Section heading used when grouping by label and an issue has none
+const UNLABELED_HEADING: &str = "Other";
+
+/// Render closed issues as Markdown release notes.
+///
+/// Each entry in `issues` pairs an issue with its label names. When grouping
+/// by label, an issue with several labels appears under each of them.
+pub fn render_changelog(
+    heading: &str,
+    issues: &[(Issue, Vec<String>)],
+    group: ChangelogGroup,
+) -> String {
+    render_sections(heading, None, issues, group)
+}
+
+/// Render closed issues as release notes: a count summary, then bug fixes and
+/// features in the same layout as [`render_changelog`].
+pub fn render_release_notes(heading: &str, issues: &[(Issue, Vec<String>)]) -> String {
+    let bugs = issues
+        .iter()
+        .filter(|(issue, _)| issue.issue_type == IssueType::Bug)
+        .count();
+    let features = issues.len() - bugs;
+    let summary = format!(
+        "{} closed: {}, {}.",
+        plural(issues.len(), "issue", "issues"),
+        plural(bugs, "bug fix", "bug fixes"),
+        plural(features, "feature", "features")
+    );
+    render_sections(heading, Some(&summary), issues, ChangelogGroup::Release)
+}
+
+/// Render one issue as a Markdown document, for `issue view --markdown`.
+///
+/// `#N` mentions in the body of any issue in `referenced` become links to its
+/// deep URL, and are listed again in a footer.
+pub fn render_issue(issue: &Issue, labels: &[String], referenced: &[Issue]) -> String {
+    let mut out = format!("# {} (#{})\n\n", issue.title, issue.id);
+    out.push_str(&format!("- Type: {}\n", issue.issue_type));
+    out.push_str(&format!("- State: {}\n", issue.state));
+    if !labels.is_empty() {
+        out.push_str(&format!("- Labels: {}\n", labels.join(", ")));
+    }
+
+    if let Some(body) = issue.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        out.push_str(&format!(
+            "\n{}\n",
+            link_references(body.trim_end(), referenced)
+        ));
+    }
+    if let Some(footer) = references_footer(referenced, ReferenceFormat::Markdown) {
+        out.push_str(&format!("\n{}\n", footer));
+    }
+    out
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+fn render_sections(
+    heading: &str,
+    summary: Option<&str>,
+    issues: &[(Issue, Vec<String>)],
+    group: ChangelogGroup,
+) -> String {
+    let mut out = format!("## {}\n", heading);
+    if let Some(summary) = summary {
+        out.push_str(&format!("\n{}\n", summary));
+    }
+
+    if issues.is_empty() {
+        out.push_str("\nNo changes.\n");
+        return out;
+    }
+
+    for (section, entries) in group_issues(issues, group) {
+        out.push_str(&format!("\n### {}\n\n", section));
+        for issue in entries {
+            out.push_str(&format!("- {} (#{})\n", issue.title, issue.id));
+        }
+    }
+
+    out
+}
+
+fn group_issues(
+    issues: &[(Issue, Vec<String>)],
+    group: ChangelogGroup,
+) -> Vec<(String, Vec<&Issue>)> {
+    match group {
+        ChangelogGroup::Type => [
+            IssueType::Epic,
+            IssueType::Request,
+            IssueType::Task,
+            IssueType::Bug,
+        ]
+        .into_iter()
+        .map(|t| {
+            let entries: Vec<&Issue> = issues
+                .iter()
+                .map(|(issue, _)| issue)
+                .filter(|issue| issue.issue_type == t)
+                .collect();
+            (type_heading(t).to_string(), entries)
+        })
+        .filter(|(_, entries)| !entries.is_empty())
+        .collect(),
+        ChangelogGroup::Label => {
+            let mut sections: BTreeMap<String, Vec<&Issue>> = BTreeMap::new();
+            let mut unlabeled = Vec::new();
+            for (issue, labels) in issues {
+                if labels.is_empty() {
+                    unlabeled.push(issue);
+                }
+                for label in labels {
+                    sections.entry(label.clone()).or_default().push(issue);
+                }
+            }
+            let mut grouped: Vec<_> = sections.into_iter().collect();
+            if !unlabeled.is_empty() {
+                grouped.push((UNLABELED_HEADING.to_string(), unlabeled));
+            }
+            grouped
+        }
+        ChangelogGroup::Release => {
+            let (bugs, features): (Vec<&Issue>, Vec<&Issue>) = issues
+                .iter()
+                .map(|(issue, _)| issue)
+                .partition(|issue| issue.issue_type == IssueType::Bug);
+            [
+                (type_heading(IssueType::Bug).to_string(), bugs),
+                (type_heading(IssueType::Request).to_string(), features),
+            ]
+            .into_iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .collect()
+        }
+    }
+}
+
+fn type_heading(issue_type: IssueType) -> &'static str {
+    match issue_type {
+        IssueType::Epic => "Epics",
+        IssueType::Request => "Features",
+        IssueType::Task => "Tasks",
+        IssueType::Bug => "Bug fixes",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IssueState, StateReason};
+    use chrono::Utc;
+
+    fn closed_issue(id: i64, title: &str, issue_type: IssueType) -> Issue {
+        let now = Utc::now();
+        Issue {
+            id,
+            title: title.to_string(),
+            body: None,
+            issue_type,
+            state: IssueState::Closed,
+            state_reason: Some(StateReason::Completed),
+            points: None,
+            created_at: now,
+            updated_at: now,
+            closed_at: Some(now),
+            deleted_at: None,
+            deleted_reason: None,
+            archived_at: None,
+            version: 0,
+            labels: None,
+        }
+    }
+
+    #[test]
+    fn render_changelog_groups_by_type() {
+        let issues = vec![
+            (closed_issue(3, "Fix crash", IssueType::Bug), vec![]),
+            (closed_issue(1, "Dark mode", IssueType::Request), vec![]),
+            (closed_issue(2, "Fix typo", IssueType::Bug), vec![]),
+        ];
+
+        let md = render_changelog("Changes since v0.9", &issues, ChangelogGroup::Type);
+
+        assert_eq!(
+            md,
+            "## Changes since v0.9\n\
+             \n### Features\n\n- Dark mode (#1)\n\
+             \n### Bug fixes\n\n- Fix crash (#3)\n- Fix typo (#2)\n"
+        );
+    }
+
+    #[test]
+    fn render_changelog_groups_by_label() {
+        let issues = vec![
+            (
+                closed_issue(1, "Faster login", IssueType::Task),
+                vec!["perf".to_string(), "auth".to_string()],
+            ),
+            (closed_issue(2, "Cleanup", IssueType::Task), vec![]),
+        ];
+
+        let md = render_changelog("Changes", &issues, ChangelogGroup::Label);
+
+        assert_eq!(
+            md,
+            "## Changes\n\
+             \n### auth\n\n- Faster login (#1)\n\
+             \n### perf\n\n- Faster login (#1)\n\
+             \n### Other\n\n- Cleanup (#2)\n"
+        );
+    }
+
+    #[test]
+    fn render_release_notes_splits_bugs_from_features() {
+        let issues = vec![
+            (closed_issue(2, "Fix crash", IssueType::Bug), vec![]),
+            (closed_issue(4, "Dark mode", IssueType::Request), vec![]),
+            (closed_issue(5, "Faster sync", IssueType::Task), vec![]),
+        ];
+
+        let md = render_release_notes("Release notes: release/1.4", &issues);
+
+        assert_eq!(
+            md,
+            "## Release notes: release/1.4\n\
+             \n3 issues closed: 1 bug fix, 2 features.\n\
+             \n### Bug fixes\n\n- Fix crash (#2)\n\
+             \n### Features\n\n- Dark mode (#4)\n- Faster sync (#5)\n"
+        );
+        assert_eq!(
+            render_release_notes("Notes", &[]),
+            "## Notes\n\n0 issues closed: 0 bug fixes, 0 features.\n\nNo changes.\n"
+        );
+    }
+
+    #[test]
+    fn render_changelog_empty() {
+        let md = render_changelog("Changes", &[], ChangelogGroup::Type);
+        assert_eq!(md, "## Changes\n\nNo changes.\n");
+    }
+
+    #[test]
+    fn render_issue_links_references() {
+        let mut issue = closed_issue(5, "Crash on save", IssueType::Bug);
+        issue.body = Some("Same as #3, not #99.\n\n```\n#3\n```\n".to_string());
+        let referenced = [closed_issue(3, "Old crash", IssueType::Bug)];
+        assert_eq!(
+            render_issue(&issue, &["ui".to_string()], &referenced),
+            "# Crash on save (#5)\n\n\
+             - Type: bug\n\
+             - State: closed\n\
+             - Labels: ui\n\
+             \n\
+             Same as [#3](skis://issue/3), not #99.\n\n```\n#3\n```\n\
+             \n\
+             References: [#3](skis://issue/3) Old crash (closed)\n"
+        );
+    }
+}
@@ -1,3 +1,17 @@
+mod digest;
+mod fields;
 mod format;
+mod markdown;
+mod reference;
 
-pub use format::{format_relative_time, format_timestamp};
+pub use digest::{render_digest, DigestFormat};
+pub use fields::{project_fields, IssueField, ViewField};
+pub use format::{
+    display_width, fit_to_width, format_relative_time, format_timestamp, labels_that_fit,
+    more_labels, truncate_to_width, tsv_row, OutputFormat, DEFAULT_MAX_LABELS,
+};
+pub use markdown::{render_changelog, render_issue, render_release_notes, ChangelogGroup};
+pub use reference::{
+    format_issue_reference, issue_url, link_references, references_footer, scan_references,
+    ReferenceFormat,
+};
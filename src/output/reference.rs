@@ -0,0 +1,293 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::models::Issue;
+
+/// Text form of a copyable issue reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferenceFormat {
+    /// `skis#12 — Login button broken [bug, open]`
+    #[default]
+    Plain,
+    /// The plain reference as a Markdown link to the issue's deep URL
+    Markdown,
+}
+
+impl FromStr for ReferenceFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "plain" => Ok(ReferenceFormat::Plain),
+            "markdown" | "md" => Ok(ReferenceFormat::Markdown),
+            _ => Err(Error::InvalidReferenceFormat(s.to_string())),
+        }
+    }
+}
+
+/// Deep URL that opens an issue in the SKIS GUI
+pub fn issue_url(id: i64) -> String {
+    format!("skis://issue/{}", id)
+}
+
+/// One-line reference to an issue, suitable for pasting into chat or commit messages
+pub fn format_issue_reference(issue: &Issue, format: ReferenceFormat) -> String {
+    let text = format!(
+        "skis#{} — {} [{}, {}]",
+        issue.id, issue.title, issue.issue_type, issue.state
+    );
+    match format {
+        ReferenceFormat::Plain => text,
+        ReferenceFormat::Markdown => format!(
+            "[{}]({})",
+            escape_markdown_link_text(&text),
+            issue_url(issue.id)
+        ),
+    }
+}
+
+/// Issue numbers mentioned as `#N` in Markdown text, in order of first mention.
+///
+/// Mentions inside fenced code blocks and inline code spans are ignored, as are
+/// `#N` glued to a word (`abc#1`), HTML entities (`&#39;`) and URL fragments.
+pub fn scan_references(text: &str) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for (_, id) in reference_spans(text) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// The "References: ..." footer for the issues a body mentions, or `None` if it
+/// mentions none. `referenced` is the result of looking up [`scan_references`];
+/// numbers that don't exist are simply left out of it.
+pub fn references_footer(referenced: &[Issue], format: ReferenceFormat) -> Option<String> {
+    if referenced.is_empty() {
+        return None;
+    }
+    let entries: Vec<String> = referenced
+        .iter()
+        .map(|issue| {
+            let number = match format {
+                ReferenceFormat::Plain => format!("#{}", issue.id),
+                ReferenceFormat::Markdown => format!("[#{}]({})", issue.id, issue_url(issue.id)),
+            };
+            let state = if issue.deleted_at.is_some() {
+                "deleted".to_string()
+            } else {
+                issue.state.to_string()
+            };
+            format!("{} {} ({})", number, issue.title, state)
+        })
+        .collect();
+    Some(format!("References: {}", entries.join(", ")))
+}
+
+/// Turn each `#N` mention of a `referenced` issue into a Markdown link to its
+/// deep URL. Unknown numbers and mentions inside code are left as they are.
+pub fn link_references(text: &str, referenced: &[Issue]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (span, id) in reference_spans(text) {
+        if referenced.iter().any(|issue| issue.id == id) {
+            out.push_str(&text[last..span.start]);
+            out.push_str(&format!("[{}]({})", &text[span.clone()], issue_url(id)));
+            last = span.end;
+        }
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Byte ranges of the `#N` mentions outside code, with the number each names
+fn reference_spans(text: &str) -> Vec<(Range<usize>, i64)> {
+    let mut spans = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        // A fence is three or more backticks or tildes; it is closed by a run
+        // of the same character at least as long
+        let trimmed = line.trim_start_matches(' ');
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        let run = marker.map_or(0, |c| trimmed.chars().take_while(|&t| t == c).count());
+        if line.len() - trimmed.len() <= 3 && run >= 3 {
+            let marker = marker.expect("fence marker");
+            match fence {
+                None => fence = Some((marker, run)),
+                Some((open, len)) if open == marker && run >= len => fence = None,
+                Some(_) => {}
+            }
+            continue;
+        }
+        if fence.is_none() {
+            scan_line(line, start, &mut spans);
+        }
+    }
+    spans
+}
+
+fn scan_line(line: &str, start: usize, spans: &mut Vec<(Range<usize>, i64)>) {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'`' => {
+                // Skip an inline code span: a run of backticks up to the next
+                // run of the same length. An unmatched run is plain text.
+                let run = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+                let closing = "`".repeat(run);
+                let after = i + run;
+                i = match line[after..].find(&closing) {
+                    Some(pos) => after + pos + run,
+                    None => after,
+                };
+            }
+            b'#' => {
+                let glued = i > 0 && {
+                    let prev = bytes[i - 1];
+                    prev.is_ascii_alphanumeric() || b"_#&/".contains(&prev) || prev >= 0x80
+                };
+                let digits = bytes[i + 1..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                let end = i + 1 + digits;
+                let trailing = bytes
+                    .get(end)
+                    .is_some_and(|&b| b.is_ascii_alphanumeric() || b == b'_');
+                if !glued && digits > 0 && !trailing {
+                    if let Ok(id @ 1..) = line[i + 1..end].parse::<i64>() {
+                        spans.push((start + i..start + end, id));
+                    }
+                }
+                i = end.max(i + 1);
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+fn escape_markdown_link_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IssueState, IssueType};
+    use chrono::Utc;
+
+    fn issue(id: i64, title: &str) -> Issue {
+        let now = Utc::now();
+        Issue {
+            id,
+            title: title.to_string(),
+            body: None,
+            issue_type: IssueType::Bug,
+            state: IssueState::Open,
+            state_reason: None,
+            points: None,
+            created_at: now,
+            updated_at: now,
+            closed_at: None,
+            deleted_at: None,
+            deleted_reason: None,
+            archived_at: None,
+            version: 0,
+            labels: None,
+        }
+    }
+
+    #[test]
+    fn plain_reference() {
+        let reference =
+            format_issue_reference(&issue(12, "Login button broken"), ReferenceFormat::Plain);
+        assert_eq!(reference, "skis#12 — Login button broken [bug, open]");
+    }
+
+    #[test]
+    fn markdown_reference_links_to_deep_url() {
+        let reference =
+            format_issue_reference(&issue(12, "Login button broken"), ReferenceFormat::Markdown);
+        assert_eq!(
+            reference,
+            "[skis#12 — Login button broken \\[bug, open\\]](skis://issue/12)"
+        );
+    }
+
+    #[test]
+    fn markdown_reference_escapes_brackets_in_title() {
+        let reference = format_issue_reference(&issue(3, "[WIP] a\\b"), ReferenceFormat::Markdown);
+        assert!(reference.starts_with("[skis#3 — \\[WIP\\] a\\\\b"));
+    }
+
+    #[test]
+    fn scan_references_in_order_without_repeats() {
+        let ids = scan_references("See #12, then #3 (and #12 again).\n#7: done");
+        assert_eq!(ids, vec![12, 3, 7]);
+    }
+
+    #[test]
+    fn scan_references_ignores_code_fences() {
+        let text = "Before #1\n```sh\nskis issue view #2\n```\n~~~~\n#3\n```\n#4\n~~~~\nAfter #5";
+        assert_eq!(scan_references(text), vec![1, 5]);
+    }
+
+    #[test]
+    fn scan_references_ignores_inline_code() {
+        let text = "Run `skis issue view #2` or ``a ` #3`` for #4, but ` #5 is unmatched";
+        assert_eq!(scan_references(text), vec![4, 5]);
+    }
+
+    #[test]
+    fn scan_references_ignores_glued_and_non_numeric_mentions() {
+        let text = "abc#1 &#39; http://x/#2 ##3 #4a #0 #tag #6_ (#7)";
+        assert_eq!(scan_references(text), vec![7]);
+    }
+
+    #[test]
+    fn references_footer_lists_titles_and_state() {
+        let mut closed = issue(3, "Old crash");
+        closed.state = IssueState::Closed;
+        let referenced = [issue(12, "Login button broken"), closed];
+        assert_eq!(
+            references_footer(&referenced, ReferenceFormat::Plain).unwrap(),
+            "References: #12 Login button broken (open), #3 Old crash (closed)"
+        );
+        assert_eq!(
+            references_footer(&referenced[..1], ReferenceFormat::Markdown).unwrap(),
+            "References: [#12](skis://issue/12) Login button broken (open)"
+        );
+        assert_eq!(references_footer(&[], ReferenceFormat::Plain), None);
+    }
+
+    #[test]
+    fn link_references_leaves_unknown_and_code_mentions() {
+        let text = "See #12 and #99.\n```\n#12\n```\n`#12`";
+        assert_eq!(
+            link_references(text, &[issue(12, "Login button broken")]),
+            "See [#12](skis://issue/12) and #99.\n```\n#12\n```\n`#12`"
+        );
+    }
+
+    #[test]
+    fn reference_format_from_str() {
+        assert_eq!(
+            "md".parse::<ReferenceFormat>().unwrap(),
+            ReferenceFormat::Markdown
+        );
+        assert_eq!(
+            "Plain".parse::<ReferenceFormat>().unwrap(),
+            ReferenceFormat::Plain
+        );
+        assert!("html".parse::<ReferenceFormat>().is_err());
+    }
+}
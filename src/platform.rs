@@ -0,0 +1,42 @@
+//! Platform-specific helpers for handing paths and applications to the OS
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+use std::process::{Child, Command};
+
+/// Open a file or directory with the system's default handler
+/// (Finder on macOS, Explorer on Windows, xdg-open elsewhere)
+pub fn open_path(path: &Path) -> io::Result<Child> {
+    system_opener().arg(path).spawn()
+}
+
+/// Launch an application with arguments.
+/// macOS `.app` bundles are started via `open -a <bundle> --args ...`.
+pub fn launch_app(app: &Path, args: &[&OsStr]) -> io::Result<Child> {
+    if app.extension() == Some(OsStr::new("app")) {
+        Command::new("open")
+            .arg("-a")
+            .arg(app)
+            .arg("--args")
+            .args(args)
+            .spawn()
+    } else {
+        Command::new(app).args(args).spawn()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_opener() -> Command {
+    Command::new("open")
+}
+
+#[cfg(target_os = "windows")]
+fn system_opener() -> Command {
+    Command::new("explorer")
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn system_opener() -> Command {
+    Command::new("xdg-open")
+}
@@ -0,0 +1,72 @@
+//! Issue body templates
+//!
+//! A new issue's editor starts from the template for its type: the file
+//! `.skis/templates/<type>.md` (e.g. `bug.md`) when it exists, otherwise a
+//! built-in skeleton. An empty template file turns the skeleton off.
+
+use std::path::Path;
+
+use crate::error::Result;
+use crate::models::IssueType;
+
+/// Directory inside `.skis/` holding `<type>.md` templates
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Skeleton used when a repository has no template file for `issue_type`
+pub fn builtin_template(issue_type: IssueType) -> &'static str {
+    match issue_type {
+        IssueType::Bug => "## Steps to reproduce\n\n1.\n\n## Expected\n\n\n## Actual\n\n",
+        IssueType::Task => "## Goal\n\n\n## Done when\n\n- [ ]\n",
+        IssueType::Request => "## Problem\n\n\n## Proposal\n\n",
+        IssueType::Epic => "## Goal\n\n\n## Scope\n\n-\n",
+    }
+}
+
+/// Template for a new `issue_type` body in the repository at `skis_dir`
+pub fn body_template(skis_dir: &Path, issue_type: IssueType) -> Result<String> {
+    let path = skis_dir
+        .join(TEMPLATES_DIR)
+        .join(format!("{}.md", issue_type));
+    match std::fs::read_to_string(&path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Ok(builtin_template(issue_type).to_string())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn falls_back_to_builtin_template() {
+        let dir = TempDir::new().unwrap();
+        let bug = body_template(dir.path(), IssueType::Bug).unwrap();
+        assert!(bug.starts_with("## Steps to reproduce"));
+        assert_eq!(bug, builtin_template(IssueType::Bug));
+        assert_ne!(body_template(dir.path(), IssueType::Task).unwrap(), bug);
+    }
+
+    #[test]
+    fn template_file_overrides_builtin_for_its_type_only() {
+        let dir = TempDir::new().unwrap();
+        let templates = dir.path().join(TEMPLATES_DIR);
+        std::fs::create_dir(&templates).unwrap();
+        std::fs::write(templates.join("bug.md"), "## Version\n\n").unwrap();
+        std::fs::write(templates.join("task.md"), "").unwrap();
+
+        assert_eq!(
+            body_template(dir.path(), IssueType::Bug).unwrap(),
+            "## Version\n\n"
+        );
+        // An empty file means no skeleton
+        assert_eq!(body_template(dir.path(), IssueType::Task).unwrap(), "");
+        assert_eq!(
+            body_template(dir.path(), IssueType::Epic).unwrap(),
+            builtin_template(IssueType::Epic)
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Throwaway repositories for tests and doc examples.
+//!
+//! Enabled with the `test-support` feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! skis = { version = "0.1", features = ["test-support"] }
+//! ```
+
+use rusqlite::{params, Transaction, TransactionBehavior};
+use tempfile::TempDir;
+
+use crate::db::SkisDb;
+use crate::error::Result;
+use crate::models::IssueType;
+
+/// Issues created by `seed_large_repo`
+pub const LARGE_REPO_ISSUES: usize = 10_000;
+/// Labels created by `seed_large_repo`
+pub const LARGE_REPO_LABELS: usize = 30;
+/// Comments created by `seed_large_repo`
+pub const LARGE_REPO_COMMENTS: usize = 50_000;
+
+/// Words the seeded titles, bodies and comments are made of
+const WORDS: &[&str] = &[
+    "crash", "login", "sync", "cache", "export", "search", "button", "timeout", "render", "docs",
+    "import", "session", "layout", "memory", "upload", "filter",
+];
+
+/// Initialize a repository in a new temporary directory. The directory is
+/// removed when the returned `TempDir` is dropped, so keep it alive as long
+/// as the database is in use.
+///
+/// ```
+/// let (db, _dir) = ski::test_support::temp_repo()?;
+/// assert!(db.labels()?.is_empty());
+/// # Ok::<(), ski::Error>(())
+/// ```
+pub fn temp_repo() -> Result<(SkisDb, TempDir)> {
+    let dir = TempDir::new()?;
+    let db = SkisDb::init(dir.path())?;
+    Ok((db, dir))
+}
+
+/// Fill `db` with `LARGE_REPO_ISSUES` issues, `LARGE_REPO_LABELS` labels and
+/// `LARGE_REPO_COMMENTS` comments, for benchmarks and tests of large repositories
+pub fn seed_large_repo(db: &SkisDb) -> Result<()> {
+    seed_repo(
+        db,
+        LARGE_REPO_ISSUES,
+        LARGE_REPO_LABELS,
+        LARGE_REPO_COMMENTS,
+    )
+}
+
+/// Add generated issues, labels and comments to a new repository in one transaction.
+///
+/// The data is the same on every run. Labels are named `label-00`, `label-01`,
+/// ... and issue `n` carries labels `n % labels` and `(n * 7) % labels`.
+/// Every third issue is closed. Issues were created a minute apart from
+/// 2024-01-01, so issue numbers and creation order agree. Comments are spread
+/// across the issues.
+pub fn seed_repo(db: &SkisDb, issues: usize, labels: usize, comments: usize) -> Result<()> {
+    let tx = Transaction::new_unchecked(db.conn(), TransactionBehavior::Immediate)?;
+    let types = [
+        IssueType::Task,
+        IssueType::Bug,
+        IssueType::Request,
+        IssueType::Epic,
+    ];
+    let word = |n: usize| WORDS[n % WORDS.len()];
+
+    {
+        let mut insert_label = tx.prepare("INSERT INTO labels (name) VALUES (?1)")?;
+        let mut label_ids = Vec::with_capacity(labels);
+        for n in 0..labels {
+            label_ids.push(insert_label.insert([format!("label-{:02}", n)])?);
+        }
+
+        let mut insert_issue = tx.prepare(
+            "INSERT INTO issues (title, body, type, state, state_reason, created_at, updated_at,
+                                 closed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, datetime('2024-01-01', ?6), datetime('2024-01-01', ?6),
+                     ?7)",
+        )?;
+        let mut insert_issue_label =
+            tx.prepare("INSERT OR IGNORE INTO issue_labels (issue_id, label_id) VALUES (?1, ?2)")?;
+        let mut issue_ids = Vec::with_capacity(issues);
+        for n in 1..=issues {
+            let closed = n % 3 == 0;
+            let id = insert_issue.insert(params![
+                format!("{} {} issue {}", word(n), word(n / 7), n),
+                format!("Seeing {} trouble near the {}.", word(n * 3), word(n * 5)),
+                types[n % types.len()].to_string(),
+                if closed { "closed" } else { "open" },
+                closed.then_some("completed"),
+                format!("+{} minutes", n),
+                closed.then_some("2024-06-01 00:00:00"),
+            ])?;
+            issue_ids.push(id);
+            if labels > 0 {
+                for label in [n % labels, (n * 7) % labels] {
+                    insert_issue_label.execute([id, label_ids[label]])?;
+                }
+            }
+        }
+
+        let mut insert_comment = tx.prepare(
+            "INSERT INTO comments (issue_id, body, created_at, updated_at)
+             VALUES (?1, ?2, datetime('2024-02-01', ?3), datetime('2024-02-01', ?3))",
+        )?;
+        for n in 0..comments {
+            let Some(&issue_id) = issue_ids.get(n * 13 % issues.max(1)) else {
+                break;
+            };
+            insert_comment.execute(params![
+                issue_id,
+                format!("Still seeing the {} problem", word(n)),
+                format!("+{} seconds", n),
+            ])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IssueFilter, IssueState};
+
+    #[test]
+    fn seed_repo_fills_issues_labels_and_comments() {
+        let (db, _dir) = temp_repo().unwrap();
+        seed_repo(&db, 30, 5, 100).unwrap();
+
+        let all = IssueFilter {
+            limit: 100,
+            ..Default::default()
+        };
+        assert_eq!(db.count(&all).unwrap(), 30);
+        let closed = IssueFilter {
+            state: Some(IssueState::Closed),
+            ..all.clone()
+        };
+        assert_eq!(db.count(&closed).unwrap(), 10);
+        assert_eq!(db.labels().unwrap().len(), 5);
+
+        // Issue 3 carries labels 3 % 5 and 21 % 5
+        let labels: Vec<String> = db
+            .issue(3)
+            .unwrap()
+            .labels
+            .unwrap()
+            .into_iter()
+            .map(|l| l.name)
+            .collect();
+        assert_eq!(labels, ["label-01", "label-03"]);
+
+        let comments: i64 = db
+            .conn()
+            .query_row("SELECT COUNT(*) FROM comments", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(comments, 100);
+    }
+}
@@ -0,0 +1,271 @@
+//! Small parsing helpers shared by the CLI and GUI
+
+use std::path::PathBuf;
+
+use chrono::Duration;
+
+use crate::error::{Error, Result};
+
+/// Parse a relative duration such as `14d`, `2w`, `12h` or `30m`
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let trimmed = s.trim();
+    let invalid = || Error::InvalidDuration(s.to_string());
+
+    let unit_start = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (number, unit) = trimmed.split_at(unit_start);
+    let n: i64 = number.parse().map_err(|_| invalid())?;
+
+    match unit.to_lowercase().as_str() {
+        "m" => Duration::try_minutes(n),
+        "h" => Duration::try_hours(n),
+        "d" => Duration::try_days(n),
+        "w" => Duration::try_weeks(n),
+        _ => None,
+    }
+    .ok_or_else(invalid)
+}
+
+/// Expand a leading `~` and any `$VAR` / `${VAR}` references in a path.
+/// Unknown variables are left as written.
+pub fn expand_path(path: &str) -> PathBuf {
+    let expanded = expand_vars(path);
+
+    if expanded == "~" {
+        if let Some(home) = dirs::home_dir() {
+            return home;
+        }
+    } else if let Some(rest) = expanded
+        .strip_prefix("~/")
+        .or_else(|| expanded.strip_prefix("~\\"))
+    {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+
+    PathBuf::from(expanded)
+}
+
+/// Convert CRLF and lone CR line endings to `\n`
+pub fn normalize_line_endings(s: &str) -> String {
+    s.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Escape `%`, `_` and `\` so `s` matches literally in a `LIKE ... ESCAPE '\'` pattern
+pub fn escape_like(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Lowercase `s` and strip diacritics from Latin letters (`Café` -> `cafe`),
+/// including combining marks left by decomposed input
+pub fn fold_accents(s: &str) -> String {
+    s.chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .map(|c| match c {
+            'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+            'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+            'ď' | 'đ' => 'd',
+            'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+            'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+            'ĥ' | 'ħ' => 'h',
+            'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+            'ĵ' => 'j',
+            'ķ' => 'k',
+            'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+            'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+            'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+            'ŕ' | 'ŗ' | 'ř' => 'r',
+            'ś' | 'ŝ' | 'ş' | 'š' => 's',
+            'ţ' | 'ť' | 'ŧ' => 't',
+            'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+            'ŵ' => 'w',
+            'ý' | 'ÿ' | 'ŷ' => 'y',
+            'ź' | 'ż' | 'ž' => 'z',
+            c => c,
+        })
+        .collect()
+}
+
+/// Edit distance (insertions, deletions, substitutions) between two strings, by character
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            row[j + 1] = substitution.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+
+    prev[b.len()]
+}
+
+/// Maximum edit distance for a "did you mean" suggestion
+pub const SUGGESTION_DISTANCE: usize = 2;
+
+/// The candidate closest to `input` (ignoring case and accents) within
+/// `SUGGESTION_DISTANCE` edits; ties go to the earliest candidate
+pub fn closest_match<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let input = fold_accents(input);
+    candidates
+        .into_iter()
+        .map(|c| (levenshtein(&input, &fold_accents(c)), c))
+        .filter(|(distance, _)| *distance <= SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c)
+}
+
+fn expand_vars(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => out.push_str(&value),
+            _ => out.push_str(&rest[pos..pos + 1 + consumed]),
+        }
+        rest = &after[consumed..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("14d").unwrap(), Duration::days(14));
+        assert_eq!(parse_duration("2W").unwrap(), Duration::weeks(2));
+    }
+
+    #[test]
+    fn fold_accents_strips_diacritics_and_case() {
+        assert_eq!(fold_accents("Café"), "cafe");
+        assert_eq!(fold_accents("NAÏVE"), "naive");
+        assert_eq!(fold_accents("Łódź"), "lodz");
+        // Decomposed e + combining acute
+        assert_eq!(fold_accents("cafe\u{0301}"), "cafe");
+        assert_eq!(fold_accents("plain-label"), "plain-label");
+    }
+
+    #[test]
+    fn normalize_line_endings_handles_crlf_and_cr() {
+        assert_eq!(normalize_line_endings("a\r\nb\rc\n"), "a\nb\nc\n");
+        assert_eq!(normalize_line_endings("plain"), "plain");
+    }
+
+    #[test]
+    fn escape_like_escapes_wildcards() {
+        assert_eq!(escape_like("area/"), "area/");
+        assert_eq!(escape_like("100%_done"), "100\\%\\_done");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+    }
+
+    #[test]
+    fn levenshtein_distances() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("backend", "backend"), 0);
+        assert_eq!(levenshtein("backnd", "backend"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("flaw", "lawn"), 2);
+        assert_eq!(levenshtein("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn closest_match_within_two_edits() {
+        let labels = ["backend", "frontend", "bug"];
+        assert_eq!(closest_match("backnd", labels), Some("backend"));
+        assert_eq!(closest_match("BUGS", labels), Some("bug"));
+        assert_eq!(closest_match("frontendd", labels), Some("frontend"));
+        assert_eq!(closest_match("database", labels), None);
+        assert_eq!(closest_match("x", Vec::<&str>::new()), None);
+    }
+
+    #[test]
+    fn expand_path_tilde() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_path("~"), home);
+        assert_eq!(expand_path("~/sub"), home.join("sub"));
+        assert_eq!(expand_path("~/sub/notes.md"), home.join("sub/notes.md"));
+    }
+
+    #[test]
+    fn expand_path_env_vars() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(expand_path("$HOME/x"), PathBuf::from(format!("{}/x", home)));
+        assert_eq!(
+            expand_path("${HOME}/x"),
+            PathBuf::from(format!("{}/x", home))
+        );
+    }
+
+    #[test]
+    fn expand_path_leaves_other_paths_alone() {
+        assert_eq!(expand_path("notes.md"), PathBuf::from("notes.md"));
+        assert_eq!(expand_path("a~b/c"), PathBuf::from("a~b/c"));
+        assert_eq!(
+            expand_path("$SKIS_SURELY_UNSET_VAR/x"),
+            PathBuf::from("$SKIS_SURELY_UNSET_VAR/x")
+        );
+        assert_eq!(expand_path("cost$"), PathBuf::from("cost$"));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid() {
+        assert!(matches!(
+            parse_duration("14"),
+            Err(Error::InvalidDuration(_))
+        ));
+        assert!(matches!(
+            parse_duration("d"),
+            Err(Error::InvalidDuration(_))
+        ));
+        assert!(matches!(
+            parse_duration("5y"),
+            Err(Error::InvalidDuration(_))
+        ));
+        assert!(matches!(
+            parse_duration("-3d"),
+            Err(Error::InvalidDuration(_))
+        ));
+    }
+}
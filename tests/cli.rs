@@ -2,12 +2,13 @@
 //!
 //! These tests verify command-line behavior using assert_cmd.
 
+use assert_cmd::cargo::cargo_bin_cmd;
 use assert_cmd::Command;
 use predicates::prelude::*;
 use tempfile::TempDir;
 
 fn skis() -> Command {
-    Command::cargo_bin("skis").unwrap()
+    cargo_bin_cmd!("skis")
 }
 
 // Task 1.11: CLI skeleton tests
@@ -343,6 +344,38 @@ fn cli_issue_reopen() {
         .stdout(predicate::str::contains("Reopened issue #1"));
 }
 
+#[test]
+fn cli_issue_reopen_deleted_suggests_restore() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Test"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "close", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "delete", "1", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "reopen", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Issue #1 is deleted"))
+        .stderr(predicate::str::contains("skis issue restore 1"));
+}
+
 #[test]
 fn cli_issue_reopen_already_open_shows_error() {
     let dir = TempDir::new().unwrap();
@@ -604,6 +637,99 @@ fn cli_issue_comment_with_body() {
         .stdout(predicate::str::contains("Added comment"));
 }
 
+#[test]
+fn cli_issue_comment_rejects_empty_body() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Test"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "comment", "1", "--body", "   "])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Comment cannot be empty"));
+}
+
+#[test]
+fn cli_doctor_reports_and_fixes_empty_comments() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Test"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .arg("doctor")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+
+    // Simulate a database written before comments were validated
+    let conn = rusqlite::Connection::open(dir.path().join(".skis/issues.db")).unwrap();
+    conn.execute("INSERT INTO comments (issue_id, body) VALUES (1, '')", []).unwrap();
+    drop(conn);
+
+    skis()
+        .arg("doctor")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Comment #1 on issue #1 is empty"))
+        .stdout(predicate::str::contains("skis doctor --fix"));
+
+    skis()
+        .args(["doctor", "--fix"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted 1 empty comment(s)"));
+
+    skis()
+        .arg("doctor")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+}
+
+#[test]
+fn cli_issue_create_warns_on_label_case_mismatch() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["label", "create", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "create", "--title", "Test", "--label", "Bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "warning: using existing label 'bug' for input 'Bug'",
+        ));
+
+    skis()
+        .args(["issue", "create", "--title", "Test 2", "--label", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
+
 // Phase 2: Task 2.5 - issue view with comments
 
 #[test]
@@ -685,6 +811,32 @@ fn cli_issue_list_search() {
         .stdout(predicate::str::contains("Update docs").not());
 }
 
+#[test]
+fn cli_issue_list_search_in_title() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Login bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "create", "--title", "Update docs", "--body", "Mention login"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list", "--search", "login", "--search-in", "title"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Login bug"))
+        .stdout(predicate::str::contains("Update docs").not());
+}
+
 #[test]
 fn cli_issue_list_search_with_filters() {
     let dir = TempDir::new().unwrap();
@@ -803,6 +955,47 @@ fn cli_issue_view_shows_links() {
         .stdout(predicate::str::contains("#2"));
 }
 
+#[test]
+fn cli_issue_link_with_type() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["Issue 1", "Issue 2"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "link", "2", "1", "--type", "blocks"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "view", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#2 (blocked by)"));
+
+    skis()
+        .args(["issue", "view", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#1 (blocks)"));
+
+    skis()
+        .args(["issue", "link", "1", "2", "--type", "duplicates"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid link type"));
+}
+
 // Phase 3: Label CLI tests
 
 #[test]
@@ -1221,9 +1414,43 @@ fn cli_issue_list_json_valid() {
         assert!(issue["state"].is_string(), "state should be a string");
         assert!(issue["created_at"].is_string(), "created_at should be a timestamp");
         assert!(issue["updated_at"].is_string(), "updated_at should be a timestamp");
+        assert!(issue["labels"].is_array(), "labels should be an array");
     }
 }
 
+#[test]
+fn cli_issue_list_json_includes_labels() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["label", "create", "bug", "--color", "d73a4a"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "--title", "Labeled", "-l", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let output = skis()
+        .args(["issue", "list", "--json"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    let labels = json[0]["labels"].as_array().unwrap();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0]["name"], "bug");
+    assert_eq!(labels[0]["color"], "d73a4a");
+    assert!(labels[0].get("id").is_none(), "label ids are internal");
+}
+
 // 4.3: JSON output for label list
 
 #[test]
@@ -1517,3 +1744,3389 @@ fn full_issue_lifecycle() {
         .success()
         .stdout(predicate::str::contains("Critical bug"));
 }
+
+// Open in GUI: falls back to the terminal view when the GUI is unavailable
+
+#[test]
+fn cli_issue_browse_falls_back_to_terminal() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Browse me"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "browse", "1"])
+        .env("SKIS_GUI", dir.path().join("no-such-gui"))
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Browse me"))
+        .stderr(predicate::str::contains("SKIS GUI not found"));
+}
+
+#[test]
+fn cli_issue_view_open_falls_back_to_terminal() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Open me"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "view", "1", "--open"])
+        .env("SKIS_GUI", dir.path().join("no-such-gui"))
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Open me"))
+        .stderr(predicate::str::contains("warning:"));
+}
+
+#[test]
+fn cli_issue_browse_nonexistent_shows_error() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "browse", "999"])
+        .env("SKIS_GUI", dir.path().join("no-such-gui"))
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Issue #999 not found"));
+}
+
+// Closed-since filter
+
+#[test]
+fn cli_issue_list_closed_since() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["Still open", "Recently closed"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "close", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list", "--closed-since", "14d"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Recently closed"))
+        .stdout(predicate::str::contains("Still open").not());
+}
+
+#[test]
+fn cli_issue_list_closed_since_invalid_duration() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "list", "--closed-since", "soon"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid duration 'soon'"));
+}
+
+#[test]
+fn cli_issue_list_closed_since_rejects_out_of_range_duration() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "list", "--closed-since", "9999999999d"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Invalid duration '9999999999d'"));
+}
+
+#[test]
+fn cli_issue_list_closed_since_conflicts_with_state() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for state in ["open", "all"] {
+        skis()
+            .args(["issue", "list", "--closed-since", "14d", "--state", state])
+            .current_dir(dir.path())
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+}
+
+// Links to deleted issues
+
+#[test]
+fn cli_issue_link_deleted_requires_force() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["Live issue", "Gone issue"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "delete", "2", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "link", "1", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Issue #2 is deleted"));
+
+    skis()
+        .args(["issue", "link", "1", "2", "--force"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "view", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#2 (deleted)"));
+}
+
+// Batch link/unlink
+
+#[test]
+fn cli_issue_link_to_many() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for i in 1..=4 {
+        skis()
+            .args(["issue", "create", "--title", &format!("Issue {}", i)])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "link", "1", "4"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "link", "1", "--to", "2", "3", "4"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Linked #1 to #2, #3; skipped #4 (already linked)",
+        ));
+
+    skis()
+        .args(["issue", "unlink", "1", "--to", "2", "3"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unlinked #1 from #2, #3"));
+}
+
+// Changelog
+
+#[test]
+fn cli_changelog_groups_completed_issues() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for (title, issue_type) in [
+        ("Fix crash", "bug"),
+        ("Dark mode", "request"),
+        ("Rewrite in COBOL", "request"),
+        ("Still open", "task"),
+    ] {
+        skis()
+            .args(["issue", "create", "--title", title, "--type", issue_type])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "close", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "close", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "close", "3", "-r", "not_planned"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["changelog", "--since", "14d"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## Changes since 14d"))
+        .stdout(predicate::str::contains("### Features\n\n- Dark mode (#2)"))
+        .stdout(predicate::str::contains("### Bug fixes\n\n- Fix crash (#1)"))
+        .stdout(predicate::str::contains("COBOL").not())
+        .stdout(predicate::str::contains("Still open").not());
+
+    skis()
+        .args(["changelog", "--since", "14d", "--include-wontfix"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Rewrite in COBOL (#3)"));
+}
+
+#[test]
+fn cli_changelog_invalid_since() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["changelog", "--since", "no-such-tag"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --since 'no-such-tag'"));
+}
+
+#[test]
+fn cli_since_rejects_out_of_range_duration() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for command in ["changelog", "digest", "release-notes"] {
+        skis()
+            .args([command, "--since", "9999999999d"])
+            .current_dir(dir.path())
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("Invalid --since '9999999999d'"));
+    }
+}
+
+#[test]
+fn cli_issue_list_sort_by_activity() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Only issue"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list", "--sort", "activity"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Only issue"));
+}
+
+// Output formats
+
+#[test]
+fn cli_label_list_tsv() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["label", "create", "bug", "-c", "ff0000", "-d", "Something broken"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["label", "create", "docs", "-c", "00ff00"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "--title", "Crash", "-l", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let output = skis()
+        .args(["label", "list", "--format", "tsv"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(
+        rows,
+        vec![
+            vec!["bug", "ff0000", "Something broken", "1", "1"],
+            vec!["docs", "00ff00", "", "0", "0"],
+        ]
+    );
+}
+
+#[test]
+fn cli_issue_list_tsv() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Tab\there", "-T", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list", "--format", "tsv"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\tbug\topen\t\tTab here\n");
+}
+
+#[test]
+fn cli_list_invalid_format_shows_error() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["label", "list", "--format", "csv"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid format 'csv'"));
+}
+
+// Issue references
+
+#[test]
+fn cli_issue_copy_prints_reference() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Login button broken", "-T", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "copy", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("skis#1 — Login button broken [bug, open]\n");
+
+    skis()
+        .args(["issue", "copy", "1", "--markdown"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(skis://issue/1)"));
+}
+
+#[test]
+fn cli_export_canonical_is_stable() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Exported"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "comment", "1", "--body", "Note"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let first = skis()
+        .args(["export", "--canonical"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let second = skis()
+        .args(["export", "--canonical"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(first.status.success());
+    assert_eq!(first.stdout, second.stdout);
+
+    let json: serde_json::Value = serde_json::from_slice(&first.stdout).unwrap();
+    assert_eq!(json["issues"][0]["title"], "Exported");
+    assert_eq!(json["issues"][0]["comments"][0]["body"], "Note");
+    assert!(json.get("exported_at").is_none());
+}
+
+#[test]
+fn cli_issue_list_has_link_and_no_link() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["Parent", "Child", "Loner"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "link", "1", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list", "--has-link"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Parent"))
+        .stdout(predicate::str::contains("Child"))
+        .stdout(predicate::str::contains("Loner").not());
+
+    skis()
+        .args(["issue", "list", "--no-link"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Loner"))
+        .stdout(predicate::str::contains("Parent").not());
+
+    skis()
+        .args(["issue", "list", "--has-link", "--no-link"])
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cli_deinit_removes_skis_directory() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Doomed"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["deinit", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 issue(s) deleted"));
+
+    assert!(!dir.path().join(".skis").exists());
+}
+
+#[test]
+fn cli_deinit_prompts_twice() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .arg("deinit")
+        .current_dir(dir.path())
+        .write_stdin("y\nn\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cancelled"));
+    assert!(dir.path().join(".skis").exists());
+
+    skis()
+        .arg("deinit")
+        .current_dir(dir.path())
+        .write_stdin("y\ny\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("0 issue(s)"));
+    assert!(!dir.path().join(".skis").exists());
+}
+
+#[test]
+fn cli_deinit_refuses_parent_repository() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    let sub = dir.path().join("sub");
+    std::fs::create_dir(&sub).unwrap();
+
+    skis()
+        .args(["deinit", "--yes"])
+        .current_dir(&sub)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not the current directory"));
+
+    assert!(dir.path().join(".skis").exists());
+}
+
+#[test]
+fn cli_issue_list_ids_prints_bare_numbers() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["First", "Second"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "list", "--ids", "--sort", "id", "--order", "asc"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n2\n");
+
+    skis()
+        .args(["issue", "list", "--ids", "--json"])
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cli_issue_close_stdin_closes_each_issue() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["One", "Two", "Three"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "close", "--stdin", "--yes"])
+        .current_dir(dir.path())
+        .write_stdin("1\n#3\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Closed issue #1"))
+        .stdout(predicate::str::contains("Closed issue #3"));
+
+    skis()
+        .args(["issue", "list", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("2\n");
+}
+
+#[test]
+fn cli_issue_list_ids_lists_every_match() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for n in 1..=35 {
+        skis()
+            .args(["issue", "create", "--title", &format!("Issue {}", n)])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    let output = skis()
+        .args(["issue", "list", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(String::from_utf8(output).unwrap().lines().count(), 35);
+
+    skis()
+        .args(["issue", "list", "--ids", "--limit", "2", "--sort", "id"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n2\n");
+}
+
+#[test]
+fn cli_issue_close_stdin_requires_yes() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "close", "--stdin"])
+        .current_dir(dir.path())
+        .write_stdin("1\n")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cli_issue_delete_stdin_reports_failures_and_continues() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Exists"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "delete", "--stdin", "--yes"])
+        .current_dir(dir.path())
+        .write_stdin("99 1")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Deleted issue #1"))
+        .stderr(predicate::str::contains("Issue #99 not found"))
+        .stderr(predicate::str::contains("1 of 2 issues failed"));
+}
+
+#[test]
+fn cli_issue_reopen_stdin_rejects_non_numeric_input() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "reopen", "--stdin"])
+        .current_dir(dir.path())
+        .write_stdin("1 abc")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid issue number 'abc'"));
+}
+
+#[test]
+fn cli_issue_list_count_prints_only_number() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "list", "--count"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("0\n");
+
+    for title in ["Login bug", "Logout bug", "Docs"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "list", "--count", "--limit", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("3\n");
+
+    skis()
+        .args(["issue", "list", "--count", "--search", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("2\n");
+}
+
+#[test]
+fn cli_issue_list_sort_uses_per_field_default_order() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["First", "Second", "Third"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    // Ids read ascending by default
+    skis()
+        .args(["issue", "list", "--ids", "--sort", "id"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n2\n3\n");
+
+    // An explicit --order always wins
+    skis()
+        .args(["issue", "list", "--ids", "--sort", "id", "--order", "desc"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("3\n2\n1\n");
+}
+
+#[test]
+fn cli_issue_comment_pin_surfaces_comment_first() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Long thread"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    for body in ["First thoughts", "The resolution", "Later chatter"] {
+        skis()
+            .args(["issue", "comment", "1", "--body", body])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "comment", "--pin", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pinned comment #2 on issue #1"));
+
+    let output = skis()
+        .args(["issue", "view", "1", "--comments"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let resolution = stdout.find("The resolution").unwrap();
+    assert!(resolution < stdout.find("First thoughts").unwrap());
+    assert!(resolution < stdout.find("Later chatter").unwrap());
+    assert!(stdout.contains("#2 pinned"));
+
+    skis()
+        .args(["issue", "comment", "--unpin", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unpinned comment #2"));
+
+    skis()
+        .args(["issue", "view", "1", "--comments"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pinned").not());
+}
+
+#[test]
+fn cli_issue_comment_pin_unknown_comment_fails() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "comment", "--pin", "42"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Comment #42 not found"));
+}
+
+#[test]
+fn cli_issue_create_applies_configured_title_prefix() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    std::fs::write(
+        dir.path().join(".skis/config.toml"),
+        "title_prefix.bug = \"[BUG] \"\n",
+    )
+    .unwrap();
+
+    for args in [
+        vec!["-t", "Crash on save", "-T", "bug"],
+        vec!["-t", "[BUG] Already prefixed", "-T", "bug"],
+        vec!["-t", "Raw title", "-T", "bug", "--no-prefix"],
+        vec!["-t", "Plain task"],
+    ] {
+        skis()
+            .args(["issue", "create"])
+            .args(args)
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    let output = skis()
+        .args(["issue", "list", "--json", "--sort", "id"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let titles: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        titles,
+        vec![
+            "[BUG] Crash on save",
+            "[BUG] Already prefixed",
+            "Raw title",
+            "Plain task"
+        ]
+    );
+
+    skis()
+        .args(["issue", "list", "--format", "tsv", "--strip-prefixes", "-T", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\tCrash on save\n"))
+        .stdout(predicate::str::contains("[BUG]").not());
+}
+
+#[test]
+fn cli_invalid_config_is_reported() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    std::fs::write(dir.path().join(".skis/config.toml"), "title_prefix = 3\n").unwrap();
+
+    skis()
+        .args(["issue", "create", "-t", "Anything"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid config file"));
+}
+
+#[test]
+fn cli_unknown_label_suggests_near_miss() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["label", "create", "backend"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "create", "-t", "Slow query", "-l", "backnd"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Did you mean 'backend'?"));
+
+    skis()
+        .args(["issue", "create", "-t", "Slow query", "-l", "database"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Did you mean").not());
+}
+
+#[test]
+fn cli_label_find_matches_name_and_description() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for args in [
+        vec!["backend", "-d", "Server side"],
+        vec!["ui", "-d", "Anything the USER sees"],
+        vec!["docs"],
+    ] {
+        skis()
+            .args(["label", "create"])
+            .args(args)
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["label", "find", "user", "--format", "tsv"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("ui\t"))
+        .stdout(predicate::str::contains("backend").not());
+
+    skis()
+        .args(["label", "find", "END"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("backend"))
+        .stdout(predicate::str::contains("docs").not());
+}
+
+/// Run skis in `dir` and parse its stdout as JSON
+fn skis_json(dir: &TempDir, args: &[&str]) -> serde_json::Value {
+    let output = skis()
+        .args(args)
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&output).expect("valid JSON")
+}
+
+#[test]
+fn cli_issue_mutations_print_json() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["label", "create", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let created = skis_json(
+        &dir,
+        &["issue", "create", "-t", "First", "-l", "bug", "--json"],
+    );
+    assert_eq!(created["id"], 1);
+    assert_eq!(created["title"], "First");
+    assert_eq!(created["labels"][0]["name"], "bug");
+    skis_json(&dir, &["issue", "create", "-t", "Second", "--json"]);
+
+    let edited = skis_json(&dir, &["issue", "edit", "1", "-t", "Renamed", "--json"]);
+    assert_eq!(edited["title"], "Renamed");
+
+    let linked = skis_json(&dir, &["issue", "link", "1", "2", "--json"]);
+    assert_eq!(linked["id"], 1);
+    assert_eq!(linked["linked_issues"][0]["id"], 2);
+
+    let unlinked = skis_json(&dir, &["issue", "unlink", "1", "--to", "2", "--json"]);
+    assert_eq!(unlinked["linked_issues"].as_array().unwrap().len(), 0);
+
+    let comment = skis_json(&dir, &["issue", "comment", "1", "-b", "Hi", "--json"]);
+    assert_eq!(comment["issue_id"], 1);
+    assert_eq!(comment["body"], "Hi");
+    let comment_id = comment["id"].to_string();
+    let pinned = skis_json(&dir, &["issue", "comment", "--pin", &comment_id, "--json"]);
+    assert_eq!(pinned["pinned"], true);
+
+    let closed = skis_json(&dir, &["issue", "close", "1", "--json"]);
+    assert_eq!(closed["state"], "closed");
+    assert_eq!(closed["state_reason"], "completed");
+
+    let reopened = skis_json(&dir, &["issue", "reopen", "1", "--json"]);
+    assert_eq!(reopened["state"], "open");
+
+    let deleted = skis_json(&dir, &["issue", "delete", "2", "--yes", "--json"]);
+    assert_eq!(deleted, serde_json::json!({"ok": true, "id": 2}));
+
+    let restored = skis_json(&dir, &["issue", "restore", "2", "--json"]);
+    assert_eq!(restored["id"], 2);
+    assert!(restored["deleted_at"].is_null());
+}
+
+#[test]
+fn cli_issue_stdin_mutation_prints_json_array() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["One", "Two"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    let output = skis()
+        .args(["issue", "close", "--stdin", "--yes", "--json"])
+        .write_stdin("1 2 9")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    let ids: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[test]
+fn cli_issue_list_label_prefix() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for label in ["area/backend", "area/ui", "bug"] {
+        skis()
+            .args(["label", "create", label])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    for (title, label) in [("Server", "area/backend"), ("Button", "area/ui"), ("Crash", "bug")] {
+        skis()
+            .args(["issue", "create", "-t", title, "-l", label])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "list", "--label-prefix", "area/", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n2\n");
+}
+
+#[test]
+fn cli_issue_edit_without_flags_errors_when_not_a_tty() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Untouched"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "edit", "1"])
+        .write_stdin("")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Updated").not())
+        .stderr(predicate::str::contains("Nothing to edit"))
+        .stderr(predicate::str::contains("--title"));
+}
+
+#[test]
+fn cli_issue_edit_with_only_label_flags_updates() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["label", "create", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Labelled"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "edit", "1", "--add-label", "bug"])
+        .write_stdin("")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated issue #1"));
+
+    skis()
+        .args(["issue", "list", "-l", "bug", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n");
+}
+
+#[test]
+fn cli_issue_list_explain_prints_sql_without_running() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    let json = skis_json(
+        &dir,
+        &["issue", "list", "-l", "bug", "-T", "task", "--explain"],
+    );
+    let sql = json["sql"].as_str().unwrap();
+    assert!(sql.contains("i.state = ?1 AND i.type = ?2"));
+    assert!(sql.contains("l.name = ?3"));
+    assert!(sql.ends_with(" LIMIT ?4 OFFSET ?5"));
+    assert_eq!(
+        json["params"],
+        serde_json::json!(["open", "task", "bug", 30, 0])
+    );
+
+    // Hidden from help
+    skis()
+        .args(["issue", "list", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--explain").not());
+}
+
+#[test]
+fn cli_crlf_body_file_is_stored_with_lf() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    let body_file = dir.path().join("body.md");
+    std::fs::write(&body_file, "Steps to reproduce\r\nopen the editor\r\n").unwrap();
+    skis()
+        .args(["issue", "create", "-t", "Windows body", "-F"])
+        .arg(&body_file)
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let json = skis_json(&dir, &["issue", "view", "1", "--json"]);
+    assert_eq!(json["body"], "Steps to reproduce\nopen the editor\n");
+
+    skis()
+        .args(["issue", "view", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains('\r').not());
+
+    // A phrase spanning the original line break still matches
+    skis()
+        .args(["issue", "list", "--search", "\"reproduce open\"", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n");
+}
+
+#[test]
+fn cli_crlf_kept_when_normalization_is_off() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    std::fs::write(
+        dir.path().join(".skis/config.toml"),
+        "[body]\nnormalize_line_endings = false\n",
+    )
+    .unwrap();
+
+    skis()
+        .args(["issue", "create", "-t", "Raw", "-b", "one\r\ntwo"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let json = skis_json(&dir, &["issue", "view", "1", "--json"]);
+    assert_eq!(json["body"], "one\r\ntwo");
+
+    // The text view still renders without stray carriage returns
+    skis()
+        .args(["issue", "view", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("one\ntwo"))
+        .stdout(predicate::str::contains('\r').not());
+}
+
+#[test]
+fn cli_skis_dir_env_overrides_discovery() {
+    let here = TempDir::new().unwrap();
+    let there = TempDir::new().unwrap();
+    for (dir, title) in [(&here, "Local"), (&there, "Remote")] {
+        skis().arg("init").current_dir(dir.path()).assert().success();
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "list"])
+        .env("SKIS_DIR", there.path().join(".skis"))
+        .current_dir(here.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Remote"))
+        .stdout(predicate::str::contains("Local").not());
+
+    // Empty means unset
+    skis()
+        .args(["issue", "list"])
+        .env("SKIS_DIR", "")
+        .current_dir(here.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Local"));
+
+    skis()
+        .args(["issue", "list"])
+        .env("SKIS_DIR", here.path().join("missing"))
+        .current_dir(here.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not a skis repository"));
+}
+
+#[test]
+fn cli_issue_link_note_is_shown() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["Crash on save", "Save fails"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    let json = skis_json(
+        &dir,
+        &["issue", "link", "2", "1", "--note", "same stack trace, see logs", "--json"],
+    );
+    assert_eq!(json["linked_issues"][0]["note"], "same stack trace, see logs");
+
+    let json = skis_json(&dir, &["issue", "view", "1", "--json"]);
+    assert_eq!(json["linked_issues"][0]["id"], 2);
+    assert_eq!(json["linked_issues"][0]["note"], "same stack trace, see logs");
+
+    skis()
+        .args(["issue", "view", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#2 — same stack trace, see logs"));
+}
+
+#[test]
+fn cli_compact_json_is_single_line() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["label", "create", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Compact", "-b", "two\nlines", "-l", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    for args in [
+        vec!["issue", "list", "--json", "--compact"],
+        vec!["issue", "list", "--format", "json", "--compact"],
+        vec!["issue", "view", "1", "--json", "--compact"],
+        vec!["label", "list", "--json", "--compact"],
+    ] {
+        let output = skis()
+            .args(&args)
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let text = String::from_utf8(output).unwrap();
+        assert_eq!(text.lines().count(), 1, "{:?} printed {:?}", args, text);
+        serde_json::from_str::<serde_json::Value>(&text).expect("valid JSON");
+    }
+
+    // Pretty stays the default
+    skis()
+        .args(["issue", "view", "1", "--json"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n  \"id\": 1,\n"));
+}
+
+#[test]
+fn cli_issue_list_unknown_label_errors() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["label", "create", "frontend"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list", "-l", "frontnd"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Label 'frontnd' not found"))
+        .stderr(predicate::str::contains("Did you mean 'frontend'?"));
+
+    skis()
+        .args(["issue", "list", "-l", "FRONTEND"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found"));
+}
+
+#[test]
+fn cli_issue_list_fields_selects_columns_and_keys() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash on save", "-T", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let json = skis_json(&dir, &["issue", "list", "--json", "--fields", "title,id"]);
+    assert_eq!(json, serde_json::json!([{"title": "Crash on save", "id": 1}]));
+
+    skis()
+        .args(["issue", "list", "--format", "tsv", "--fields", "id,type"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\tbug\n");
+
+    skis()
+        .args(["issue", "list", "--fields", "id,updated,title"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPDATED"))
+        .stdout(predicate::str::contains("just now"))
+        .stdout(predicate::str::contains("TYPE").not());
+}
+
+#[test]
+fn cli_issue_list_fields_rejects_unknown() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "list", "--fields", "id,assignee"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid field 'assignee'"));
+}
+
+#[test]
+fn cli_reindex_restores_search() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash on save"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    // Empty the index behind SKIS's back
+    let conn = rusqlite::Connection::open(dir.path().join(".skis/issues.db")).unwrap();
+    conn.execute("INSERT INTO issues_fts(issues_fts) VALUES('delete-all')", [])
+        .unwrap();
+    drop(conn);
+    skis()
+        .args(["issue", "list", "--search", "crash"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found"));
+
+    skis()
+        .arg("reindex")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Rebuilt the search index\n");
+    skis()
+        .args(["issue", "list", "--search", "crash"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Crash on save"));
+}
+
+#[test]
+fn cli_issue_list_commented_filters() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["Answered", "Ignored"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "comment", "1", "-b", "On it"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let ids = |flag: &str| -> Vec<i64> {
+        skis_json(&dir, &["issue", "list", "--json", flag])
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["id"].as_i64().unwrap())
+            .collect()
+    };
+    assert_eq!(ids("--commented"), [1]);
+    assert_eq!(ids("--uncommented"), [2]);
+
+    skis()
+        .args(["issue", "list", "--commented", "--uncommented"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn cli_issue_points_and_status() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash", "-T", "bug", "--points", "3"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Refactor", "--points", "5"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Docs"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "view", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Points: 3"));
+
+    let status = skis_json(&dir, &["status", "--json"]);
+    assert_eq!(status["points"], 8);
+    assert_eq!(status["issues"], 3);
+    assert_eq!(status["unestimated"], 1);
+
+    skis()
+        .args(["issue", "edit", "2", "--clear-points"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let issue = skis_json(&dir, &["issue", "view", "2", "--json"]);
+    assert!(issue.get("points").is_none());
+
+    let listed = skis_json(&dir, &["issue", "list", "--json", "--min-points", "1"]);
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["points"], 3);
+
+    skis()
+        .arg("status")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("total"));
+}
+
+#[test]
+fn cli_issue_list_invalid_filter_messages() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for (args, message) in [
+        (
+            &["--state", "shut"][..],
+            "error: invalid state 'shut', must be open, closed, or all\n",
+        ),
+        (
+            &["--sort", "name"][..],
+            "error: invalid sort field 'name', must be updated, created, closed, activity, comments, id, or title\n",
+        ),
+        (
+            &["--order", "UP"][..],
+            "error: invalid sort order 'up', must be asc or desc\n",
+        ),
+        (
+            &["--search", "x", "--search-in", "labels"][..],
+            "error: invalid search scope 'labels', must be title, body, or all\n",
+        ),
+    ] {
+        skis()
+            .args(["issue", "list"])
+            .args(args)
+            .current_dir(dir.path())
+            .assert()
+            .code(1)
+            .stderr(message);
+    }
+}
+
+#[test]
+fn cli_issue_list_table_aligns_wide_and_combining_titles() {
+    use unicode_width::UnicodeWidthStr;
+
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    let titles = [
+        "Crash on save 🚀🚀🚀 when the disk is full",
+        "日本語のタイトルがとても長い場合の表示について",
+        "Cafe\u{301} menu e\u{301}e\u{301}e\u{301} renders with combining accents throughout",
+        "Short",
+    ];
+    for title in titles {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    let output = skis()
+        .args(["issue", "list", "--sort", "id", "--order", "asc"])
+        .env("COLUMNS", "60")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<&str> = stdout.lines().skip(2).collect();
+    assert_eq!(rows.len(), 4);
+    for row in &rows {
+        assert!(row.width() <= 60, "row too wide: {row:?}");
+    }
+    assert!(rows[0].ends_with('…'));
+    assert!(rows[3].ends_with("Short"));
+
+    // A fixed-width title column keeps the next column lined up
+    let output = skis()
+        .args(["issue", "list", "--fields", "title,id", "--sort", "id", "--order", "asc"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    for (i, row) in stdout.lines().skip(2).enumerate() {
+        let id = format!("#{}", i + 1);
+        assert!(row.ends_with(&id), "{row:?}");
+        assert_eq!(row.width(), 41 + id.len(), "{row:?}");
+    }
+}
+
+#[test]
+fn cli_issue_list_time_format_epoch() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash on save"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let rfc3339 = skis_json(&dir, &["issue", "list", "--json"]);
+    let epoch = skis_json(&dir, &["issue", "list", "--json", "--time-format", "epoch"]);
+    for key in ["created_at", "updated_at"] {
+        let parsed = chrono::DateTime::parse_from_rfc3339(rfc3339[0][key].as_str().unwrap())
+            .unwrap();
+        assert_eq!(epoch[0][key].as_i64().unwrap(), parsed.timestamp());
+    }
+    assert!(epoch[0]["closed_at"].is_null());
+
+    let projected = skis_json(
+        &dir,
+        &["issue", "list", "--json", "--fields", "id,updated", "--time-format", "epoch"],
+    );
+    assert!(projected[0]["updated_at"].is_i64());
+
+    skis()
+        .args(["issue", "list", "--json", "--time-format", "unix"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("must be rfc3339 or epoch"));
+}
+
+#[test]
+fn cli_issue_view_marks_edited_comments() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash on save"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    for body in ["First look", "Second look"] {
+        skis()
+            .args(["issue", "comment", "1", "--body", body])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    // Edit the first comment after it was posted
+    let conn = rusqlite::Connection::open(dir.path().join(".skis/issues.db")).unwrap();
+    conn.execute(
+        "UPDATE comments SET body = 'First look, revised',
+                             updated_at = datetime(created_at, '+5 minutes')
+         WHERE id = 1",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    let output = skis()
+        .args(["issue", "view", "1", "--comments"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let headers: Vec<&str> = stdout.lines().filter(|l| l.contains(" · #")).collect();
+    assert_eq!(headers.len(), 2);
+    assert!(headers[0].ends_with("#1 (edited)"), "{headers:?}");
+    assert!(headers[1].ends_with("#2"), "{headers:?}");
+}
+
+#[test]
+fn cli_digest_writes_text_and_html() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["Crash on save", "Dark mode"] {
+        skis()
+            .args(["issue", "create", "-t", title, "-T", "bug"])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "close", "2", "--reason", "not_planned"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .arg("digest")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("New issues (2)\n  #1 [bug] Crash on save\n"))
+        .stdout(predicate::str::contains("  Not planned\n    #2 [bug] Dark mode\n"));
+
+    skis()
+        .args(["digest", "--format", "html", "--output", "digest.html"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Wrote digest to digest.html\n");
+    let html = std::fs::read_to_string(dir.path().join("digest.html")).unwrap();
+    assert!(html.starts_with("<div style="));
+    assert!(html.contains("Crash on save</li>"));
+
+    skis()
+        .args(["digest", "--format", "pdf"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("must be text or html"));
+}
+
+#[test]
+fn cli_issue_next_picks_unblocked_issue() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for (title, issue_type) in [
+        ("Write docs", "task"),
+        ("Crash on save", "bug"),
+        ("Data loss", "bug"),
+    ] {
+        skis()
+            .args(["issue", "create", "-t", title, "-T", issue_type])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "link", "2", "3", "--type", "blocked-by"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "next"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("#3 Data loss\n"));
+    let json = skis_json(&dir, &["issue", "next", "-T", "task", "--json"]);
+    assert_eq!(json["id"], 1);
+
+    for number in ["1", "3"] {
+        skis()
+            .args(["issue", "close", number])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    let json = skis_json(&dir, &["issue", "next", "--json"]);
+    assert_eq!(json["id"], 2);
+
+    skis()
+        .args(["issue", "close", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "next"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Nothing to do 🎉\n");
+}
+
+#[test]
+fn cli_issue_list_random_keeps_the_matches() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for n in 1..=6 {
+        skis()
+            .args(["issue", "create", "-t", &format!("Issue {n}")])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    let json = skis_json(&dir, &["issue", "list", "--random", "--json"]);
+    let mut ids: Vec<i64> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["id"].as_i64().unwrap())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, [1, 2, 3, 4, 5, 6]);
+
+    let json = skis_json(&dir, &["issue", "list", "--random", "-L", "2", "--json"]);
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    skis()
+        .args(["issue", "list", "--random", "--sort", "id"])
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cli_body_dash_reads_stdin() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "-t", "Piped", "--body", "-"])
+        .current_dir(dir.path())
+        .write_stdin("Body from a pipe\n")
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "comment", "1", "--body", "-"])
+        .current_dir(dir.path())
+        .write_stdin("Comment from a pipe")
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "edit", "1", "-b", "-"])
+        .current_dir(dir.path())
+        .write_stdin("Edited from a pipe")
+        .assert()
+        .success();
+
+    let json = skis_json(&dir, &["issue", "view", "1", "--json"]);
+    assert_eq!(json["body"], "Edited from a pipe");
+    skis()
+        .args(["issue", "view", "1", "--comments"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Comment from a pipe"));
+
+    skis()
+        .args(["issue", "comment", "1", "--body", "-", "--body-file", "-"])
+        .current_dir(dir.path())
+        .write_stdin("Twice")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("both read stdin"));
+}
+
+#[test]
+fn cli_issue_list_limit_is_capped_by_config() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["One", "Two", "Three"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    std::fs::write(dir.path().join(".skis/config.toml"), "max_list_limit = 2\n").unwrap();
+
+    let output = skis()
+        .args(["issue", "list", "--limit", "5", "--json"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "warning: --limit 5 is above max_list_limit (2); showing at most 2",
+        ))
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    // Within the cap: no warning
+    skis()
+        .args(["issue", "list", "--limit", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stderr("");
+}
+
+#[test]
+fn cli_init_force_recreates_empty_database() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join(".skis")).unwrap();
+    std::fs::write(dir.path().join(".skis/issues.db"), "").unwrap();
+
+    skis()
+        .args(["issue", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("skis init --force"));
+    skis()
+        .arg("init")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing or empty"));
+
+    skis()
+        .args(["init", "--force"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Moved broken database to"));
+    assert!(dir.path().join(".skis/issues.db.broken").exists());
+    skis()
+        .args(["issue", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["init", "--force"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Already initialized"));
+}
+
+#[test]
+fn cli_comment_on_deleted_issue_needs_force() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Gone"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "delete", "1", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "comment", "1", "-b", "Hello"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("deleted"));
+    skis()
+        .args(["issue", "comment", "1", "-b", "Restoring this", "--force"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "view", "1", "--comments"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("This issue is deleted"))
+        .stdout(predicate::str::contains("Restoring this"));
+}
+
+#[test]
+fn cli_list_deleted_marks_deleted_issues_with_reason() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["Keep me", "Buy cheap watches"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "delete", "2", "--yes", "--reason", "spam"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let output = skis()
+        .args(["issue", "list", "--deleted"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(stdout.contains("DELETED"));
+    let row = |id: &str| stdout.lines().find(|l| l.starts_with(id)).unwrap().to_string();
+    assert!(row("#2").contains("deleted"));
+    assert!(!row("#1").contains("deleted"));
+
+    // Without --deleted the column isn't shown
+    skis()
+        .args(["issue", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DELETED").not());
+
+    let json = skis_json(&dir, &["issue", "list", "--deleted", "--json"]);
+    let issues = json.as_array().unwrap();
+    let deleted = issues.iter().find(|i| i["id"] == 2).unwrap();
+    assert!(deleted["deleted_at"].is_string());
+    assert_eq!(deleted["deleted_reason"], "spam");
+    let kept = issues.iter().find(|i| i["id"] == 1).unwrap();
+    assert!(kept["deleted_at"].is_null());
+    assert!(kept.get("deleted_reason").is_none());
+
+    skis()
+        .args(["issue", "view", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("This issue is deleted (spam)"));
+
+    skis()
+        .args(["issue", "restore", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let json = skis_json(&dir, &["issue", "view", "2", "--json"]);
+    assert!(json.get("deleted_reason").is_none());
+}
+
+#[test]
+fn cli_label_list_sorts_by_usage() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for name in ["alpha", "beta", "gamma"] {
+        skis()
+            .args(["label", "create", name])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    for labels in [["-l", "gamma"], ["-l", "gamma"], ["-l", "beta"]] {
+        skis()
+            .args(["issue", "create", "-t", "Issue"])
+            .args(labels)
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    let json = skis_json(&dir, &["label", "list", "--sort", "usage", "--json"]);
+    let names: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["gamma", "beta", "alpha"]);
+    assert_eq!(json[0]["total_count"], 2);
+
+    skis()
+        .args(["label", "list", "--sort", "color"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid label sort 'color'"));
+}
+
+#[test]
+fn cli_label_prune_deletes_unused_labels() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for name in ["bug", "spam", "stale"] {
+        skis()
+            .args(["label", "create", name])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    for label in ["bug", "spam"] {
+        skis()
+            .args(["issue", "create", "-t", "Issue", "-l", label])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "delete", "2", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let json = skis_json(
+        &dir,
+        &["label", "prune", "--dry-run", "--include-deleted", "--json"],
+    );
+    assert_eq!(json, serde_json::json!({"dry_run": true, "pruned": ["stale"]}));
+
+    skis()
+        .args(["label", "prune", "--dry-run"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would delete 2 unused label(s):"));
+    assert_eq!(skis_json(&dir, &["label", "list", "--json"]).as_array().unwrap().len(), 3);
+
+    let json = skis_json(&dir, &["label", "prune", "--yes", "--json"]);
+    assert_eq!(
+        json,
+        serde_json::json!({"dry_run": false, "pruned": ["spam", "stale"]})
+    );
+    let json = skis_json(&dir, &["label", "list", "--json"]);
+    assert_eq!(json.as_array().unwrap().len(), 1);
+
+    skis()
+        .args(["label", "prune", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("No unused labels\n");
+}
+
+#[test]
+fn cli_issue_list_exclude_label() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for name in ["bug", "wontfix"] {
+        skis()
+            .args(["label", "create", name])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "create", "-t", "Crash", "-l", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Quirk", "-l", "bug", "-l", "wontfix"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Docs"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list", "--exclude-label", "WONTFIX", "--sort", "id", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n3\n");
+    skis()
+        .args(["issue", "list", "-l", "bug", "--exclude-label", "wontfix", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n");
+    skis()
+        .args(["issue", "list", "--exclude-label", "wontfx"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("wontfx"));
+}
+
+#[test]
+fn cli_issue_retype_and_stats_types() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["One", "Two", "Three"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "edit", "2", "-T", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "retype", "1", "2", "3", "BUG"])
+        .current_dir(dir.path())
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(
+            "Changed #1 type: task → bug\n\
+             Issue #2 already has type bug\n\
+             Changed #3 type: task → bug\n",
+        );
+
+    // Unknown type or issue: nothing changes
+    skis()
+        .args(["issue", "retype", "1", "story"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("story"));
+    skis()
+        .args(["issue", "retype", "1", "42", "epic"])
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+
+    skis()
+        .args(["issue", "close", "3"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let json = skis_json(&dir, &["stats", "types", "--json"]);
+    assert_eq!(
+        json,
+        serde_json::json!([{"type": "bug", "open": 2, "closed": 1}])
+    );
+    skis()
+        .args(["stats", "types"])
+        .current_dir(dir.path())
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bug           2      1      3"));
+}
+
+#[test]
+fn cli_issue_view_comment_header_shows_age_and_id() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "comment", "1", "-b", "Seen it too"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "view", "1", "--comments"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("just now · #1\nSeen it too\n"));
+}
+
+#[test]
+fn cli_issue_set_type() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "set-type", "1", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Changed #1 type: task → bug\n");
+    let json = skis_json(&dir, &["issue", "view", "1", "--json"]);
+    assert_eq!(json["type"], "bug");
+
+    skis()
+        .args(["issue", "set-type", "1", "feature"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid issue type 'feature'"));
+}
+
+#[test]
+fn cli_skis_db_env_opens_database_file() {
+    let data = TempDir::new().unwrap();
+    let elsewhere = TempDir::new().unwrap();
+    let db_path = data.path().join("ci.db");
+
+    skis()
+        .arg("init")
+        .env("SKIS_DB", &db_path)
+        .current_dir(elsewhere.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Initialized empty SKIS database at"));
+    assert!(db_path.is_file());
+    assert!(!elsewhere.path().join(".skis").exists());
+
+    skis()
+        .args(["issue", "create", "-t", "From CI"])
+        .env("SKIS_DB", &db_path)
+        .current_dir(elsewhere.path())
+        .assert()
+        .success();
+
+    // Takes precedence over SKIS_DIR and discovery
+    let other = TempDir::new().unwrap();
+    skis().arg("init").current_dir(other.path()).assert().success();
+    skis()
+        .args(["issue", "list"])
+        .env("SKIS_DB", &db_path)
+        .env("SKIS_DIR", other.path().join(".skis"))
+        .current_dir(other.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("From CI"));
+
+    skis()
+        .args(["issue", "list"])
+        .env("SKIS_DB", data.path().join("missing.db"))
+        .current_dir(other.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not a skis repository"));
+}
+
+
+#[test]
+fn cli_release_notes_by_label() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["label", "create", "release/1.4"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    for (title, issue_type, label) in [
+        ("Fix crash", "bug", Some("release/1.4")),
+        ("Dark mode", "request", Some("release/1.4")),
+        ("Faster sync", "task", Some("release/1.4")),
+        ("Unreleased fix", "bug", None),
+    ] {
+        let mut cmd = skis();
+        cmd.args(["issue", "create", "-t", title, "-T", issue_type]);
+        if let Some(label) = label {
+            cmd.args(["--label", label]);
+        }
+        cmd.current_dir(dir.path()).assert().success();
+    }
+    for number in ["1", "2", "4"] {
+        skis()
+            .args(["issue", "close", number])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["release-notes", "--label", "release/1.4", "--format", "markdown"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            "## Release notes: release/1.4\n\
+             \n2 issues closed: 1 bug fix, 1 feature.\n\
+             \n### Bug fixes\n\n- Fix crash (#1)\n\
+             \n### Features\n\n- Dark mode (#2)\n",
+        );
+
+    skis()
+        .args(["release-notes", "--since", "14d", "--output", "NOTES.md"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Wrote release notes to NOTES.md\n");
+    let notes = std::fs::read_to_string(dir.path().join("NOTES.md")).unwrap();
+    assert!(notes.contains("3 issues closed: 2 bug fixes, 1 feature."));
+    assert!(notes.contains("- Unreleased fix (#4)"));
+
+    skis()
+        .arg("release-notes")
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+    skis()
+        .args(["release-notes", "--label", "release/1.4", "--format", "html"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("must be markdown"));
+}
+
+#[cfg(feature = "sqlcipher")]
+#[test]
+fn cli_encrypted_repository_needs_key() {
+    let dir = TempDir::new().unwrap();
+    skis()
+        .arg("init")
+        .env("SKIS_KEY", "s3cret")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Invoice for ACME"])
+        .env("SKIS_KEY", "s3cret")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Wrong or missing key"));
+
+    std::fs::write(dir.path().join("key.txt"), "s3cret\n").unwrap();
+    skis()
+        .args(["issue", "list", "--key-file", "key.txt"])
+        .env("SKIS_KEY", "wrong")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Invoice for ACME"));
+}
+
+#[test]
+fn cli_issue_create_editor_starts_from_type_template() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    // `true` leaves the buffer as it was written, i.e. the template
+    skis()
+        .args(["issue", "create", "-t", "Crash", "-T", "bug", "--editor"])
+        .env("EDITOR", "true")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let bug = skis_json(&dir, &["issue", "view", "1", "--json"]);
+    assert!(bug["body"]
+        .as_str()
+        .unwrap()
+        .starts_with("## Steps to reproduce"));
+
+    let templates = dir.path().join(".skis/templates");
+    std::fs::create_dir(&templates).unwrap();
+    std::fs::write(templates.join("bug.md"), "Version:\n").unwrap();
+    skis()
+        .args(["issue", "create", "-t", "Hang", "-T", "bug", "--editor"])
+        .env("EDITOR", "true")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        skis_json(&dir, &["issue", "view", "2", "--json"])["body"],
+        "Version:"
+    );
+
+    skis()
+        .args(["issue", "create", "-t", "Leak", "-T", "bug", "--editor"])
+        .args(["--body", "Given"])
+        .env("EDITOR", "false")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        skis_json(&dir, &["issue", "view", "3", "--json"])["body"],
+        "Given"
+    );
+}
+
+#[test]
+fn cli_issue_close_reopen_delete_accept_several_numbers() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["One", "Two", "Three"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "close", "1", "2", "3"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            "Closed issue #1 as completed\nClosed issue #2 as completed\n\
+             Closed issue #3 as completed\nClosed 3 issues\n",
+        );
+    skis()
+        .args(["issue", "reopen", "1", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Reopened issue #1\nReopened issue #2\nReopened 2 issues\n");
+    // A single number behaves as before: no summary, object not array
+    skis()
+        .args(["issue", "reopen", "3"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Reopened issue #3\n");
+    let closed = skis_json(&dir, &["issue", "close", "1", "2", "--json"]);
+    assert_eq!(closed.as_array().unwrap().len(), 2);
+
+    skis()
+        .args(["issue", "delete", "2", "3"])
+        .current_dir(dir.path())
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Delete issues #2, #3? [y/N]"))
+        .stdout("Cancelled\n");
+    skis()
+        .args(["issue", "delete", "2", "99", "3", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stdout("Deleted issue #2\nDeleted issue #3\n")
+        .stderr(predicate::str::contains("Issue #99 not found"))
+        .stderr(predicate::str::contains("1 of 3 issues failed"));
+
+    skis()
+        .args(["issue", "close", "1", "--stdin", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cli_issue_archive_hides_until_unarchived() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["Active", "Parked"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "archive", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Archived issue #2\n");
+    skis()
+        .args(["issue", "list", "--state", "all"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Parked").not());
+    skis()
+        .args(["issue", "list", "--archived"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Parked"));
+    skis()
+        .args(["issue", "view", "2"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("This issue is archived."));
+    let view = skis_json(&dir, &["issue", "view", "2", "--json"]);
+    assert!(view["archived_at"].is_string());
+
+    skis()
+        .args(["issue", "archive", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Issue #2 is already archived"));
+    skis()
+        .args(["issue", "unarchive", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Unarchived issue #2\n");
+    skis()
+        .args(["issue", "unarchive", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Issue #1 is not archived"));
+}
+
+#[test]
+fn cli_list_marks_deleted_rows_and_only_deleted() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["Keep me", "Buy cheap watches"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "delete", "2", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let list = |args: &[&str]| {
+        let output = skis()
+            .args(["issue", "list"])
+            .args(args)
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    let stdout = list(&["--deleted", "--color", "never"]);
+    assert!(!stdout.contains('\x1b'));
+    let row = |id: &str| {
+        stdout
+            .lines()
+            .find(|l| l.starts_with(id))
+            .unwrap()
+            .to_string()
+    };
+    assert!(row("#2").ends_with("Buy cheap watches [deleted]"));
+    assert!(!row("#1").contains("[deleted]"));
+
+    // With colors, the deleted row is dimmed as a whole
+    let colored = list(&["--deleted", "--color", "always"]);
+    let deleted_row = colored.lines().find(|l| l.contains("Buy cheap")).unwrap();
+    assert!(deleted_row.starts_with("\x1b[2m"));
+    let kept_row = colored.lines().find(|l| l.contains("Keep me")).unwrap();
+    assert!(!kept_row.starts_with("\x1b[2m"));
+
+    let only = list(&["--only-deleted", "--color", "never"]);
+    assert!(only.contains("DELETED"));
+    assert!(only.contains("#2"));
+    assert!(!only.contains("Keep me"));
+
+    skis()
+        .args(["issue", "list", "--color", "sometimes"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("must be auto, always, or never"));
+}
+
+// Golden JSON: the documented `issue view --json` schema, key order included
+
+/// Replace every non-null `*_at` value in pretty-printed JSON with a placeholder
+fn normalize_timestamps(json: &str) -> String {
+    json.lines()
+        .map(|line| {
+            let Some((key, value)) = line.split_once(": ") else {
+                return line.to_string();
+            };
+            if key.trim_start().ends_with("_at\"") && value.starts_with('"') {
+                let comma = if value.ends_with(',') { "," } else { "" };
+                format!("{key}: \"<timestamp>\"{comma}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn cli_issue_view_json_matches_golden_file() {
+    let dir = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        skis()
+            .args(args)
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone()
+    };
+    run(&["init"]);
+    run(&[
+        "label",
+        "create",
+        "bug",
+        "--color",
+        "d73a4a",
+        "--description",
+        "Something is broken",
+    ]);
+    run(&[
+        "issue",
+        "create",
+        "-t",
+        "Crash on start",
+        "-b",
+        "Steps to reproduce",
+        "--type",
+        "bug",
+        "-l",
+        "bug",
+        "--points",
+        "3",
+    ]);
+    run(&["issue", "create", "-t", "Add retry"]);
+    run(&["issue", "link", "1", "2"]);
+    run(&["issue", "close", "1"]);
+
+    let stdout = String::from_utf8(run(&["issue", "view", "1", "--json"])).unwrap();
+    let actual = normalize_timestamps(stdout.trim_end());
+    let golden = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/issue_view.json");
+    if std::env::var_os("SKIS_UPDATE_GOLDEN").is_some() {
+        std::fs::write(golden, format!("{actual}\n")).unwrap();
+    }
+    let expected = std::fs::read_to_string(golden).unwrap();
+    assert_eq!(
+        actual,
+        expected.trim_end(),
+        "issue view --json changed; if intended, rerun with SKIS_UPDATE_GOLDEN=1"
+    );
+}
+
+#[test]
+fn cli_list_caps_labels_in_table_only() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    let mut create = vec!["issue", "create", "-t", "Many labels"];
+    for label in ["api", "cli", "db", "docs", "perf", "ui"] {
+        skis()
+            .args(["label", "create", label])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+        create.extend(["-l", label]);
+    }
+    skis()
+        .args(&create)
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "list"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("api,cli,db +3 more"));
+    skis()
+        .args(["issue", "list", "--max-labels", "1"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("api +5 more"));
+    skis()
+        .args(["issue", "list", "--format", "tsv", "--max-labels", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("api,cli,db,docs,perf,ui"));
+    let json = skis_json(&dir, &["issue", "list", "--json", "--max-labels", "1"]);
+    assert_eq!(json[0]["labels"].as_array().unwrap().len(), 6);
+}
+
+#[test]
+fn cli_issue_get_prints_one_field() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for label in ["bug", "ui"] {
+        skis()
+            .args(["label", "create", label])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "create", "-t", "Crash on save", "-T", "bug"])
+        .args(["-l", "bug", "-l", "ui"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let get = |field: &str| {
+        let output = skis()
+            .args(["issue", "get", "1", "--field", field])
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+    assert_eq!(get("title"), "Crash on save\n");
+    assert_eq!(get("type"), "bug\n");
+    assert_eq!(get("state"), "open\n");
+    assert_eq!(get("labels"), "bug\nui\n");
+    assert_eq!(get("body"), "\n");
+    assert_eq!(get("closed_at"), "\n");
+    assert!(get("created_at").ends_with("Z\n"));
+
+    skis()
+        .args(["issue", "get", "1", "--field", "assignee"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("must be one of id, title"));
+    skis()
+        .args(["issue", "get", "99", "--field", "title"])
+        .current_dir(dir.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("not found"));
+}
+
+#[test]
+fn cli_comma_separated_labels_match_repeated_flags() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for label in ["bug", "ui", "urgent"] {
+        skis()
+            .args(["label", "create", label])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "create", "-t", "Repeated", "-l", "bug", "-l", "ui"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Comma", "-l", "bug, ui"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let labels = |number: &str| {
+        let view = skis_json(&dir, &["issue", "view", number, "--json"]);
+        view["labels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|l| l["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(labels("1"), ["bug", "ui"]);
+    assert_eq!(labels("2"), ["bug", "ui"]);
+
+    skis()
+        .args(["issue", "edit", "2", "--add-label", "urgent,", "--remove-label", "bug,ui"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    assert_eq!(labels("2"), ["urgent"]);
+}
+
+#[test]
+fn cli_list_sort_title_is_alphabetical() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["banana", "Cherry", "apple"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    let titles = |args: &[&str]| {
+        let json = skis_json(&dir, args);
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["title"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        titles(&["issue", "list", "--sort", "title", "--json"]),
+        ["apple", "banana", "Cherry"]
+    );
+    assert_eq!(
+        titles(&["issue", "list", "--sort", "title", "--order", "desc", "--json"]),
+        ["Cherry", "banana", "apple"]
+    );
+}
+
+#[test]
+fn cli_label_rename_bulk() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for label in ["P1", "P2", "bug"] {
+        skis()
+            .args(["label", "create", label])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "create", "-t", "Urgent", "-l", "P1"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let rename = [
+        "label",
+        "rename-bulk",
+        "--from-regex",
+        r"^P(\d)$",
+        "--to",
+        "prio/p$1",
+    ];
+
+    skis()
+        .args(rename)
+        .arg("--dry-run")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would rename 2 label(s):"))
+        .stdout(predicate::str::contains("P1 → prio/p1"));
+    let json = skis_json(&dir, &["label", "list", "--json"]);
+    assert_eq!(json[0]["name"], "bug");
+    assert_eq!(json[1]["name"], "P1");
+
+    skis()
+        .args([
+            "label",
+            "rename-bulk",
+            "--from-regex",
+            r"^P\d$",
+            "--to",
+            "bug",
+            "--yes",
+        ])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("P1, P2 -> 'bug': same new name"));
+
+    let json = skis_json(&dir, &[&rename[..], &["--yes", "--json"]].concat());
+    assert_eq!(json["dry_run"], false);
+    assert_eq!(json["renamed"][1]["to"], "prio/p2");
+    let view = skis_json(&dir, &["issue", "view", "1", "--json"]);
+    assert_eq!(view["labels"][0]["name"], "prio/p1");
+
+    skis()
+        .args(rename)
+        .arg("--yes")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r"No labels match '^P(\d)$'"));
+    skis()
+        .args(["label", "rename-bulk", "--from-regex", "^P(", "--to", "x"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid regex '^P('"));
+}
+
+#[test]
+fn cli_list_sizes_id_column_to_widest_number() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Small"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    // Continue numbering from a seven-digit id
+    let conn = rusqlite::Connection::open(dir.path().join(".skis/issues.db")).unwrap();
+    conn.execute(
+        "UPDATE sqlite_sequence SET seq = 1234566 WHERE name = 'issues'",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+    skis()
+        .args(["issue", "create", "-t", "Large"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let output = skis()
+        .args(["issue", "list", "--sort", "id"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("ID       TYPE"), "{}", lines[0]);
+    assert!(lines[2].starts_with("#1       task"), "{}", lines[2]);
+    assert!(lines[3].starts_with("#1234567 task"), "{}", lines[3]);
+    let title = |line: &str| line.find("Small").or_else(|| line.find("Large"));
+    assert_eq!(title(lines[2]), title(lines[3]));
+
+    skis()
+        .args(["issue", "list", "--sort", "id", "-L", "1"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("ID TYPE"));
+}
+
+// Fixtures
+
+#[test]
+fn cli_fixtures_generate_is_deterministic() {
+    let generate = |seed: &str| {
+        let dir = TempDir::new().unwrap();
+        skis().arg("init").current_dir(dir.path()).assert().success();
+        skis()
+            .args(["fixtures", "generate", "--issues", "40", "--seed", seed])
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Generated 40 issues"));
+        skis_json(&dir, &["export", "--canonical"])
+    };
+    let first = generate("42");
+    assert_eq!(first["issues"].as_array().unwrap().len(), 40);
+    assert_eq!(first, generate("42"));
+    assert_ne!(first, generate("7"));
+}
+
+#[test]
+fn cli_fixtures_generate_refuses_non_empty_repo() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Real work"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["fixtures", "generate", "--issues", "5"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Pass --force"));
+    skis()
+        .args(["fixtures", "generate", "--issues", "5", "--force"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let json = skis_json(&dir, &["issue", "list", "--state", "all", "--json"]);
+    assert_eq!(json.as_array().unwrap().len(), 6);
+}
+
+#[test]
+fn cli_issue_body_prints_only_the_body() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash on save", "-b", "Steps:\n1. Save"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "No body"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "body", "1"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("Steps:\n1. Save\n");
+    skis()
+        .args(["issue", "body", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("");
+
+    let json = skis_json(&dir, &["issue", "body", "1", "--json"]);
+    assert_eq!(json["id"], 1);
+    assert_eq!(json["body"], "Steps:\n1. Save");
+    let json = skis_json(&dir, &["issue", "body", "2", "--json"]);
+    assert!(json["body"].is_null());
+
+    skis()
+        .args(["issue", "body", "99"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("not found"));
+}
+
+#[test]
+fn cli_issue_view_resolves_references() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Login button broken"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "-t", "Crash on save"])
+        .args(["-b", "See #1, not #99.\n```\n#1 in code\n```"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "view", "2"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\nReferences: #1 Login button broken (open)\n",
+        ));
+    skis()
+        .args(["issue", "view", "1"])
+        .env("NO_COLOR", "1")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("References").not());
+
+    skis()
+        .args(["issue", "view", "2", "--markdown"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "See [#1](skis://issue/1), not #99.\n```\n#1 in code\n```\n",
+        ))
+        .stdout(predicate::str::contains(
+            "References: [#1](skis://issue/1) Login button broken (open)\n",
+        ));
+}
+
+#[test]
+fn cli_issue_list_state_defaults_from_config() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    for title in ["Open one", "Closed one"] {
+        skis()
+            .args(["issue", "create", "-t", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "close", "2"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    let listed = |args: &[&str]| -> Vec<i64> {
+        let mut all = vec!["issue", "list", "--json"];
+        all.extend_from_slice(args);
+        let json = skis_json(&dir, &all);
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|issue| issue["id"].as_i64().unwrap())
+            .collect()
+    };
+
+    // Built-in default, then config, then the flag
+    assert_eq!(listed(&[]), vec![1]);
+    std::fs::write(
+        dir.path().join(".skis/config.toml"),
+        "[list]\ndefault_state = \"all\"\n",
+    )
+    .unwrap();
+    assert_eq!(listed(&["--sort", "id"]), vec![1, 2]);
+    assert_eq!(listed(&["--state", "closed"]), vec![2]);
+    assert_eq!(listed(&["-s", "open"]), vec![1]);
+}
+
+#[test]
+fn cli_issue_list_with_relative_adds_relative_times() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    skis()
+        .args(["issue", "create", "-t", "Crash on save"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let json = skis_json(&dir, &["issue", "list", "--json"]);
+    assert!(json[0].get("created_relative").is_none());
+    assert!(json[0].get("updated_relative").is_none());
+
+    let json = skis_json(&dir, &["issue", "list", "--json", "--with-relative"]);
+    assert_eq!(json[0]["created_relative"], "just now");
+    assert_eq!(json[0]["updated_relative"], "just now");
+    assert!(json[0]["created_at"].is_string());
+
+    let json = skis_json(
+        &dir,
+        &["issue", "list", "--json", "--with-relative", "--fields", "id"],
+    );
+    assert_eq!(json[0]["id"], 1);
+    assert_eq!(json[0]["created_relative"], "just now");
+    assert!(json[0].get("title").is_none());
+}
+
+#[test]
+fn cli_issue_close_matching_closes_filtered_issues() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["One", "Two", "Three"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["label", "create", "wontfix"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    for number in ["1", "3"] {
+        skis()
+            .args(["issue", "edit", number, "--add-label", "wontfix"])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+
+    skis()
+        .args(["issue", "close", "--matching", "-l", "wontfix"])
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+    skis()
+        .args(["issue", "close", "1", "-l", "wontfix", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+
+    skis()
+        .args(["issue", "close", "--matching", "-l", "wontfix", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Closed issue #1"))
+        .stdout(predicate::str::contains("Closed issue #3"));
+
+    skis()
+        .args(["issue", "list", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("2\n");
+
+    // Without --state, reopen only selects closed issues
+    let reopened = skis_json(&dir, &["issue", "reopen", "--matching", "--json"]);
+    let ids: Vec<i64> = reopened
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&1) && ids.contains(&3));
+}
+
+#[test]
+fn cli_issue_restore_matching_restores_deleted_issues() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    for title in ["Keep", "Drop"] {
+        skis()
+            .args(["issue", "create", "--title", title])
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    skis()
+        .args(["issue", "delete", "--matching", "--search", "Drop", "--yes"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted issue #2"));
+
+    skis()
+        .args(["issue", "restore", "--matching"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored issue #2"));
+}
+
+#[test]
+fn cli_export_filters_issues() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["label", "create", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "--title", "Plain"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    skis()
+        .args(["issue", "create", "--title", "Broken", "-l", "bug"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let data = skis_json(&dir, &["export", "--canonical", "-l", "bug"]);
+    let issues = data["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0]["id"], 2);
+    // Labels are exported whatever the filter
+    assert_eq!(data["labels"].as_array().unwrap().len(), 1);
+
+    let all = skis_json(&dir, &["export", "--canonical"]);
+    assert_eq!(all["issues"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn cli_issue_close_rejects_empty_comment() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+
+    skis()
+        .args(["issue", "create", "--title", "Keep open"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    for comment in ["", "   "] {
+        skis()
+            .args(["issue", "close", "1", "--comment", comment])
+            .current_dir(dir.path())
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Comment cannot be empty"));
+    }
+
+    skis()
+        .args(["issue", "list", "--ids"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout("1\n");
+}
+
+#[test]
+fn cli_comment_max_length_comes_from_config() {
+    let dir = TempDir::new().unwrap();
+    skis().arg("init").current_dir(dir.path()).assert().success();
+    std::fs::write(
+        dir.path().join(".skis/config.toml"),
+        "[body]\nmax_comment_length = 5\n",
+    )
+    .unwrap();
+
+    skis()
+        .args(["issue", "create", "--title", "Short comments"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    skis()
+        .args(["issue", "comment", "1", "-b", "Too long"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("maximum is 5"));
+    skis()
+        .args(["issue", "close", "1", "-c", "Too long"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("maximum is 5"));
+}